@@ -0,0 +1,154 @@
+//! Typed CPI builders (`cpi` feature) for the paths other programs call
+//! most: creating a launch, buying, and claiming.
+//!
+//! Each builder fills the instruction's `cpi::accounts` struct from the
+//! accounts every call needs and leaves the optional ones out. Set fields
+//! on the result to pass any of them, then hand it to the matching
+//! `diamondpad::cpi` function:
+//!
+//! ```ignore
+//! let accounts = diamondpad::builders::buy(buyer, protocol, /* ... */);
+//! diamondpad::cpi::buy(CpiContext::new(program, accounts), amount, min_out)?;
+//! ```
+
+use anchor_lang::prelude::*;
+
+use crate::cpi::accounts::{Buy, ClaimRewards, ClaimTokens, CreateLaunch};
+
+/// A launch with no mint, no creator stake and no review queue entry
+pub fn create_launch<'info>(
+    creator: AccountInfo<'info>,
+    protocol: AccountInfo<'info>,
+    launch: AccountInfo<'info>,
+    sol_vault: AccountInfo<'info>,
+    reward_vault: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> CreateLaunch<'info> {
+    CreateLaunch {
+        creator,
+        protocol,
+        launch,
+        sol_vault,
+        reward_vault,
+        creator_stake: None,
+        review_queue: None,
+        channel: None,
+        launch_mint: None,
+        launch_authority: None,
+        token_program: None,
+        system_program,
+    }
+}
+
+/// A curve buy with no gateway token, risk record, referral, season or
+/// priority-access accounts
+#[allow(clippy::too_many_arguments)]
+pub fn buy<'info>(
+    buyer: AccountInfo<'info>,
+    protocol: AccountInfo<'info>,
+    protocol_stats: AccountInfo<'info>,
+    launch: AccountInfo<'info>,
+    position: AccountInfo<'info>,
+    contribution: AccountInfo<'info>,
+    sol_vault: AccountInfo<'info>,
+    reward_vault: AccountInfo<'info>,
+    treasury: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Buy<'info> {
+    Buy {
+        buyer,
+        protocol,
+        protocol_stats,
+        launch,
+        position,
+        contribution,
+        sol_vault,
+        reward_vault,
+        treasury,
+        gateway_token: None,
+        wallet_risk: None,
+        referral_code: None,
+        referral_stats: None,
+        referral_board: None,
+        season: None,
+        season_score: None,
+        priority_launch: None,
+        priority_position: None,
+        holder_profile: None,
+        channel: None,
+        system_program,
+    }
+}
+
+/// A reward claim paid to the holder, with no LP position, referral code,
+/// contribution, profile or bundler stats. `bundler` is the holder's
+/// bundler PDA whether or not it exists; a flagged holder also needs
+/// `bundler_stats` set.
+#[allow(clippy::too_many_arguments)]
+pub fn claim_rewards<'info>(
+    holder: AccountInfo<'info>,
+    protocol: AccountInfo<'info>,
+    protocol_stats: AccountInfo<'info>,
+    launch: AccountInfo<'info>,
+    position: AccountInfo<'info>,
+    reward_vault: AccountInfo<'info>,
+    sol_vault: AccountInfo<'info>,
+    bundler: AccountInfo<'info>,
+    instructions_sysvar: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> ClaimRewards<'info> {
+    ClaimRewards {
+        destination: holder.clone(),
+        holder,
+        protocol,
+        protocol_stats,
+        launch,
+        position,
+        lp_position: None,
+        referral_code: None,
+        referral_stats: None,
+        reward_vault,
+        sol_vault,
+        contribution: None,
+        holder_profile: None,
+        bundler,
+        bundler_stats: None,
+        instructions_sysvar,
+        gas_pool: None,
+        treasury: None,
+        channel: None,
+        gateway_token: None,
+        wallet_risk: None,
+        priority_launch: None,
+        priority_position: None,
+        system_program,
+    }
+}
+
+/// A sale token claim into the contributor's associated token account
+#[allow(clippy::too_many_arguments)]
+pub fn claim_tokens<'info>(
+    contributor: AccountInfo<'info>,
+    launch: AccountInfo<'info>,
+    contribution: AccountInfo<'info>,
+    position: AccountInfo<'info>,
+    launch_mint: AccountInfo<'info>,
+    launch_authority: AccountInfo<'info>,
+    contributor_token_account: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    associated_token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> ClaimTokens<'info> {
+    ClaimTokens {
+        contributor,
+        launch,
+        contribution,
+        position,
+        launch_mint,
+        launch_authority,
+        contributor_token_account,
+        token_program,
+        associated_token_program,
+        system_program,
+    }
+}
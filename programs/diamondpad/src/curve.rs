@@ -0,0 +1,335 @@
+//! Bonding curve pricing.
+//!
+//! Each curve shape implements [`PricingCurve`] against the same
+//! [`CurveState`] snapshot, and [`CurveType`] (stored on the launch config)
+//! dispatches to the shape the creator picked. All quotes round in the
+//! curve's favour and return `None` on overflow.
+
+use anchor_lang::prelude::*;
+
+/// Prices are quoted in lamports per `PRICE_PRECISION` token base units
+pub const PRICE_PRECISION: u128 = 1_000_000;
+
+/// Fixed-point scale used for exponential growth factors
+const WAD: u128 = 1_000_000_000_000;
+
+/// Most price steps a single exponential trade may cross
+pub const MAX_CURVE_STEPS: u32 = 64;
+
+/// Largest per-step price increase an exponential curve may use
+pub const MAX_EXPONENTIAL_GROWTH_BPS: u16 = 1000;
+
+/// Curve inputs read from the launch at trade time
+pub struct CurveState {
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub tokens_sold: u64,
+}
+
+/// Shared pricing interface for every curve shape
+pub trait PricingCurve {
+    /// Tokens received for spending `quote_in` lamports
+    fn tokens_out(&self, state: &CurveState, quote_in: u64) -> Option<u64>;
+
+    /// Lamports received for selling `tokens_in` tokens
+    fn quote_out(&self, state: &CurveState, tokens_in: u64) -> Option<u64>;
+
+    /// Current marginal price in lamports per `PRICE_PRECISION` tokens
+    fn spot_price(&self, state: &CurveState) -> Option<u64>;
+}
+
+/// Price grows linearly with tokens sold: `base_price + slope * sold / PRICE_PRECISION`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct LinearCurve {
+    pub base_price: u64,
+    pub slope: u64,
+}
+
+/// Price is multiplied by `1 + growth_bps` every `step_size` tokens sold
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct ExponentialCurve {
+    pub base_price: u64,
+    pub growth_bps: u16,
+    pub step_size: u64,
+}
+
+/// `x * y = k` over the launch's virtual reserves
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ConstantProductCurve;
+
+// Unit structs serialize to nothing; the derive only handles named fields
+impl Space for ConstantProductCurve {
+    const INIT_SPACE: usize = 0;
+}
+
+/// Price-discovery shape selected by the creator
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum CurveType {
+    Linear(LinearCurve),
+    Exponential(ExponentialCurve),
+    ConstantProduct(ConstantProductCurve),
+}
+
+impl PricingCurve for CurveType {
+    fn tokens_out(&self, state: &CurveState, quote_in: u64) -> Option<u64> {
+        match self {
+            CurveType::Linear(curve) => curve.tokens_out(state, quote_in),
+            CurveType::Exponential(curve) => curve.tokens_out(state, quote_in),
+            CurveType::ConstantProduct(curve) => curve.tokens_out(state, quote_in),
+        }
+    }
+
+    fn quote_out(&self, state: &CurveState, tokens_in: u64) -> Option<u64> {
+        match self {
+            CurveType::Linear(curve) => curve.quote_out(state, tokens_in),
+            CurveType::Exponential(curve) => curve.quote_out(state, tokens_in),
+            CurveType::ConstantProduct(curve) => curve.quote_out(state, tokens_in),
+        }
+    }
+
+    fn spot_price(&self, state: &CurveState) -> Option<u64> {
+        match self {
+            CurveType::Linear(curve) => curve.spot_price(state),
+            CurveType::Exponential(curve) => curve.spot_price(state),
+            CurveType::ConstantProduct(curve) => curve.spot_price(state),
+        }
+    }
+}
+
+impl PricingCurve for LinearCurve {
+    fn tokens_out(&self, state: &CurveState, quote_in: u64) -> Option<u64> {
+        let base = self.base_price as u128;
+        let slope = self.slope as u128;
+        let sold = state.tokens_sold as u128;
+        let quote = quote_in as u128;
+
+        if slope == 0 {
+            return u64::try_from(quote.checked_mul(PRICE_PRECISION)? / base).ok();
+        }
+
+        // Solve slope*d^2 + b*d - 2*q*P^2 = 0 for d, with b = 2*(base*P + slope*sold)
+        let b = base
+            .checked_mul(PRICE_PRECISION)?
+            .checked_add(slope.checked_mul(sold)?)?
+            .checked_mul(2)?;
+        let c = quote
+            .checked_mul(PRICE_PRECISION)?
+            .checked_mul(PRICE_PRECISION)?
+            .checked_mul(8)?
+            .checked_mul(slope)?;
+        let root = isqrt(b.checked_mul(b)?.checked_add(c)?);
+        u64::try_from(root.checked_sub(b)? / slope.checked_mul(2)?).ok()
+    }
+
+    fn quote_out(&self, state: &CurveState, tokens_in: u64) -> Option<u64> {
+        let base = self.base_price as u128;
+        let slope = self.slope as u128;
+        let sold = state.tokens_sold as u128;
+        let delta = tokens_in as u128;
+        let start = sold.checked_sub(delta)?;
+
+        // Area under the price line between start and sold
+        let flat = base.checked_mul(delta)?;
+        let ramp = slope
+            .checked_mul(delta)?
+            .checked_mul(start.checked_add(sold)?)?
+            / (2 * PRICE_PRECISION);
+        u64::try_from(flat.checked_add(ramp)? / PRICE_PRECISION).ok()
+    }
+
+    fn spot_price(&self, state: &CurveState) -> Option<u64> {
+        let ramp = (self.slope as u128).checked_mul(state.tokens_sold as u128)? / PRICE_PRECISION;
+        u64::try_from((self.base_price as u128).checked_add(ramp)?).ok()
+    }
+}
+
+impl ExponentialCurve {
+    fn step_price(&self, step: u64) -> Option<u128> {
+        let factor = (10000 + self.growth_bps as u128).checked_mul(WAD)? / 10000;
+        (self.base_price as u128).checked_mul(pow_wad(factor, step)?)?.checked_div(WAD)
+    }
+}
+
+impl PricingCurve for ExponentialCurve {
+    fn tokens_out(&self, state: &CurveState, quote_in: u64) -> Option<u64> {
+        let step_size = self.step_size as u128;
+        let mut sold = state.tokens_sold as u128;
+        let mut remaining = quote_in as u128;
+        let mut bought: u128 = 0;
+
+        for _ in 0..MAX_CURVE_STEPS {
+            let price = self.step_price(u64::try_from(sold / step_size).ok()?)?;
+            let left_in_step = step_size - sold % step_size;
+            let step_cost = price.checked_mul(left_in_step)? / PRICE_PRECISION;
+
+            if remaining < step_cost {
+                bought = bought.checked_add(remaining.checked_mul(PRICE_PRECISION)? / price)?;
+                return u64::try_from(bought).ok();
+            }
+
+            remaining -= step_cost;
+            bought = bought.checked_add(left_in_step)?;
+            sold = sold.checked_add(left_in_step)?;
+        }
+
+        None
+    }
+
+    fn quote_out(&self, state: &CurveState, tokens_in: u64) -> Option<u64> {
+        let step_size = self.step_size as u128;
+        let mut sold = state.tokens_sold as u128;
+        let mut remaining = tokens_in as u128;
+        let mut quote: u128 = 0;
+
+        if remaining > sold {
+            return None;
+        }
+
+        for _ in 0..MAX_CURVE_STEPS {
+            if remaining == 0 {
+                return u64::try_from(quote).ok();
+            }
+
+            // Walk back down through the step the last token sold was in
+            let step = (sold - 1) / step_size;
+            let price = self.step_price(u64::try_from(step).ok()?)?;
+            let in_step = sold - step * step_size;
+            let take = remaining.min(in_step);
+
+            quote = quote.checked_add(price.checked_mul(take)? / PRICE_PRECISION)?;
+            remaining -= take;
+            sold -= take;
+        }
+
+        if remaining == 0 {
+            u64::try_from(quote).ok()
+        } else {
+            None
+        }
+    }
+
+    fn spot_price(&self, state: &CurveState) -> Option<u64> {
+        u64::try_from(self.step_price(state.tokens_sold / self.step_size)?).ok()
+    }
+}
+
+impl PricingCurve for ConstantProductCurve {
+    fn tokens_out(&self, state: &CurveState, quote_in: u64) -> Option<u64> {
+        let numerator = (state.virtual_token_reserves as u128).checked_mul(quote_in as u128)?;
+        let denominator = (state.virtual_sol_reserves as u128).checked_add(quote_in as u128)?;
+        u64::try_from(numerator / denominator).ok()
+    }
+
+    fn quote_out(&self, state: &CurveState, tokens_in: u64) -> Option<u64> {
+        let numerator = (state.virtual_sol_reserves as u128).checked_mul(tokens_in as u128)?;
+        let denominator = (state.virtual_token_reserves as u128).checked_add(tokens_in as u128)?;
+        u64::try_from(numerator / denominator).ok()
+    }
+
+    fn spot_price(&self, state: &CurveState) -> Option<u64> {
+        let numerator = (state.virtual_sol_reserves as u128).checked_mul(PRICE_PRECISION)?;
+        u64::try_from(numerator.checked_div(state.virtual_token_reserves as u128)?).ok()
+    }
+}
+
+/// `base^exp` for a WAD-scaled base, by repeated squaring
+fn pow_wad(mut base: u128, mut exp: u64) -> Option<u128> {
+    let mut result = WAD;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result.checked_mul(base)? / WAD;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = base.checked_mul(base)? / WAD;
+        }
+    }
+    Some(result)
+}
+
+/// Integer square root (floor)
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n / 2 + 1;
+    let mut y = (x + n / x) / 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curves() -> [CurveType; 3] {
+        [
+            CurveType::Linear(LinearCurve { base_price: 1_000, slope: 10 }),
+            CurveType::Exponential(ExponentialCurve {
+                base_price: 1_000,
+                growth_bps: 100,
+                step_size: 100_000_000_000,
+            }),
+            CurveType::ConstantProduct(ConstantProductCurve),
+        ]
+    }
+
+    fn fresh() -> CurveState {
+        CurveState {
+            virtual_sol_reserves: 30_000_000_000,
+            virtual_token_reserves: 1_073_000_000_000_000,
+            tokens_sold: 0,
+        }
+    }
+
+    /// The state after a buy, updated the way `buy` updates the launch
+    fn after_buy(state: &CurveState, quote_in: u64, tokens_out: u64) -> CurveState {
+        CurveState {
+            virtual_sol_reserves: state.virtual_sol_reserves + quote_in,
+            virtual_token_reserves: state.virtual_token_reserves - tokens_out,
+            tokens_sold: state.tokens_sold + tokens_out,
+        }
+    }
+
+    #[test]
+    fn zero_input_quotes_nothing() {
+        for curve in curves() {
+            let bought = curve.tokens_out(&fresh(), 1_000_000_000).unwrap();
+            let state = after_buy(&fresh(), 1_000_000_000, bought);
+            assert_eq!(curve.tokens_out(&state, 0), Some(0));
+            assert_eq!(curve.quote_out(&state, 0), Some(0));
+        }
+    }
+
+    #[test]
+    fn exhausted_reserves_quote_nothing_or_fail() {
+        // Nothing sold yet: there is nothing to sell back into
+        for curve in &curves()[..2] {
+            assert_eq!(curve.quote_out(&fresh(), 1), None);
+        }
+
+        let drained = CurveState { virtual_token_reserves: 0, ..fresh() };
+        let curve = CurveType::ConstantProduct(ConstantProductCurve);
+        assert_eq!(curve.tokens_out(&drained, 1_000_000_000), Some(0));
+        assert_eq!(curve.spot_price(&drained), None);
+    }
+
+    #[test]
+    fn buying_then_selling_never_returns_more_than_was_paid() {
+        for curve in curves() {
+            let mut last_tokens = 0;
+            let mut last_refund = 0;
+            for quote in [1, 1_000, 1_000_000, 100_000_000, 1_000_000_000] {
+                let tokens = curve.tokens_out(&fresh(), quote).unwrap();
+                let refund = curve.quote_out(&after_buy(&fresh(), quote, tokens), tokens).unwrap();
+
+                assert!(refund <= quote);
+                assert!(tokens >= last_tokens && refund >= last_refund);
+                (last_tokens, last_refund) = (tokens, refund);
+            }
+        }
+    }
+}
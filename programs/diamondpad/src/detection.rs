@@ -0,0 +1,107 @@
+//! Signed off-chain detection reports.
+//!
+//! The detector signs each report off-chain; the relaying transaction puts
+//! an Ed25519 program instruction (which checks the signature) directly
+//! before ours. We can't see the signature check's result, only that the
+//! transaction didn't fail, so we read that instruction back from the
+//! instructions sysvar and make sure it verified exactly our message under
+//! the detector's key.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::keccak::hashv;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+use crate::DiamondPadError;
+
+/// Domain separator so a detector signature can't be replayed as anything else
+const REPORT_DOMAIN: &[u8] = b"diamondpad:bundler_report";
+
+/// Ed25519 instruction header: signature count plus one padding byte
+const ED25519_HEADER_LEN: usize = 2;
+/// Per-signature offsets block in the Ed25519 instruction
+const ED25519_OFFSETS_LEN: usize = 14;
+
+/// The bytes a detector signs for a report. The evidence URI goes in as
+/// its keccak hash so the message stays a fixed size.
+pub fn report_message(
+    wallet: &Pubkey,
+    evidence_hash: &[u8; 32],
+    evidence_uri: &str,
+    reported_at: i64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(REPORT_DOMAIN.len() + 32 + 32 + 32 + 8);
+    message.extend_from_slice(REPORT_DOMAIN);
+    message.extend_from_slice(wallet.as_ref());
+    message.extend_from_slice(evidence_hash);
+    message.extend_from_slice(&hashv(&[evidence_uri.as_bytes()]).to_bytes());
+    message.extend_from_slice(&reported_at.to_le_bytes());
+    message
+}
+
+/// Identifies a signed report; its receipt PDA is seeded with this so the
+/// same report can only be relayed once
+pub fn report_hash(
+    wallet: &Pubkey,
+    evidence_hash: &[u8; 32],
+    evidence_uri: &str,
+    reported_at: i64,
+) -> [u8; 32] {
+    hashv(&[&report_message(wallet, evidence_hash, evidence_uri, reported_at)]).to_bytes()
+}
+
+/// Require the instruction before this one to be an Ed25519 check of
+/// `message` signed by `signer`, with all data inline in that instruction
+pub fn verify_signed_report(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current = load_current_index_checked(instructions_sysvar)?;
+    require!(current > 0, DiamondPadError::MissingReportSignature);
+    let ix = load_instruction_at_checked(current as usize - 1, instructions_sysvar)?;
+
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        DiamondPadError::MissingReportSignature
+    );
+
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_HEADER_LEN + ED25519_OFFSETS_LEN && data[0] == 1,
+        DiamondPadError::InvalidReportSignature
+    );
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let offsets = ED25519_HEADER_LEN;
+    let signature_ix = read_u16(offsets + 2);
+    let pubkey_offset = read_u16(offsets + 4) as usize;
+    let pubkey_ix = read_u16(offsets + 6);
+    let message_offset = read_u16(offsets + 8) as usize;
+    let message_len = read_u16(offsets + 10) as usize;
+    let message_ix = read_u16(offsets + 12);
+
+    // Everything must live in the Ed25519 instruction itself, or the check
+    // could have been pointed at bytes we never look at
+    require!(
+        signature_ix == u16::MAX && pubkey_ix == u16::MAX && message_ix == u16::MAX,
+        DiamondPadError::InvalidReportSignature
+    );
+
+    let signed_pubkey = data
+        .get(pubkey_offset..pubkey_offset + 32)
+        .ok_or(DiamondPadError::InvalidReportSignature)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_len)
+        .ok_or(DiamondPadError::InvalidReportSignature)?;
+
+    require!(
+        signed_pubkey == signer.as_ref() && signed_message == message,
+        DiamondPadError::InvalidReportSignature
+    );
+
+    Ok(())
+}
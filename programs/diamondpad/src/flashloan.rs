@@ -0,0 +1,34 @@
+//! Same-transaction buy detection.
+//!
+//! Rewards and sells are priced off a position's state when the instruction
+//! runs, so a transaction that borrows, buys, claims or sells, and repays
+//! in one go never carries any real risk. We can't see a lender, but we can
+//! see the rest of the transaction in the instructions sysvar: if it also
+//! buys on the same launch, the claim or sell is refused.
+//!
+//! Only buys addressed to this program at the top level are visible; a buy
+//! made through a CPI shows up as the calling program's instruction.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+use anchor_lang::Discriminator;
+
+use crate::DiamondPadError;
+
+/// Fail if any instruction in the transaction is a `buy` on `launch`
+pub fn require_no_buy_in_transaction(
+    instructions_sysvar: &AccountInfo,
+    launch: &Pubkey,
+) -> Result<()> {
+    let mut index = 0;
+    while let Ok(ix) = load_instruction_at_checked(index, instructions_sysvar) {
+        let is_buy = ix.program_id == crate::ID
+            && ix.data.get(..8) == Some(&crate::instruction::Buy::DISCRIMINATOR[..]);
+        require!(
+            !(is_buy && ix.accounts.iter().any(|meta| meta.pubkey == *launch)),
+            DiamondPadError::BuyInSameTransaction
+        );
+        index += 1;
+    }
+    Ok(())
+}
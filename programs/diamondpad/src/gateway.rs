@@ -0,0 +1,68 @@
+//! Civic gateway pass verification.
+//!
+//! The gateway program has no "verify" instruction to CPI into; passes are
+//! accounts it owns, so we check the owner program and read the pass state
+//! directly (as the `solana-gateway` client does). No PII is involved.
+
+use anchor_lang::prelude::*;
+
+use crate::DiamondPadError;
+
+/// Civic gateway program
+pub mod gateway_program {
+    anchor_lang::declare_id!("gatem74V238djXdzWnJf94Wo1DcnuGkfijbf3AuBhfs");
+}
+
+#[derive(AnchorDeserialize)]
+enum GatewayTokenState {
+    Active,
+    Frozen,
+    Revoked,
+}
+
+#[derive(AnchorDeserialize)]
+struct GatewayToken {
+    _features: u8,
+    _parent_gateway_token: Option<Pubkey>,
+    owner_wallet: Pubkey,
+    _owner_identity: Option<Pubkey>,
+    gatekeeper_network: Pubkey,
+    _issuing_gatekeeper: Pubkey,
+    state: GatewayTokenState,
+    expire_time: Option<i64>,
+}
+
+/// Require `gateway_token` to be an active, unexpired pass for `wallet`
+/// issued on `gatekeeper_network`
+pub fn verify_gateway_token(
+    gateway_token: &AccountInfo,
+    wallet: &Pubkey,
+    gatekeeper_network: &Pubkey,
+    now: i64,
+) -> Result<()> {
+    require_keys_eq!(
+        *gateway_token.owner,
+        gateway_program::ID,
+        DiamondPadError::InvalidGatewayToken
+    );
+
+    let data = gateway_token.try_borrow_data()?;
+    let token = GatewayToken::deserialize(&mut &data[..])
+        .map_err(|_| error!(DiamondPadError::InvalidGatewayToken))?;
+
+    require_keys_eq!(token.owner_wallet, *wallet, DiamondPadError::InvalidGatewayToken);
+    require_keys_eq!(
+        token.gatekeeper_network,
+        *gatekeeper_network,
+        DiamondPadError::InvalidGatewayToken
+    );
+    require!(
+        matches!(token.state, GatewayTokenState::Active),
+        DiamondPadError::GatewayTokenInactive
+    );
+    if let Some(expire_time) = token.expire_time {
+        require!(now < expire_time, DiamondPadError::GatewayTokenInactive);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+//! Jupiter swap CPI.
+//!
+//! Routes are built off-chain by the Jupiter API. The client passes the
+//! route instruction's data and accounts (as remaining accounts) and we
+//! forward them unchanged with no signer seeds of our own, so the route
+//! can only move what the signing holder could move themselves. Callers
+//! check the output balance afterwards rather than trusting the route.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+
+/// Jupiter aggregator v6
+pub mod jupiter_program {
+    anchor_lang::declare_id!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+}
+
+/// Invoke a Jupiter route instruction over `route_accounts`
+pub fn swap<'info>(
+    jupiter: &AccountInfo<'info>,
+    route_accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+) -> Result<()> {
+    let accounts = route_accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: *account.key,
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        })
+        .collect();
+    let ix = Instruction {
+        program_id: jupiter_program::ID,
+        accounts,
+        data,
+    };
+
+    let mut infos = route_accounts.to_vec();
+    infos.push(jupiter.clone());
+    invoke(&ix, &infos)?;
+    Ok(())
+}
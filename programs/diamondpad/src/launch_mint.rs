@@ -0,0 +1,202 @@
+//! Token-2022 launch mints.
+//!
+//! A launch can be created with its own Token-2022 mint at
+//! `["launch_mint", launch]`. Every authority on the mint is the launch
+//! authority PDA (`["launch_authority", launch]`), so nothing about the
+//! mint can be changed except through this program. Extensions have to be
+//! initialized before the mint itself, so the account is sized and created
+//! by hand rather than with an `init` constraint.
+//!
+//! Name, symbol and URI live on the mint through the metadata-pointer and
+//! token-metadata extensions, so there's no separate metadata program. The
+//! metadata has no update authority once written.
+//!
+//! A launch that freezes transfers until graduation also gets the
+//! default-account-state extension set to frozen, with the launch authority
+//! as freeze authority, so no token account can move tokens until
+//! `thaw_launch_transfers` runs after graduation.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::{AccountState, Mint};
+use anchor_spl::token_2022::{self, InitializeMint2};
+use anchor_spl::token_interface::spl_pod::optional_keys::OptionalNonZeroPubkey;
+use anchor_spl::token_interface::spl_token_metadata_interface::state::TokenMetadata;
+use anchor_spl::token_interface::{
+    default_account_state_initialize, interest_bearing_mint_initialize,
+    metadata_pointer_initialize, token_metadata_initialize, token_metadata_update_authority,
+    transfer_fee_initialize, DefaultAccountStateInitialize, InterestBearingMintInitialize,
+    MetadataPointerInitialize, TokenMetadataInitialize, TokenMetadataUpdateAuthority,
+    TransferFeeInitialize,
+};
+
+use crate::{Launch, LaunchConfig};
+
+/// Decimals of every launch mint
+pub const LAUNCH_TOKEN_DECIMALS: u8 = 6;
+
+/// Fixed-size extensions a launch mint needs for its config. Token metadata
+/// is variable-length and is added on top by the token program itself.
+fn mint_extensions(config: &LaunchConfig) -> Vec<ExtensionType> {
+    let mut extensions = vec![ExtensionType::MetadataPointer];
+    if config.transfer_fee_bps > 0 {
+        extensions.push(ExtensionType::TransferFeeConfig);
+    }
+    if config.interest_rate_bps > 0 {
+        extensions.push(ExtensionType::InterestBearingConfig);
+    }
+    if config.freeze_until_graduation {
+        extensions.push(ExtensionType::DefaultAccountState);
+    }
+    extensions
+}
+
+/// Create and initialize the launch mint. The transfer fee has no config
+/// authority, so the creator's fee is fixed for the life of the mint; only
+/// the launch authority can withdraw what it withholds. The interest rate
+/// authority is also the launch authority, which only moves the rate on
+/// the protocol authority's instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn create_launch_mint<'info>(
+    payer: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    launch_authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    launch: &Launch,
+    uri: String,
+    mint_seeds: &[&[u8]],
+    authority_seeds: &[&[u8]],
+) -> Result<()> {
+    let config = &launch.config;
+    let extensions = mint_extensions(config);
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&extensions)?;
+
+    // Fund the metadata up front; the token program reallocs into it
+    let metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey::try_from(Some(*launch_authority.key))?,
+        mint: *mint.key,
+        name: launch.name(),
+        symbol: launch.symbol(),
+        uri: uri.clone(),
+        additional_metadata: vec![],
+    };
+    let funded_space = space + metadata.tlv_size_of()?;
+
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program,
+            system_program::CreateAccount { from: payer, to: mint.clone() },
+            &[mint_seeds],
+        ),
+        Rent::get()?.minimum_balance(funded_space),
+        space as u64,
+        token_program.key,
+    )?;
+
+    metadata_pointer_initialize(
+        CpiContext::new(
+            token_program.clone(),
+            MetadataPointerInitialize {
+                token_program_id: token_program.clone(),
+                mint: mint.clone(),
+            },
+        ),
+        None,
+        Some(*mint.key),
+    )?;
+
+    if config.transfer_fee_bps > 0 {
+        transfer_fee_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                TransferFeeInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            None,
+            Some(launch_authority.key),
+            config.transfer_fee_bps,
+            config.max_transfer_fee,
+        )?;
+    }
+
+    if config.interest_rate_bps > 0 {
+        interest_bearing_mint_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                InterestBearingMintInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            Some(*launch_authority.key),
+            config.interest_rate_bps,
+        )?;
+    }
+
+    if config.freeze_until_graduation {
+        default_account_state_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                DefaultAccountStateInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            &AccountState::Frozen,
+        )?;
+    }
+
+    token_2022::initialize_mint2(
+        CpiContext::new(token_program.clone(), InitializeMint2 { mint: mint.clone() }),
+        LAUNCH_TOKEN_DECIMALS,
+        launch_authority.key,
+        config.freeze_until_graduation.then_some(launch_authority.key),
+    )?;
+
+    token_metadata_initialize(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            TokenMetadataInitialize {
+                token_program_id: token_program.clone(),
+                metadata: mint.clone(),
+                update_authority: launch_authority.clone(),
+                mint_authority: launch_authority.clone(),
+                mint: mint.clone(),
+            },
+            &[authority_seeds],
+        ),
+        metadata.name,
+        metadata.symbol,
+        uri,
+    )?;
+
+    // Freeze the metadata as written
+    token_metadata_update_authority(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            TokenMetadataUpdateAuthority {
+                token_program_id: token_program,
+                metadata: mint,
+                current_authority: launch_authority.clone(),
+                new_authority: launch_authority,
+            },
+            &[authority_seeds],
+        ),
+        OptionalNonZeroPubkey::default(),
+    )
+}
+
+/// Whether a launch mint carries token metadata with a name and URI
+pub fn has_metadata(mint: &AccountInfo) -> Result<bool> {
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+    Ok(mint
+        .get_variable_len_extension::<TokenMetadata>()
+        .is_ok_and(|metadata| !metadata.name.is_empty() && !metadata.uri.is_empty()))
+}
@@ -1,7 +1,49 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint};
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token_2022::spl_token_2022::state::AccountState;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface;
 
-declare_id!("DiamPad1111111111111111111111111111111111");
+#[cfg(feature = "cpi")]
+pub mod builders;
+pub mod curve;
+pub mod detection;
+pub mod flashloan;
+pub mod gateway;
+pub mod jupiter;
+pub mod launch_mint;
+pub mod merkle;
+pub mod pda;
+pub mod quality;
+#[cfg(feature = "client")]
+pub mod replay;
+pub mod routing;
+pub mod supply;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod twap;
+pub mod validation;
+
+use curve::{CurveState, CurveType, PricingCurve, PRICE_PRECISION};
+use quality::{QualityInputs, QualityScore};
+use supply::{SupplyBucket, SupplyLedger};
+use twap::PriceHistory;
+use validation::{
+    validate_launch_config, validate_launch_params, validate_rank_thresholds, validate_username,
+};
+
+// Program ID per cluster deployment (see [programs.*] in Anchor.toml).
+// Crates using this one as a CPI library pick theirs with a feature;
+// localnet is the default.
+#[cfg(feature = "mainnet")]
+declare_id!("DiamondPadMainnet11111111111111111111111111");
+#[cfg(all(feature = "devnet", not(feature = "mainnet")))]
+declare_id!("DiamondPadDevnet111111111111111111111111111");
+#[cfg(not(any(feature = "mainnet", feature = "devnet")))]
+declare_id!("DiamondPad111111111111111111111111111111111");
 
 /// DiamondPad - The launchpad for believers
 /// 
@@ -22,13 +64,302 @@ pub mod diamondpad {
     pub fn initialize(ctx: Context<Initialize>, launch_token_mint: Pubkey) -> Result<()> {
         let protocol = &mut ctx.accounts.protocol;
         protocol.authority = ctx.accounts.authority.key();
+        protocol.moderator = ctx.accounts.authority.key();
+        protocol.verifier = ctx.accounts.authority.key();
+        protocol.reviewer = ctx.accounts.authority.key();
+        protocol.detector = Pubkey::default(); // no signed detection feed until delegated
+        protocol.risk_oracle = ctx.accounts.authority.key();
+        protocol.treasury = ctx.accounts.authority.key();
         protocol.launch_token_mint = launch_token_mint;
         protocol.total_launches = 0;
         protocol.total_stakers = 0;
         protocol.total_staked = 0;
         protocol.total_bundlers_caught = 0;
         protocol.early_unstake_penalty_bps = 1000; // 10%
+        protocol.trade_fee_bps = 100; // 1%
+        protocol.curated_mode = false;
+        protocol.flag_threshold = 1;
+        protocol.max_sale_extension_days = 14;
+        protocol.program_immutable = false; // until verify_program_immutability says otherwise
         protocol.bump = ctx.bumps.protocol;
+
+        ctx.accounts.protocol_stats.bump = ctx.bumps.protocol_stats;
+        Ok(())
+    }
+
+    /// Create the protocol stats account for a deployment initialized before
+    /// it existed. Anyone can pay for it; calling it again changes nothing.
+    pub fn init_protocol_stats(ctx: Context<InitProtocolStats>) -> Result<()> {
+        ctx.accounts.protocol_stats.bump = ctx.bumps.protocol_stats;
+        Ok(())
+    }
+
+    /// Hand a delegated protocol role to a different key
+    pub fn set_protocol_role(
+        ctx: Context<SetProtocolRole>,
+        role: ProtocolRole,
+        key: Pubkey,
+    ) -> Result<()> {
+        let protocol = &mut ctx.accounts.protocol;
+        let slot = match role {
+            ProtocolRole::Moderator => &mut protocol.moderator,
+            ProtocolRole::Verifier => &mut protocol.verifier,
+            ProtocolRole::Reviewer => &mut protocol.reviewer,
+            ProtocolRole::Detector => &mut protocol.detector,
+            ProtocolRole::RiskOracle => &mut protocol.risk_oracle,
+            ProtocolRole::Adjudicator => &mut protocol.adjudicator,
+        };
+        let previous = *slot;
+        *slot = key;
+
+        emit!(ProtocolRoleUpdated {
+            role,
+            previous,
+            new: key,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SetProtocolRole(role),
+            protocol.key(),
+            AuditValue::Key(previous),
+            AuditValue::Key(key),
+        )?;
+
+        Ok(())
+    }
+
+    /// Set how many distinct flaggers must approve before a bundler flag is enforced
+    pub fn set_flag_threshold(ctx: Context<SetProtocolRole>, threshold: u8) -> Result<()> {
+        require!(
+            threshold >= 1 && threshold as usize <= MAX_FLAG_APPROVALS,
+            DiamondPadError::InvalidFlagThreshold
+        );
+
+        let protocol = &mut ctx.accounts.protocol;
+        let previous = protocol.flag_threshold;
+        protocol.flag_threshold = threshold;
+
+        emit!(FlagThresholdUpdated {
+            previous,
+            new: threshold,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SetFlagThreshold,
+            protocol.key(),
+            AuditValue::Amount(previous as u64),
+            AuditValue::Amount(threshold as u64),
+        )?;
+
+        Ok(())
+    }
+
+    /// Cap how far a creator may push back their sale deadline
+    pub fn set_max_sale_extension(ctx: Context<SetProtocolRole>, days: u16) -> Result<()> {
+        require!(days <= MAX_SALE_DURATION_DAYS, DiamondPadError::InvalidSaleExtension);
+
+        let protocol = &mut ctx.accounts.protocol;
+        let previous = protocol.max_sale_extension_days;
+        protocol.max_sale_extension_days = days;
+
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SetMaxSaleExtension,
+            protocol.key(),
+            AuditValue::Amount(previous as u64),
+            AuditValue::Amount(days as u64),
+        )
+
+    }
+
+    /// Cap how many buys, sells and recorded trades one position can make
+    /// per minute (0 = no limit)
+    pub fn set_position_rate_limit(ctx: Context<SetProtocolRole>, max_per_minute: u16) -> Result<()> {
+        let protocol = &mut ctx.accounts.protocol;
+        let previous = protocol.max_position_actions_per_minute;
+        protocol.max_position_actions_per_minute = max_per_minute;
+
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SetPositionRateLimit,
+            protocol.key(),
+            AuditValue::Amount(previous as u64),
+            AuditValue::Amount(max_per_minute as u64),
+        )
+    }
+
+    /// Set the buy fee discount for each proven rank, Paper to Diamond
+    pub fn set_loyalty_discounts(ctx: Context<SetProtocolRole>, schedule: [u16; 6]) -> Result<()> {
+        require!(
+            schedule.iter().all(|&discount| discount <= MAX_LOYALTY_DISCOUNT_BPS),
+            DiamondPadError::InvalidLoyaltyDiscount
+        );
+
+        let protocol = &mut ctx.accounts.protocol;
+        let previous = protocol.loyalty_discount_bps;
+        protocol.loyalty_discount_bps = schedule;
+
+        let top = DiamondRank::Diamond as usize;
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SetLoyaltyDiscounts,
+            protocol.key(),
+            AuditValue::Amount(previous[top] as u64),
+            AuditValue::Amount(schedule[top] as u64),
+        )
+    }
+
+    /// Set the SOL deposit creators escrow with each new launch, and the
+    /// share of it returned when a launch with real activity fails
+    pub fn set_listing_deposit(
+        ctx: Context<SetProtocolRole>,
+        amount: u64,
+        failure_refund_bps: u16,
+    ) -> Result<()> {
+        require!(failure_refund_bps <= 10000, DiamondPadError::InvalidAmount);
+
+        let protocol = &mut ctx.accounts.protocol;
+        let previous = protocol.listing_deposit;
+        protocol.listing_deposit = amount;
+        protocol.listing_deposit_failure_refund_bps = failure_refund_bps;
+
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SetListingDeposit,
+            protocol.key(),
+            AuditValue::Amount(previous),
+            AuditValue::Amount(amount),
+        )
+    }
+
+    /// Configure `gc_position`: positions below `dust_threshold` tokens and
+    /// untouched for `inactivity_days` can be closed by anyone, who keeps
+    /// `cranker_share_bps` of the rent (0 threshold = off)
+    pub fn set_position_gc(
+        ctx: Context<SetProtocolRole>,
+        dust_threshold: u64,
+        inactivity_days: u16,
+        cranker_share_bps: u16,
+    ) -> Result<()> {
+        require!(cranker_share_bps <= 10000, DiamondPadError::InvalidAmount);
+
+        let protocol = &mut ctx.accounts.protocol;
+        let previous = protocol.gc_dust_threshold;
+        protocol.gc_dust_threshold = dust_threshold;
+        protocol.gc_inactivity_days = inactivity_days;
+        protocol.gc_cranker_share_bps = cranker_share_bps;
+
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SetPositionGc,
+            protocol.key(),
+            AuditValue::Amount(previous),
+            AuditValue::Amount(dust_threshold),
+        )
+    }
+
+    /// Set when legacy event shapes stop being emitted, giving indexers a
+    /// deprecation window to move to the current schemas
+    pub fn set_legacy_event_window(ctx: Context<SetProtocolRole>, until: i64) -> Result<()> {
+        let protocol = &mut ctx.accounts.protocol;
+        let previous = protocol.legacy_events_until;
+        protocol.legacy_events_until = until;
+
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SetLegacyEventWindow,
+            protocol.key(),
+            AuditValue::Timestamp(previous),
+            AuditValue::Timestamp(until),
+        )
+    }
+
+    /// Cap the lamports paid out in holder rewards across all launches per
+    /// UTC day (0 = uncapped), as a backstop against an accrual bug
+    /// draining every reward vault at once
+    pub fn set_daily_reward_cap(ctx: Context<SetProtocolRole>, cap: u64) -> Result<()> {
+        let protocol = &mut ctx.accounts.protocol;
+        let previous = protocol.daily_reward_cap;
+        protocol.daily_reward_cap = cap;
+
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SetDailyRewardCap,
+            protocol.key(),
+            AuditValue::Amount(previous),
+            AuditValue::Amount(cap),
+        )
+    }
+
+    /// Switch to the feature flags a governance proposal scheduled, once
+    /// their timelock has passed. Permissionless.
+    pub fn apply_feature_flags(ctx: Context<ApplyFeatureFlags>) -> Result<()> {
+        let protocol = &mut ctx.accounts.protocol;
+
+        require!(
+            protocol.feature_flags_effective_at != 0,
+            DiamondPadError::NoPendingFeatureFlags
+        );
+        require!(
+            Clock::get()?.unix_timestamp >= protocol.feature_flags_effective_at,
+            DiamondPadError::FeatureFlagsTimelocked
+        );
+
+        let previous = protocol.disabled_features;
+        protocol.disabled_features = protocol.pending_disabled_features;
+        protocol.pending_disabled_features = 0;
+        protocol.feature_flags_effective_at = 0;
+
+        audit_admin_action(
+            ctx.accounts.cranker.key(),
+            AdminAction::ApplyFeatureFlags,
+            protocol.key(),
+            AuditValue::Amount(previous as u64),
+            AuditValue::Amount(protocol.disabled_features as u64),
+        )
+    }
+
+    /// Require reviewer approval before new launches can open for trading
+    pub fn set_curated_mode(ctx: Context<SetCuratedMode>, enabled: bool) -> Result<()> {
+        let protocol = &mut ctx.accounts.protocol;
+        let previous = protocol.curated_mode;
+        protocol.curated_mode = enabled;
+        ctx.accounts.review_queue.bump = ctx.bumps.review_queue;
+
+        emit!(CuratedModeUpdated {
+            enabled,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SetCuratedMode,
+            protocol.key(),
+            AuditValue::Bool(previous),
+            AuditValue::Bool(enabled),
+        )?;
+
+        Ok(())
+    }
+
+    /// Permissionless: read the program's upgrade authority from its
+    /// ProgramData account and record on the Protocol whether the deployed
+    /// code can still change
+    pub fn verify_program_immutability(ctx: Context<VerifyProgramImmutability>) -> Result<()> {
+        let upgrade_authority = ctx.accounts.program_data.upgrade_authority_address;
+        let protocol = &mut ctx.accounts.protocol;
+
+        protocol.program_immutable = upgrade_authority.is_none();
+        protocol.program_verified_at = Clock::get()?.unix_timestamp;
+
+        emit!(ProgramImmutabilityVerified {
+            immutable: protocol.program_immutable,
+            upgrade_authority,
+            verified_at: protocol.program_verified_at,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
         Ok(())
     }
 
@@ -82,6 +413,7 @@ pub mod diamondpad {
             lock_days,
             tier,
             total_staked: staker.staked_amount,
+            schema_version: EVENT_SCHEMA_VERSION,
         });
 
         Ok(())
@@ -150,762 +482,14700 @@ pub mod diamondpad {
             early: clock.unix_timestamp < staker.lock_end_timestamp,
             remaining_stake: staker.staked_amount,
             new_tier: staker.tier,
+            schema_version: EVENT_SCHEMA_VERSION,
         });
 
         Ok(())
     }
 
-    // ============ Launches ============
-
-    /// Create a new token launch with enforced safety settings
-    pub fn create_launch(
-        ctx: Context<CreateLaunch>,
-        name: String,
-        symbol: String,
-        total_supply: u64,
-        dev_allocation_bps: u16,
-        dev_vesting_days: u16,
-        lp_lock_days: u16,
-        holder_rewards_bps: u16,
-    ) -> Result<()> {
-        require!(dev_allocation_bps <= 1000, DiamondPadError::DevAllocationTooHigh);
-        require!(dev_vesting_days >= 180, DiamondPadError::VestingTooShort);
-        require!(lp_lock_days >= 365, DiamondPadError::LpLockTooShort);
-        require!(name.len() <= 32, DiamondPadError::NameTooLong);
-        require!(symbol.len() <= 10, DiamondPadError::SymbolTooLong);
+    // ============ Emission Gauges ============
 
-        let launch = &mut ctx.accounts.launch;
+    /// Set the lamports the emissions vault pays out per gauge epoch. Takes
+    /// effect from the next epoch to receive its first vote.
+    pub fn set_gauge_emissions(ctx: Context<SetProtocolRole>, lamports_per_epoch: u64) -> Result<()> {
         let protocol = &mut ctx.accounts.protocol;
-        
-        launch.creator = ctx.accounts.creator.key();
-        launch.name = name.clone();
-        launch.symbol = symbol.clone();
-        launch.total_supply = total_supply;
-        launch.dev_allocation_bps = dev_allocation_bps;
-        launch.dev_vesting_days = dev_vesting_days;
-        launch.lp_lock_days = lp_lock_days;
-        launch.holder_rewards_bps = holder_rewards_bps;
-        launch.created_at = Clock::get()?.unix_timestamp;
-        launch.launch_id = protocol.total_launches;
-        launch.status = LaunchStatus::Pending;
-        launch.total_raised = 0;
-        launch.holder_count = 0;
-        
-        // Allocation pools (in basis points of total supply)
-        launch.guaranteed_pool_bps = 3000;      // 30%
-        launch.lottery_pool_bps = 2500;         // 25%
-        launch.public_pool_bps = 1000;          // 10%
-        launch.fcfs_pool_bps = 500;             // 5%
-        launch.flipper_pool_bps = 500;          // 5%
-        launch.liquidity_pool_bps = 1500;       // 15%
-        launch.trader_rewards_pool_bps = 1000;  // 10%
-        
-        launch.bump = ctx.bumps.launch;
-
-        protocol.total_launches += 1;
-
-        emit!(LaunchCreated {
-            launch_id: launch.launch_id,
-            creator: launch.creator,
-            name,
-            symbol,
-            total_supply,
-            dev_allocation_bps,
-            dev_vesting_days,
-        });
+        let previous = protocol.gauge_emissions_per_epoch;
+        protocol.gauge_emissions_per_epoch = lamports_per_epoch;
 
-        Ok(())
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SetGaugeEmissions,
+            protocol.key(),
+            AuditValue::Amount(previous),
+            AuditValue::Amount(lamports_per_epoch),
+        )
     }
 
-    /// Request allocation for a launch
-    pub fn request_allocation(
-        ctx: Context<RequestAllocation>,
-        pool: AllocationPool,
-        amount_usd: u64,
-    ) -> Result<()> {
-        let allocation = &mut ctx.accounts.allocation;
+    /// Put the caller's full stake behind one launch for the current gauge
+    /// epoch. Only stake locked through the end of the epoch counts, so the
+    /// same tokens can't be unstaked and voted again from another wallet
+    /// without paying the early-unstake penalty.
+    pub fn vote_gauge(ctx: Context<VoteGauge>, epoch: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            epoch == (now / GAUGE_EPOCH_SECONDS) as u64,
+            DiamondPadError::NotCurrentGaugeEpoch
+        );
+        let epoch_end = (epoch as i64 + 1) * GAUGE_EPOCH_SECONDS;
+
         let staker = &ctx.accounts.staker_account;
+        require!(staker.staked_amount > 0, DiamondPadError::InsufficientStake);
+        require!(
+            staker.lock_end_timestamp >= epoch_end,
+            DiamondPadError::StakeUnlocksBeforeEpochEnd
+        );
+
         let launch = &ctx.accounts.launch;
-        let clock = Clock::get()?;
-        
-        // Validate pool access based on tier
-        match pool {
-            AllocationPool::Guaranteed => {
-                require!(
-                    staker.tier == StakingTier::Diamond || staker.tier == StakingTier::Gold,
-                    DiamondPadError::TierTooLow
-                );
-            },
-            AllocationPool::WeightedLottery => {
-                require!(
-                    staker.tier != StakingTier::Public,
-                    DiamondPadError::StakingRequired
-                );
-            },
-            _ => {} // Public pools open to all
-        }
-        
-        // Calculate weight based on tier
-        let weight = get_tier_weight(staker.tier);
-        
-        allocation.owner = ctx.accounts.requester.key();
-        allocation.launch = launch.key();
-        allocation.pool = pool;
-        allocation.requested_amount_usd = amount_usd;
-        allocation.weight = weight;
-        allocation.status = AllocationStatus::Pending;
-        allocation.requested_at = clock.unix_timestamp;
-        allocation.bump = ctx.bumps.allocation;
+        require!(
+            matches!(launch.status, LaunchStatus::Active | LaunchStatus::Graduated),
+            DiamondPadError::LaunchNotOpen
+        );
 
-        emit!(AllocationRequested {
-            owner: allocation.owner,
-            launch_id: launch.launch_id,
-            pool,
-            amount_usd,
-            weight,
-        });
+        let gauge_epoch = &mut ctx.accounts.gauge_epoch;
+        if gauge_epoch.total_votes == 0 {
+            gauge_epoch.epoch = epoch;
+            gauge_epoch.emissions = ctx.accounts.protocol.gauge_emissions_per_epoch;
+            gauge_epoch.bump = ctx.bumps.gauge_epoch;
+        }
+        gauge_epoch.total_votes = gauge_epoch.total_votes.checked_add(staker.staked_amount).unwrap();
 
-        Ok(())
-    }
+        let launch_gauge = &mut ctx.accounts.launch_gauge;
+        if launch_gauge.votes == 0 {
+            launch_gauge.launch = launch.key();
+            launch_gauge.epoch = epoch;
+            launch_gauge.bump = ctx.bumps.launch_gauge;
+        }
+        launch_gauge.votes = launch_gauge.votes.checked_add(staker.staked_amount).unwrap();
 
-    /// Fulfill allocation (called by protocol after lottery/distribution)
-    pub fn fulfill_allocation(
-        ctx: Context<FulfillAllocation>,
-        allocated_tokens: u64,
-        vesting_cliff_days: u16,
-        vesting_duration_days: u16,
-        tge_unlock_bps: u16,
-    ) -> Result<()> {
-        let allocation = &mut ctx.accounts.allocation;
-        let clock = Clock::get()?;
-        
-        require!(
-            ctx.accounts.authority.key() == ctx.accounts.protocol.authority,
-            DiamondPadError::Unauthorized
-        );
-        
-        allocation.allocated_tokens = allocated_tokens;
-        allocation.vesting_start = clock.unix_timestamp;
-        allocation.vesting_cliff_days = vesting_cliff_days;
-        allocation.vesting_duration_days = vesting_duration_days;
-        allocation.tge_unlock_bps = tge_unlock_bps;
-        allocation.tokens_claimed = 0;
-        allocation.status = if allocated_tokens > 0 {
-            AllocationStatus::Won
-        } else {
-            AllocationStatus::Lost
-        };
+        let vote = &mut ctx.accounts.gauge_vote;
+        vote.voter = staker.owner;
+        vote.epoch = epoch;
+        vote.launch = launch.key();
+        vote.weight = staker.staked_amount;
+        vote.bump = ctx.bumps.gauge_vote;
 
-        emit!(AllocationFulfilled {
-            owner: allocation.owner,
-            launch: allocation.launch,
-            allocated_tokens,
-            status: allocation.status,
+        emit!(GaugeVoted {
+            voter: vote.voter,
+            launch: vote.launch,
+            epoch,
+            weight: vote.weight,
+            launch_votes: launch_gauge.votes,
+            total_votes: gauge_epoch.total_votes,
+            schema_version: EVENT_SCHEMA_VERSION,
         });
 
         Ok(())
     }
 
-    /// Claim vested tokens from allocation
-    pub fn claim_allocation(ctx: Context<ClaimAllocation>) -> Result<()> {
-        let allocation = &mut ctx.accounts.allocation;
-        let clock = Clock::get()?;
-        
+    /// Pay a launch its share of an ended epoch's emissions into its reward
+    /// vault, where they're paid out to holders like trade-fee rewards.
+    /// Permissionless; each launch gauge pays once. If the emissions vault
+    /// is short, the launch gets what's left.
+    pub fn distribute_gauge_emissions(ctx: Context<DistributeGaugeEmissions>, epoch: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
         require!(
-            allocation.status == AllocationStatus::Won,
-            DiamondPadError::NoAllocation
+            now >= (epoch as i64 + 1) * GAUGE_EPOCH_SECONDS,
+            DiamondPadError::GaugeEpochNotEnded
         );
-        
-        // Calculate claimable amount based on vesting
-        let claimable = calculate_vested_amount(
-            allocation.allocated_tokens,
-            allocation.vesting_start,
-            allocation.vesting_cliff_days,
-            allocation.vesting_duration_days,
-            allocation.tge_unlock_bps,
-            clock.unix_timestamp,
-        ).checked_sub(allocation.tokens_claimed).unwrap_or(0);
-        
-        require!(claimable > 0, DiamondPadError::NothingToClaim);
-        
-        allocation.tokens_claimed = allocation.tokens_claimed.checked_add(claimable).unwrap();
-        
-        // Token transfer would happen here via CPI
-        
-        emit!(AllocationClaimed {
-            owner: allocation.owner,
-            launch: allocation.launch,
-            claimed: claimable,
-            total_claimed: allocation.tokens_claimed,
-            remaining: allocation.allocated_tokens.checked_sub(allocation.tokens_claimed).unwrap(),
-        });
 
-        Ok(())
-    }
+        let launch_gauge = &mut ctx.accounts.launch_gauge;
+        require!(!launch_gauge.distributed, DiamondPadError::GaugeAlreadyDistributed);
+        launch_gauge.distributed = true;
 
-    // ============ Holder Tracking ============
+        let gauge_epoch = &mut ctx.accounts.gauge_epoch;
+        let share = (gauge_epoch.emissions as u128 * launch_gauge.votes as u128
+            / gauge_epoch.total_votes as u128) as u64;
 
-    /// Record a holder's position (called on buy)
-    pub fn record_position(
-        ctx: Context<RecordPosition>,
-        amount: u64,
-    ) -> Result<()> {
-        let position = &mut ctx.accounts.position;
-        let launch = &mut ctx.accounts.launch;
-        let clock = Clock::get()?;
+        let vault = &ctx.accounts.emissions_vault;
+        let amount = share.min(withdrawable_lamports(vault)?);
 
-        if position.balance == 0 {
-            position.holder = ctx.accounts.holder.key();
-            position.launch = launch.key();
-            position.first_buy_timestamp = clock.unix_timestamp;
-            position.bump = ctx.bumps.position;
-            launch.holder_count += 1;
-        }
+        let vault_seeds = &[b"emissions_vault".as_ref(), &[ctx.bumps.emissions_vault]];
+        transfer_lamports(
+            vault.to_account_info(),
+            ctx.accounts.reward_vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            amount,
+            &[vault_seeds],
+        )?;
 
-        position.balance = position.balance.checked_add(amount).unwrap();
-        position.last_activity_timestamp = clock.unix_timestamp;
-        position.diamond_rank = calculate_diamond_rank(
-            position.first_buy_timestamp,
-            clock.unix_timestamp
-        );
-        position.multiplier_bps = get_diamond_multiplier_bps(position.diamond_rank);
+        gauge_epoch.emissions_paid = gauge_epoch.emissions_paid.checked_add(amount).unwrap();
+        launch_gauge.emissions_received = amount;
 
-        emit!(PositionUpdated {
-            holder: position.holder,
-            launch: position.launch,
-            balance: position.balance,
-            diamond_rank: position.diamond_rank,
-            multiplier_bps: position.multiplier_bps,
+        let launch = &mut ctx.accounts.launch;
+        launch.reward_vault_funded = launch.reward_vault_funded.checked_add(amount).unwrap();
+        distribute_rewards(launch, amount);
+
+        emit!(GaugeEmissionsDistributed {
+            launch: launch.key(),
+            epoch,
+            votes: launch_gauge.votes,
+            total_votes: gauge_epoch.total_votes,
+            amount,
+            schema_version: EVENT_SCHEMA_VERSION,
         });
 
         Ok(())
     }
 
-    /// Flag a wallet as a bundler
-    pub fn flag_bundler(
-        ctx: Context<FlagBundler>,
-        evidence: String,
+    // ============ Launches ============
+
+    /// Create a new token launch with enforced safety settings. Passing the
+    /// mint accounts also creates a Token-2022 mint carrying the name,
+    /// symbol and `uri` as frozen on-mint metadata.
+    pub fn create_launch(
+        ctx: Context<CreateLaunch>,
+        name: String,
+        symbol: String,
+        uri: String,
+        total_supply: u64,
+        config: LaunchConfig,
     ) -> Result<()> {
-        let bundler = &mut ctx.accounts.bundler;
-        let protocol = &mut ctx.accounts.protocol;
+        validate_launch_params(&name, &symbol, total_supply, &config)?;
+        require!(uri.len() <= MAX_METADATA_URI_LEN, DiamondPadError::MetadataUriTooLong);
 
-        bundler.wallet = ctx.accounts.flagged_wallet.key();
-        bundler.flagged_at = Clock::get()?.unix_timestamp;
-        bundler.evidence = evidence.clone();
-        bundler.incident_count = 1;
-        bundler.bump = ctx.bumps.bundler;
+        fund_launch_vaults(
+            &ctx.accounts.creator,
+            &ctx.accounts.sol_vault,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.system_program,
+        )?;
+
+        let launch_id = ctx.accounts.protocol.take_launch_id();
+        init_launch(
+            &mut ctx.accounts.launch,
+            &ctx.accounts.protocol,
+            launch_id,
+            ctx.accounts.creator.key(),
+            name,
+            symbol,
+            total_supply,
+            config,
+            Pubkey::default(),
+            ctx.bumps.launch,
+        )?;
+        escrow_listing_deposit(
+            &ctx.accounts.creator,
+            &mut ctx.accounts.launch,
+            ctx.accounts.protocol.listing_deposit,
+            &ctx.accounts.system_program,
+        )?;
 
-        protocol.total_bundlers_caught += 1;
+        apply_creator_tier(&mut ctx.accounts.launch, ctx.accounts.creator_stake.as_mut())?;
 
-        emit!(BundlerFlagged {
-            wallet: bundler.wallet,
-            evidence,
+        // Token-2022 launches get their mint here, with every authority on
+        // the launch authority PDA
+        match (
+            ctx.accounts.launch_mint.as_ref(),
+            ctx.accounts.launch_authority.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+        ) {
+            (Some(mint), Some(launch_authority), Some(token_program)) => {
+                let protocol = &ctx.accounts.protocol;
+                protocol.require_feature(FEATURE_TOKEN2022_MINTS)?;
+                if ctx.accounts.launch.config.transfer_fee_bps > 0 {
+                    protocol.require_feature(FEATURE_TRANSFER_FEES)?;
+                }
+
+                let launch_key = ctx.accounts.launch.key();
+                let mint_bump = ctx.bumps.launch_mint.unwrap();
+                let authority_bump = ctx.bumps.launch_authority.unwrap();
+                let mint_seeds = &[b"launch_mint".as_ref(), launch_key.as_ref(), &[mint_bump]];
+                let authority_seeds = &[
+                    b"launch_authority".as_ref(),
+                    launch_key.as_ref(),
+                    &[authority_bump],
+                ];
+                launch_mint::create_launch_mint(
+                    ctx.accounts.creator.to_account_info(),
+                    mint.to_account_info(),
+                    launch_authority.to_account_info(),
+                    token_program.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    &ctx.accounts.launch,
+                    uri,
+                    mint_seeds,
+                    authority_seeds,
+                )?;
+                let launch = &mut ctx.accounts.launch;
+                launch.mint = mint.key();
+                launch.interest_bearing = launch.config.interest_rate_bps > 0;
+            }
+            (None, None, None) => {
+                let config = &ctx.accounts.launch.config;
+                require!(
+                    config.transfer_fee_bps == 0
+                        && config.interest_rate_bps == 0
+                        && !config.freeze_until_graduation,
+                    DiamondPadError::LaunchMintRequired
+                );
+            }
+            _ => return err!(DiamondPadError::LaunchMintRequired),
+        }
+
+        attribute_channel(&mut ctx.accounts.launch, ctx.accounts.channel.as_mut());
+        enqueue_for_review(&ctx.accounts.launch, ctx.accounts.review_queue.as_mut())
+    }
+
+    /// Create a launch at a creator-chosen address (`["launch", creator, seed]`)
+    /// so it can be announced before it exists. The launch still takes the
+    /// next launch id, and an index entry maps that id back to the address.
+    pub fn create_launch_with_seed(
+        ctx: Context<CreateLaunchWithSeed>,
+        seed: String,
+        name: String,
+        symbol: String,
+        total_supply: u64,
+        config: LaunchConfig,
+    ) -> Result<()> {
+        require!(
+            !seed.is_empty() && seed.len() <= MAX_LAUNCH_SEED_LEN,
+            DiamondPadError::InvalidLaunchSeed
+        );
+        validate_launch_params(&name, &symbol, total_supply, &config)?;
+
+        fund_launch_vaults(
+            &ctx.accounts.creator,
+            &ctx.accounts.sol_vault,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.system_program,
+        )?;
+
+        let launch_id = ctx.accounts.protocol.take_launch_id();
+        init_launch(
+            &mut ctx.accounts.launch,
+            &ctx.accounts.protocol,
+            launch_id,
+            ctx.accounts.creator.key(),
+            name,
+            symbol,
+            total_supply,
+            config,
+            Pubkey::default(),
+            ctx.bumps.launch,
+        )?;
+        escrow_listing_deposit(
+            &ctx.accounts.creator,
+            &mut ctx.accounts.launch,
+            ctx.accounts.protocol.listing_deposit,
+            &ctx.accounts.system_program,
+        )?;
+
+        let launch_index = &mut ctx.accounts.launch_index;
+        launch_index.launch_id = ctx.accounts.launch.launch_id;
+        launch_index.launch = ctx.accounts.launch.key();
+        launch_index.seed = seed;
+        launch_index.bump = ctx.bumps.launch_index;
+
+        apply_creator_tier(&mut ctx.accounts.launch, ctx.accounts.creator_stake.as_mut())?;
+
+        attribute_channel(&mut ctx.accounts.launch, ctx.accounts.channel.as_mut());
+        enqueue_for_review(&ctx.accounts.launch, ctx.accounts.review_queue.as_mut())
+    }
+
+    /// Open the account that numbers a creator's launches
+    pub fn open_creator_account(ctx: Context<OpenCreatorAccount>) -> Result<()> {
+        let creator_account = &mut ctx.accounts.creator_account;
+        creator_account.creator = ctx.accounts.creator.key();
+        creator_account.created_at = Clock::get()?.unix_timestamp;
+        creator_account.bump = ctx.bumps.creator_account;
+        Ok(())
+    }
+
+    /// Open one of the launch id shards; anyone can pay for it
+    pub fn open_launch_id_shard(ctx: Context<OpenLaunchIdShard>, shard: u8) -> Result<()> {
+        require!(shard < LAUNCH_ID_SHARDS, DiamondPadError::InvalidLaunchIdShard);
+        let launch_id_shard = &mut ctx.accounts.launch_id_shard;
+        launch_id_shard.shard = shard;
+        launch_id_shard.bump = ctx.bumps.launch_id_shard;
+        Ok(())
+    }
+
+    /// Create a launch at the creator's next nonce
+    /// (`["creator_launch", creator, nonce]`), so a creator's launches can
+    /// be listed by walking nonces from zero. The launch id comes from a
+    /// client-picked `LaunchIdShard` rather than the protocol counter, so
+    /// this never writes the Protocol account; an index entry maps the id
+    /// back to the address.
+    pub fn create_creator_launch(
+        ctx: Context<CreateCreatorLaunch>,
+        name: String,
+        symbol: String,
+        total_supply: u64,
+        config: LaunchConfig,
+    ) -> Result<()> {
+        validate_launch_params(&name, &symbol, total_supply, &config)?;
+
+        fund_launch_vaults(
+            &ctx.accounts.creator,
+            &ctx.accounts.sol_vault,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.system_program,
+        )?;
+
+        let launch_id = ctx.accounts.launch_id_shard.take_launch_id();
+        init_launch(
+            &mut ctx.accounts.launch,
+            &ctx.accounts.protocol,
+            launch_id,
+            ctx.accounts.creator.key(),
+            name,
+            symbol,
+            total_supply,
+            config,
+            Pubkey::default(),
+            ctx.bumps.launch,
+        )?;
+        escrow_listing_deposit(
+            &ctx.accounts.creator,
+            &mut ctx.accounts.launch,
+            ctx.accounts.protocol.listing_deposit,
+            &ctx.accounts.system_program,
+        )?;
+
+        let creator_account = &mut ctx.accounts.creator_account;
+        ctx.accounts.launch.creator_nonce = creator_account.launch_count;
+        creator_account.launch_count += 1;
+
+        let launch_index = &mut ctx.accounts.launch_index;
+        launch_index.launch_id = ctx.accounts.launch.launch_id;
+        launch_index.launch = ctx.accounts.launch.key();
+        launch_index.bump = ctx.bumps.launch_index;
+
+        apply_creator_tier(&mut ctx.accounts.launch, ctx.accounts.creator_stake.as_mut())?;
+
+        attribute_channel(&mut ctx.accounts.launch, ctx.accounts.channel.as_mut());
+        enqueue_for_review(&ctx.accounts.launch, ctx.accounts.review_queue.as_mut())
+    }
+
+    // ============ Launch Mint ============
+
+    /// Sweep Token-2022 transfer fees withheld on the launch mint (and on
+    /// any token accounts passed as remaining accounts) into a token account
+    /// owned by the reward vault. Permissionless: the withdraw authority is
+    /// the launch authority PDA, so the tax can't go anywhere else. A mint
+    /// frozen until graduation has to be thawed first, along with the
+    /// vault's account if it was opened while frozen.
+    pub fn harvest_transfer_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, HarvestTransferFees<'info>>,
+    ) -> Result<()> {
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let mint = ctx.accounts.launch_mint.to_account_info();
+
+        if !ctx.remaining_accounts.is_empty() {
+            token_interface::harvest_withheld_tokens_to_mint(
+                CpiContext::new(
+                    token_program.clone(),
+                    token_interface::HarvestWithheldTokensToMint {
+                        token_program_id: token_program.clone(),
+                        mint: mint.clone(),
+                    },
+                ),
+                ctx.remaining_accounts.to_vec(),
+            )?;
+        }
+
+        let launch_key = ctx.accounts.launch.key();
+        let seeds = &[
+            b"launch_authority".as_ref(),
+            launch_key.as_ref(),
+            &[ctx.bumps.launch_authority],
+        ];
+        let balance_before = ctx.accounts.reward_token_account.amount;
+        token_interface::withdraw_withheld_tokens_from_mint(CpiContext::new_with_signer(
+            token_program.clone(),
+            token_interface::WithdrawWithheldTokensFromMint {
+                token_program_id: token_program,
+                mint,
+                destination: ctx.accounts.reward_token_account.to_account_info(),
+                authority: ctx.accounts.launch_authority.to_account_info(),
+            },
+            &[&seeds[..]],
+        ))?;
+        ctx.accounts.reward_token_account.reload()?;
+        let amount = ctx
+            .accounts
+            .reward_token_account
+            .amount
+            .saturating_sub(balance_before);
+
+        let launch = &mut ctx.accounts.launch;
+        launch.transfer_fees_harvested = launch.transfer_fees_harvested.saturating_add(amount);
+
+        emit!(TransferFeesHarvested {
+            launch_id: launch.launch_id,
+            amount,
+            total_harvested: launch.transfer_fees_harvested,
+            schema_version: EVENT_SCHEMA_VERSION,
         });
 
         Ok(())
     }
-}
 
-// ============ Helper Functions ============
+    /// Lift a launch mint's freeze once the launch has graduated: token
+    /// accounts opened from now on start unfrozen, and accounts passed as
+    /// remaining accounts (opened while frozen) are thawed. Permissionless;
+    /// call again with more accounts as needed.
+    pub fn thaw_launch_transfers<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ThawLaunchTransfers<'info>>,
+    ) -> Result<()> {
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let mint = ctx.accounts.launch_mint.to_account_info();
+        let authority = ctx.accounts.launch_authority.to_account_info();
 
-fn calculate_staking_tier(amount: u64, lock_days: u16) -> StakingTier {
-    if amount >= 100_000_000_000 && lock_days >= 180 { // 100k tokens (assuming 6 decimals)
-        StakingTier::Diamond
-    } else if amount >= 50_000_000_000 && lock_days >= 90 {
-        StakingTier::Gold
-    } else if amount >= 20_000_000_000 && lock_days >= 60 {
-        StakingTier::Silver
-    } else if amount >= 5_000_000_000 && lock_days >= 30 {
-        StakingTier::Bronze
-    } else {
-        StakingTier::Public
+        let launch_key = ctx.accounts.launch.key();
+        let seeds = &[
+            b"launch_authority".as_ref(),
+            launch_key.as_ref(),
+            &[ctx.bumps.launch_authority],
+        ];
+
+        if !ctx.accounts.launch.transfers_thawed {
+            token_interface::default_account_state_update(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    token_interface::DefaultAccountStateUpdate {
+                        token_program_id: token_program.clone(),
+                        mint: mint.clone(),
+                        freeze_authority: authority.clone(),
+                    },
+                    &[&seeds[..]],
+                ),
+                &AccountState::Initialized,
+            )?;
+            ctx.accounts.launch.transfers_thawed = true;
+        }
+
+        for account in ctx.remaining_accounts {
+            token_interface::thaw_account(CpiContext::new_with_signer(
+                token_program.clone(),
+                token_interface::ThawAccount {
+                    account: account.clone(),
+                    mint: mint.clone(),
+                    authority: authority.clone(),
+                },
+                &[&seeds[..]],
+            ))?;
+        }
+
+        emit!(LaunchTransfersThawed {
+            launch_id: ctx.accounts.launch.launch_id,
+            accounts_thawed: ctx.remaining_accounts.len() as u32,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Launch Templates ============
+
+    /// Publish a reusable launch configuration
+    pub fn publish_template(
+        ctx: Context<PublishTemplate>,
+        name: String,
+        config: LaunchConfig,
+    ) -> Result<()> {
+        require!(name.len() <= MAX_NAME_LEN, DiamondPadError::NameTooLong);
+        validate_launch_config(&config)?;
+
+        let template = &mut ctx.accounts.template;
+        template.publisher = ctx.accounts.publisher.key();
+        template.name = name.clone();
+        template.config = config;
+        template.verified = template.publisher == ctx.accounts.protocol.authority;
+        template.times_used = 0;
+        template.created_at = Clock::get()?.unix_timestamp;
+        template.bump = ctx.bumps.template;
+
+        emit!(TemplatePublished {
+            template: template.key(),
+            publisher: template.publisher,
+            name,
+            verified: template.verified,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Mark a partner template as vetted (or revoke it)
+    pub fn set_template_verified(
+        ctx: Context<SetTemplateVerified>,
+        verified: bool,
+    ) -> Result<()> {
+        let template = &mut ctx.accounts.template;
+        let previous = template.verified;
+        template.verified = verified;
+
+        emit!(TemplateVerificationUpdated {
+            template: template.key(),
+            verified,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SetTemplateVerified,
+            template.key(),
+            AuditValue::Bool(previous),
+            AuditValue::Bool(verified),
+        )?;
+
+        Ok(())
+    }
+
+    /// Create a launch using a published template's configuration
+    pub fn create_launch_from_template(
+        ctx: Context<CreateLaunchFromTemplate>,
+        name: String,
+        symbol: String,
+        total_supply: u64,
+    ) -> Result<()> {
+        let template = &mut ctx.accounts.template;
+        let config = template.config.clone();
+        validate_launch_params(&name, &symbol, total_supply, &config)?;
+
+        template.times_used += 1;
+
+        fund_launch_vaults(
+            &ctx.accounts.creator,
+            &ctx.accounts.sol_vault,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.system_program,
+        )?;
+
+        let launch_id = ctx.accounts.protocol.take_launch_id();
+        init_launch(
+            &mut ctx.accounts.launch,
+            &ctx.accounts.protocol,
+            launch_id,
+            ctx.accounts.creator.key(),
+            name,
+            symbol,
+            total_supply,
+            config,
+            template.key(),
+            ctx.bumps.launch,
+        )?;
+        escrow_listing_deposit(
+            &ctx.accounts.creator,
+            &mut ctx.accounts.launch,
+            ctx.accounts.protocol.listing_deposit,
+            &ctx.accounts.system_program,
+        )?;
+
+        apply_creator_tier(&mut ctx.accounts.launch, ctx.accounts.creator_stake.as_mut())?;
+
+        attribute_channel(&mut ctx.accounts.launch, ctx.accounts.channel.as_mut());
+        enqueue_for_review(&ctx.accounts.launch, ctx.accounts.review_queue.as_mut())
+    }
+
+    /// Create a launch with another launch's configuration (curve, vesting,
+    /// sell policy, caps and every other `LaunchConfig` field, plus its rank
+    /// schedule), overriding only name, symbol and supply. The schedule is
+    /// checked like a `SetRankThresholds` proposal's. Launches with a Token-2022
+    /// mint can't be cloned this way.
+    pub fn clone_launch(
+        ctx: Context<CloneLaunch>,
+        name: String,
+        symbol: String,
+        total_supply: u64,
+    ) -> Result<()> {
+        let source = &ctx.accounts.source_launch;
+        let config = source.config.clone();
+        validate_launch_params(&name, &symbol, total_supply, &config)?;
+        require!(
+            config.transfer_fee_bps == 0
+                && config.interest_rate_bps == 0
+                && !config.freeze_until_graduation,
+            DiamondPadError::LaunchMintRequired
+        );
+        let rank_threshold_days = source.rank_thresholds();
+        validate_rank_thresholds(&rank_threshold_days)?;
+        let source_launch_id = source.launch_id;
+
+        fund_launch_vaults(
+            &ctx.accounts.creator,
+            &ctx.accounts.sol_vault,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.system_program,
+        )?;
+
+        let launch_id = ctx.accounts.protocol.take_launch_id();
+        init_launch(
+            &mut ctx.accounts.launch,
+            &ctx.accounts.protocol,
+            launch_id,
+            ctx.accounts.creator.key(),
+            name,
+            symbol,
+            total_supply,
+            config,
+            Pubkey::default(),
+            ctx.bumps.launch,
+        )?;
+        escrow_listing_deposit(
+            &ctx.accounts.creator,
+            &mut ctx.accounts.launch,
+            ctx.accounts.protocol.listing_deposit,
+            &ctx.accounts.system_program,
+        )?;
+        ctx.accounts.launch.rank_threshold_days = rank_threshold_days;
+
+        emit!(LaunchCloned {
+            launch_id,
+            source_launch_id,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        apply_creator_tier(&mut ctx.accounts.launch, ctx.accounts.creator_stake.as_mut())?;
+
+        attribute_channel(&mut ctx.accounts.launch, ctx.accounts.channel.as_mut());
+        enqueue_for_review(&ctx.accounts.launch, ctx.accounts.review_queue.as_mut())
+    }
+
+    // ============ Partner Channels ============
+
+    /// Register a partner (wallet, community) as a distribution channel
+    /// launches can be attributed to at creation. The channel earns
+    /// `fee_share_bps` of those launches' protocol trade fees.
+    pub fn register_channel(
+        ctx: Context<RegisterChannel>,
+        partner: Pubkey,
+        fee_share_bps: u16,
+    ) -> Result<()> {
+        require!(
+            fee_share_bps <= MAX_CHANNEL_FEE_SHARE_BPS,
+            DiamondPadError::InvalidChannelFeeShare
+        );
+
+        let channel = &mut ctx.accounts.channel;
+        channel.partner = partner;
+        channel.fee_share_bps = fee_share_bps;
+        channel.created_at = Clock::get()?.unix_timestamp;
+        channel.bump = ctx.bumps.channel;
+
+        emit!(ChannelUpdated {
+            channel: channel.key(),
+            partner,
+            fee_share_bps,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::RegisterChannel,
+            channel.key(),
+            AuditValue::None,
+            AuditValue::Amount(fee_share_bps as u64),
+        )
+    }
+
+    /// Change a channel's share of its launches' protocol fees (0 stops
+    /// new accrual; what it has accrued stays claimable)
+    pub fn set_channel_fee_share(ctx: Context<UpdateChannel>, fee_share_bps: u16) -> Result<()> {
+        require!(
+            fee_share_bps <= MAX_CHANNEL_FEE_SHARE_BPS,
+            DiamondPadError::InvalidChannelFeeShare
+        );
+
+        let channel = &mut ctx.accounts.channel;
+        let previous = channel.fee_share_bps;
+        channel.fee_share_bps = fee_share_bps;
+
+        emit!(ChannelUpdated {
+            channel: channel.key(),
+            partner: channel.partner,
+            fee_share_bps,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SetChannelFeeShare,
+            channel.key(),
+            AuditValue::Amount(previous as u64),
+            AuditValue::Amount(fee_share_bps as u64),
+        )
+    }
+
+    /// Partner withdraws the fees its channel has accrued
+    pub fn claim_channel_fees(ctx: Context<ClaimChannelFees>) -> Result<()> {
+        let channel = &mut ctx.accounts.channel;
+        let amount = channel.fees_accrued - channel.fees_claimed;
+        require!(amount > 0, DiamondPadError::NothingToClaim);
+
+        channel.fees_claimed = channel.fees_accrued;
+        channel.sub_lamports(amount)?;
+        ctx.accounts.partner.add_lamports(amount)?;
+
+        emit!(ChannelFeesClaimed {
+            channel: channel.key(),
+            partner: channel.partner,
+            amount,
+            total_claimed: channel.fees_claimed,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Liquidity Lock ============
+
+    /// Push the LP unlock date further out (never earlier)
+    pub fn extend_lp_lock(
+        ctx: Context<UpdateLpLock>,
+        new_unlock_timestamp: i64,
+    ) -> Result<()> {
+        extend_launch_lp_lock(&mut ctx.accounts.launch, new_unlock_timestamp)
+    }
+
+    /// Permanently burn the LP lock (liquidity can never be withdrawn)
+    pub fn burn_lp_lock(ctx: Context<UpdateLpLock>) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        launch.require_unfrozen(FREEZE_LP_LOCK)?;
+
+        require!(!launch.lp_burned, DiamondPadError::LpAlreadyBurned);
+
+        launch.lp_burned = true;
+        launch.lp_unlock_timestamp = i64::MAX;
+
+        emit!(LpLockBurned {
+            launch_id: launch.launch_id,
+            creator: launch.creator,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        grant_relock_incentive(launch);
+
+        if let Some(checklist) = ctx.accounts.checklist.as_mut() {
+            checklist.complete(CHECKLIST_LP_LOCKED, Clock::get()?.unix_timestamp);
+        }
+
+        Ok(())
+    }
+
+    // ============ Quality Score ============
+
+    /// Recompute a launch's 0-100 quality score from its current on-chain
+    /// state. Permissionless; every input account is pinned to its PDA or
+    /// the launch, so a caller can't lower the score by leaving one out.
+    pub fn refresh_quality_score(ctx: Context<RefreshQualityScore>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let launch = &mut ctx.accounts.launch;
+
+        let mint_authority_revoked = match &ctx.accounts.launch_mint {
+            Some(mint) => mint.mint_authority.is_none(),
+            None => {
+                require!(launch.mint == Pubkey::default(), DiamondPadError::LaunchMintRequired);
+                false
+            }
+        };
+
+        let creator_stake = read_creator_stake(&ctx.accounts.creator_stake)?;
+        let score = QualityScore::compute(&QualityInputs {
+            lp_burned: launch.lp_burned,
+            lp_lock_days_remaining: ((launch.lp_unlock_timestamp - now).max(0) / 86400) as u64,
+            mint_authority_revoked,
+            dev_allocation_bps: launch.config.dev_allocation_bps,
+            dev_vesting_days: launch.config.dev_vesting_days,
+            creator_tier: creator_stake.as_ref().map(|stake| stake.tier),
+            creator_times_slashed: creator_stake.map_or(0, |stake| stake.times_slashed),
+        });
+
+        launch.quality_score = score.total();
+        launch.quality_score_updated_at = now;
+
+        emit!(QualityScoreRefreshed {
+            launch: launch.key(),
+            score: launch.quality_score,
+            components: score,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        if let Some(checklist) = ctx.accounts.checklist.as_mut() {
+            let revoked = if mint_authority_revoked { CHECKLIST_MINT_AUTHORITY_REVOKED } else { 0 };
+            let mint = ctx.accounts.launch_mint.as_ref().map(|mint| mint.to_account_info());
+            let steps = launch.checklist_steps(mint.as_ref(), now)?;
+            checklist.complete(steps | revoked, now);
+        }
+
+        Ok(())
+    }
+
+    /// Open a launch's creator checklist, ticking off whatever the launch
+    /// already shows as done. Anyone can pay for it.
+    pub fn open_creator_checklist(ctx: Context<OpenCreatorChecklist>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let launch = &ctx.accounts.launch;
+        let mint = ctx.accounts.launch_mint.as_ref().map(|mint| mint.to_account_info());
+        let steps = launch.checklist_steps(mint.as_ref(), now)?;
+        let checklist = &mut ctx.accounts.checklist;
+        checklist.launch = launch.key();
+        checklist.creator = launch.creator;
+        checklist.bump = ctx.bumps.checklist;
+        checklist.complete(steps, now);
+        Ok(())
+    }
+
+    // ============ Launch Terms ============
+
+    /// Creator adjusts launch terms. Each field is skipped when `None` and
+    /// rejected once frozen by `finalize_launch_config`.
+    pub fn update_launch_terms(
+        ctx: Context<UpdateLaunchTerms>,
+        reward_fee_share_bps: Option<u16>,
+        dev_vesting_days: Option<u16>,
+        sell_policy: Option<SellPolicy>,
+        sell_grace_bps: Option<u16>,
+    ) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+
+        require!(
+            launch.status == LaunchStatus::Pending || launch.status == LaunchStatus::Active,
+            DiamondPadError::LaunchNotOpen
+        );
+
+        if let Some(reward_fee_share_bps) = reward_fee_share_bps {
+            launch.require_unfrozen(FREEZE_FEES)?;
+            launch.config.reward_fee_share_bps = reward_fee_share_bps;
+        }
+        if let Some(dev_vesting_days) = dev_vesting_days {
+            launch.require_unfrozen(FREEZE_VESTING)?;
+            launch.config.dev_vesting_days = dev_vesting_days;
+        }
+        if let Some(sell_policy) = sell_policy {
+            launch.require_unfrozen(FREEZE_SELL_POLICY)?;
+            launch.config.sell_policy = sell_policy;
+        }
+        if let Some(sell_grace_bps) = sell_grace_bps {
+            launch.require_unfrozen(FREEZE_SELL_POLICY)?;
+            launch.config.sell_grace_bps = sell_grace_bps;
+        }
+        validate_launch_config(&launch.config)?;
+
+        emit!(LaunchTermsUpdated {
+            launch_id: launch.launch_id,
+            reward_fee_share_bps: launch.config.reward_fee_share_bps,
+            dev_vesting_days: launch.config.dev_vesting_days,
+            sell_policy: launch.config.sell_policy,
+            sell_grace_bps: launch.config.sell_grace_bps,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Creator raises the holder rewards share. One-way: it can never be
+    /// lowered again, and it stays under the fee freeze.
+    pub fn raise_holder_rewards(
+        ctx: Context<UpdateLaunchTerms>,
+        holder_rewards_bps: u16,
+    ) -> Result<()> {
+        raise_launch_holder_rewards(&mut ctx.accounts.launch, holder_rewards_bps)
+    }
+
+    /// Permanently freeze the parameter groups in `mask` (FREEZE_* bits).
+    /// Bits can only ever be added.
+    pub fn finalize_launch_config(ctx: Context<UpdateLaunchTerms>, mask: u16) -> Result<()> {
+        require!(
+            mask != 0 && mask & !FREEZE_ALL == 0,
+            DiamondPadError::InvalidFreezeMask
+        );
+
+        let launch = &mut ctx.accounts.launch;
+        launch.frozen_params |= mask;
+
+        emit!(LaunchConfigFrozen {
+            launch_id: launch.launch_id,
+            frozen_params: launch.frozen_params,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Insiders ============
+
+    /// Register a team wallet as an insider of the launch. The wallet signs
+    /// alongside the creator, so nobody can be tagged without agreeing.
+    pub fn declare_insider(ctx: Context<DeclareInsider>) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let wallet = ctx.accounts.insider.key();
+
+        require!(!launch.is_insider(&wallet), DiamondPadError::AlreadyInsider);
+        require!(launch.insiders.len() < MAX_INSIDERS, DiamondPadError::TooManyInsiders);
+
+        launch.insiders.push(wallet);
+
+        // Tag an existing position right away; new ones are tagged on open
+        if let Some(position) = ctx.accounts.position.as_mut() {
+            let now = Clock::get()?.unix_timestamp;
+            settle_rewards(position, launch, now);
+            position.set_flag(POSITION_INSIDER, true);
+            refresh_rank(position, launch, now);
+            sync_reward_weight(position, launch);
+        }
+
+        emit!(InsiderDeclared {
+            launch_id: launch.launch_id,
+            wallet,
+            insider_count: launch.insiders.len() as u8,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Moderation ============
+
+    /// Let a key moderate one launch only: halt and resume its trading and
+    /// open bundler flags for the protocol's flaggers to approve
+    pub fn assign_launch_moderator(ctx: Context<AssignLaunchModerator>, moderator: Pubkey) -> Result<()> {
+        let assignment = &mut ctx.accounts.mod_assignment;
+        assignment.launch = ctx.accounts.launch.key();
+        assignment.moderator = moderator;
+        assignment.assigned_at = Clock::get()?.unix_timestamp;
+        assignment.bump = ctx.bumps.mod_assignment;
+
+        emit!(LaunchModeratorUpdated {
+            launch_id: ctx.accounts.launch.launch_id,
+            moderator,
+            active: true,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::AssignLaunchModerator,
+            ctx.accounts.launch.key(),
+            AuditValue::None,
+            AuditValue::Key(moderator),
+        )?;
+
+        Ok(())
     }
+
+    /// Revoke a per-launch moderator assignment
+    pub fn remove_launch_moderator(ctx: Context<RemoveLaunchModerator>) -> Result<()> {
+        let moderator = ctx.accounts.mod_assignment.moderator;
+
+        emit!(LaunchModeratorUpdated {
+            launch_id: ctx.accounts.launch.launch_id,
+            moderator,
+            active: false,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::RemoveLaunchModerator,
+            ctx.accounts.launch.key(),
+            AuditValue::Key(moderator),
+            AuditValue::None,
+        )?;
+
+        Ok(())
+    }
+
+    /// Halt buys and sells on a single launch (exploit or rug investigation)
+    pub fn halt_launch_trading(
+        ctx: Context<ModerateLaunch>,
+        reason_code: u8,
+    ) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(!launch.is_paused(PAUSE_TRADING), DiamondPadError::TradingHalted);
+
+        launch.paused |= PAUSE_TRADING;
+        launch.halt_reason = reason_code;
+        launch.halted_at = clock.unix_timestamp;
+
+        emit!(LaunchTradingHalted {
+            launch_id: launch.launch_id,
+            moderator: ctx.accounts.moderator.key(),
+            reason_code,
+            timestamp: clock.unix_timestamp,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.moderator.key(),
+            AdminAction::HaltTrading,
+            launch.key(),
+            AuditValue::None,
+            AuditValue::Amount(reason_code as u64),
+        )?;
+
+        Ok(())
+    }
+
+    /// Lift a per-launch trading halt
+    pub fn resume_launch_trading(ctx: Context<ModerateLaunch>) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(launch.is_paused(PAUSE_TRADING), DiamondPadError::TradingNotHalted);
+
+        let reason_code = launch.halt_reason;
+        launch.paused &= !PAUSE_TRADING;
+        launch.halt_reason = 0;
+
+        emit!(LaunchTradingResumed {
+            launch_id: launch.launch_id,
+            moderator: ctx.accounts.moderator.key(),
+            reason_code,
+            halted_for_seconds: clock.unix_timestamp - launch.halted_at,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.moderator.key(),
+            AdminAction::ResumeTrading,
+            launch.key(),
+            AuditValue::Amount(reason_code as u64),
+            AuditValue::None,
+        )?;
+
+        Ok(())
+    }
+
+    /// Pause reward claims on a single launch while leaving trading open
+    /// (e.g. while an accrual anomaly is investigated)
+    pub fn pause_launch_claims(ctx: Context<ModerateLaunch>, reason_code: u8) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(!launch.is_paused(PAUSE_CLAIMS), DiamondPadError::ClaimsPaused);
+
+        launch.paused |= PAUSE_CLAIMS;
+        launch.claims_paused_at = clock.unix_timestamp;
+
+        emit!(LaunchClaimsPaused {
+            launch_id: launch.launch_id,
+            moderator: ctx.accounts.moderator.key(),
+            reason_code,
+            timestamp: clock.unix_timestamp,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.moderator.key(),
+            AdminAction::PauseClaims,
+            launch.key(),
+            AuditValue::None,
+            AuditValue::Amount(reason_code as u64),
+        )?;
+
+        Ok(())
+    }
+
+    /// Lift a per-launch claim pause
+    pub fn resume_launch_claims(ctx: Context<ModerateLaunch>) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(launch.is_paused(PAUSE_CLAIMS), DiamondPadError::ClaimsNotPaused);
+
+        launch.paused &= !PAUSE_CLAIMS;
+
+        emit!(LaunchClaimsResumed {
+            launch_id: launch.launch_id,
+            moderator: ctx.accounts.moderator.key(),
+            paused_for_seconds: clock.unix_timestamp - launch.claims_paused_at,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.moderator.key(),
+            AdminAction::ResumeClaims,
+            launch.key(),
+            AuditValue::None,
+            AuditValue::None,
+        )?;
+
+        Ok(())
+    }
+
+    /// Mark a launch as spam; its listing deposit goes to the treasury
+    /// whatever happens to the launch afterwards
+    pub fn flag_launch_spam(ctx: Context<ModerateLaunch>) -> Result<()> {
+        // Forfeits money to the treasury, so not for per-launch moderators
+        require_keys_eq!(
+            ctx.accounts.moderator.key(),
+            ctx.accounts.protocol.moderator,
+            DiamondPadError::Unauthorized
+        );
+        let launch = &mut ctx.accounts.launch;
+
+        require!(!launch.spam_flagged, DiamondPadError::AlreadySpamFlagged);
+        launch.spam_flagged = true;
+
+        emit!(LaunchSpamFlagged {
+            launch_id: launch.launch_id,
+            moderator: ctx.accounts.moderator.key(),
+            listing_deposit: launch.listing_deposit,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.moderator.key(),
+            AdminAction::FlagLaunchSpam,
+            launch.key(),
+            AuditValue::None,
+            AuditValue::None,
+        )?;
+
+        Ok(())
+    }
+
+    // ============ Curated Review ============
+
+    /// Approve a pending launch application so it can open for trading
+    pub fn approve_launch(ctx: Context<ApproveLaunch>) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(
+            launch.review_status == ReviewStatus::Pending,
+            DiamondPadError::LaunchNotUnderReview
+        );
+
+        launch.review_status = ReviewStatus::Approved;
+        launch.reviewed_at = clock.unix_timestamp;
+        ctx.accounts.review_queue.remove(&launch.key());
+
+        emit!(LaunchApproved {
+            launch_id: launch.launch_id,
+            reviewer: ctx.accounts.reviewer.key(),
+            timestamp: clock.unix_timestamp,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.reviewer.key(),
+            AdminAction::ApproveLaunch,
+            launch.key(),
+            AuditValue::None,
+            AuditValue::None,
+        )?;
+
+        Ok(())
+    }
+
+    /// Reject a pending launch application, closing it and returning rent to the creator
+    pub fn reject_launch(ctx: Context<RejectLaunch>, reason_code: u8) -> Result<()> {
+        let launch = &ctx.accounts.launch;
+
+        require!(
+            launch.review_status == ReviewStatus::Pending,
+            DiamondPadError::LaunchNotUnderReview
+        );
+
+        let launch_key = launch.key();
+        ctx.accounts.review_queue.remove(&launch_key);
+
+        // Rejected before it could ever trade: the deposit is forfeit
+        release_listing_deposit(
+            &mut ctx.accounts.launch,
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            0,
+        )?;
+        let launch = &ctx.accounts.launch;
+
+        let sol_vault_seeds = &[b"sol_vault".as_ref(), launch_key.as_ref(), &[ctx.bumps.sol_vault]];
+        transfer_lamports(
+            ctx.accounts.sol_vault.to_account_info(),
+            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.sol_vault.lamports(),
+            &[&sol_vault_seeds[..]],
+        )?;
+        let reward_vault_seeds = &[b"reward_vault".as_ref(), launch_key.as_ref(), &[ctx.bumps.reward_vault]];
+        transfer_lamports(
+            ctx.accounts.reward_vault.to_account_info(),
+            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.reward_vault.lamports(),
+            &[&reward_vault_seeds[..]],
+        )?;
+
+        emit!(LaunchRejected {
+            launch_id: launch.launch_id,
+            reviewer: ctx.accounts.reviewer.key(),
+            reason_code,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.reviewer.key(),
+            AdminAction::RejectLaunch,
+            launch_key,
+            AuditValue::None,
+            AuditValue::Amount(reason_code as u64),
+        )?;
+
+        Ok(())
+    }
+
+    // ============ Creator Stake ============
+
+    /// Stake SOL behind your launches to unlock higher launch tiers
+    pub fn stake_creator_sol(ctx: Context<StakeCreatorSol>, amount: u64) -> Result<()> {
+        require!(amount > 0, DiamondPadError::InvalidAmount);
+
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.creator.to_account_info(),
+            to: ctx.accounts.creator_stake.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+
+        let creator_stake = &mut ctx.accounts.creator_stake;
+        if creator_stake.creator == Pubkey::default() {
+            creator_stake.creator = ctx.accounts.creator.key();
+            creator_stake.bump = ctx.bumps.creator_stake;
+        }
+        creator_stake.sol_staked = creator_stake.sol_staked.checked_add(amount).unwrap();
+        creator_stake.tier = calculate_launch_tier(creator_stake.sol_staked, creator_stake.dpad_staked);
+
+        emit!(CreatorStakeUpdated {
+            creator: creator_stake.creator,
+            sol_staked: creator_stake.sol_staked,
+            dpad_staked: creator_stake.dpad_staked,
+            tier: creator_stake.tier,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Stake DPAD behind your launches to unlock higher launch tiers
+    pub fn stake_creator_dpad(ctx: Context<StakeCreatorDpad>, amount: u64) -> Result<()> {
+        require!(amount > 0, DiamondPadError::InvalidAmount);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.creator_token_account.to_account_info(),
+            to: ctx.accounts.stake_vault.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        let creator_stake = &mut ctx.accounts.creator_stake;
+        if creator_stake.creator == Pubkey::default() {
+            creator_stake.creator = ctx.accounts.creator.key();
+            creator_stake.bump = ctx.bumps.creator_stake;
+        }
+        creator_stake.dpad_staked = creator_stake.dpad_staked.checked_add(amount).unwrap();
+        creator_stake.tier = calculate_launch_tier(creator_stake.sol_staked, creator_stake.dpad_staked);
+
+        emit!(CreatorStakeUpdated {
+            creator: creator_stake.creator,
+            sol_staked: creator_stake.sol_staked,
+            dpad_staked: creator_stake.dpad_staked,
+            tier: creator_stake.tier,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw creator stake once no recent launch depends on it
+    pub fn unstake_creator(
+        ctx: Context<UnstakeCreator>,
+        sol_amount: u64,
+        dpad_amount: u64,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let creator_stake = &mut ctx.accounts.creator_stake;
+
+        require!(
+            clock.unix_timestamp >= creator_stake.locked_until,
+            DiamondPadError::CreatorStakeLocked
+        );
+        require!(sol_amount <= creator_stake.sol_staked, DiamondPadError::InsufficientStake);
+        require!(dpad_amount <= creator_stake.dpad_staked, DiamondPadError::InsufficientStake);
+
+        if sol_amount > 0 {
+            creator_stake.sub_lamports(sol_amount)?;
+            ctx.accounts.creator.add_lamports(sol_amount)?;
+            creator_stake.sol_staked -= sol_amount;
+        }
+
+        if dpad_amount > 0 {
+            let seeds = &[b"protocol".as_ref(), &[ctx.accounts.protocol.bump]];
+            let signer = &[&seeds[..]];
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.stake_vault.to_account_info(),
+                to: ctx.accounts.creator_token_account.to_account_info(),
+                authority: ctx.accounts.protocol.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token::transfer(cpi_ctx, dpad_amount)?;
+            creator_stake.dpad_staked -= dpad_amount;
+        }
+
+        creator_stake.tier = calculate_launch_tier(creator_stake.sol_staked, creator_stake.dpad_staked);
+
+        emit!(CreatorStakeUpdated {
+            creator: creator_stake.creator,
+            sol_staked: creator_stake.sol_staked,
+            dpad_staked: creator_stake.dpad_staked,
+            tier: creator_stake.tier,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Rule a launch a rug and slash its creator's stake and listing deposit
+    /// into the launch's insurance vault, for its holders to claim. The
+    /// ruling needs a council majority alongside the authority, and stays on
+    /// the creator's stake record for good.
+    pub fn slash_creator_stake(ctx: Context<SlashCreatorStake>) -> Result<()> {
+        require_council_majority(
+            &ctx.accounts.council,
+            ctx.remaining_accounts,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        let launch = &mut ctx.accounts.launch;
+        let creator_stake = &mut ctx.accounts.creator_stake;
+        let vault = &mut ctx.accounts.insurance_vault;
+
+        require!(!launch.rug_flagged, DiamondPadError::AlreadyRugFlagged);
+        launch.rug_flagged = true;
+        launch.rugged_at = Clock::get()?.unix_timestamp;
+
+        if vault.launch == Pubkey::default() {
+            vault.launch = launch.key();
+            vault.bump = ctx.bumps.insurance_vault;
+        }
+        require!(!vault.settled, DiamondPadError::InsuranceSettled);
+
+        let sol_slashed = creator_stake.sol_staked;
+        let dpad_slashed = creator_stake.dpad_staked;
+
+        if sol_slashed > 0 {
+            creator_stake.sub_lamports(sol_slashed)?;
+            vault.add_lamports(sol_slashed)?;
+            vault.total_funded = vault.total_funded.checked_add(sol_slashed).unwrap();
+        }
+
+        let deposit_forfeited = launch.listing_deposit;
+        if deposit_forfeited > 0 {
+            launch.listing_deposit = 0;
+            launch.sub_lamports(deposit_forfeited)?;
+            vault.add_lamports(deposit_forfeited)?;
+            vault.total_funded = vault.total_funded.checked_add(deposit_forfeited).unwrap();
+        }
+
+        if dpad_slashed > 0 {
+            let seeds = &[b"protocol".as_ref(), &[ctx.accounts.protocol.bump]];
+            let signer = &[&seeds[..]];
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.stake_vault.to_account_info(),
+                to: ctx.accounts.insurance_token_vault.to_account_info(),
+                authority: ctx.accounts.protocol.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token::transfer(cpi_ctx, dpad_slashed)?;
+            vault.dpad_funded = vault.dpad_funded.checked_add(dpad_slashed).unwrap();
+        }
+
+        creator_stake.sol_staked = 0;
+        creator_stake.dpad_staked = 0;
+        creator_stake.tier = LaunchTier::Standard;
+        creator_stake.times_slashed += 1;
+        if creator_stake.first_rug_verdict_at == 0 {
+            creator_stake.first_rug_verdict_at = Clock::get()?.unix_timestamp;
+        }
+
+        emit!(Slashed {
+            creator: creator_stake.creator,
+            launch_id: launch.launch_id,
+            insurance_vault: vault.key(),
+            sol_slashed,
+            dpad_slashed,
+            deposit_forfeited,
+            times_slashed: creator_stake.times_slashed,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        if Clock::get()?.unix_timestamp < ctx.accounts.protocol.legacy_events_until {
+            emit!(CreatorStakeSlashed {
+                creator: creator_stake.creator,
+                launch_id: launch.launch_id,
+                sol_slashed,
+                dpad_slashed,
+            });
+        }
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SlashCreatorStake,
+            creator_stake.creator,
+            AuditValue::Amount(sol_slashed),
+            AuditValue::Amount(0),
+        )?;
+
+        Ok(())
+    }
+
+    // ============ Wallet Risk ============
+
+    /// Push an oracle risk score (0-100) and category flags for a wallet
+    pub fn update_wallet_risk(
+        ctx: Context<UpdateWalletRisk>,
+        wallet: Pubkey,
+        score: u8,
+        categories: u32,
+    ) -> Result<()> {
+        require!(score <= MAX_RISK_SCORE, DiamondPadError::InvalidRiskScore);
+
+        let wallet_risk = &mut ctx.accounts.wallet_risk;
+        let previous_score = wallet_risk.score;
+        wallet_risk.wallet = wallet;
+        wallet_risk.score = score;
+        wallet_risk.categories = categories;
+        wallet_risk.updated_at = Clock::get()?.unix_timestamp;
+        wallet_risk.bump = ctx.bumps.wallet_risk;
+
+        emit!(WalletRiskUpdated {
+            wallet,
+            score,
+            categories,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.risk_oracle.key(),
+            AdminAction::UpdateWalletRisk,
+            wallet,
+            AuditValue::Amount(previous_score as u64),
+            AuditValue::Amount(score as u64),
+        )?;
+
+        Ok(())
+    }
+
+    /// Attest when a wallet first signed and which wallet funded it, for
+    /// launches that require a minimum wallet age
+    pub fn attest_wallet_age(
+        ctx: Context<UpdateWalletRisk>,
+        wallet: Pubkey,
+        first_signature_slot: u64,
+        funded_by: Pubkey,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(
+            first_signature_slot > 0 && first_signature_slot <= clock.slot,
+            DiamondPadError::InvalidWalletAge
+        );
+
+        let wallet_risk = &mut ctx.accounts.wallet_risk;
+        let previous_slot = wallet_risk.first_signature_slot;
+        wallet_risk.wallet = wallet;
+        wallet_risk.first_signature_slot = first_signature_slot;
+        wallet_risk.funded_by = funded_by;
+        wallet_risk.updated_at = clock.unix_timestamp;
+        wallet_risk.bump = ctx.bumps.wallet_risk;
+
+        emit!(WalletAgeAttested {
+            wallet,
+            first_signature_slot,
+            funded_by,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.risk_oracle.key(),
+            AdminAction::AttestWalletAge,
+            wallet,
+            AuditValue::Amount(previous_slot),
+            AuditValue::Amount(first_signature_slot),
+        )?;
+
+        Ok(())
+    }
+
+    // ============ Creator Attestations ============
+
+    /// Record that a creator's social handle or domain was verified
+    pub fn record_attestation(
+        ctx: Context<RecordAttestation>,
+        kind: AttestationKind,
+        handle_hash: [u8; 32],
+    ) -> Result<()> {
+        let attestation = &mut ctx.accounts.attestation;
+        let clock = Clock::get()?;
+
+        attestation.creator = ctx.accounts.creator.key();
+        attestation.verifier = ctx.accounts.verifier.key();
+        attestation.kind = kind;
+        attestation.handle_hash = handle_hash;
+        attestation.verified_at = clock.unix_timestamp;
+        attestation.revoked = false;
+        attestation.bump = ctx.bumps.attestation;
+
+        emit!(AttestationRecorded {
+            creator: attestation.creator,
+            verifier: attestation.verifier,
+            kind,
+            handle_hash,
+            verified_at: attestation.verified_at,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            attestation.verifier,
+            AdminAction::RecordAttestation,
+            attestation.creator,
+            AuditValue::None,
+            AuditValue::Bool(true),
+        )?;
+
+        Ok(())
+    }
+
+    /// Withdraw a previously recorded attestation
+    pub fn revoke_attestation(ctx: Context<RevokeAttestation>) -> Result<()> {
+        let attestation = &mut ctx.accounts.attestation;
+
+        require!(!attestation.revoked, DiamondPadError::AttestationRevoked);
+        attestation.revoked = true;
+
+        emit!(AttestationRevoked {
+            creator: attestation.creator,
+            verifier: ctx.accounts.verifier.key(),
+            kind: attestation.kind,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.verifier.key(),
+            AdminAction::RevokeAttestation,
+            attestation.creator,
+            AuditValue::Bool(true),
+            AuditValue::Bool(false),
+        )?;
+
+        Ok(())
+    }
+
+    // ============ Allocations ============
+
+    /// Request allocation for a launch
+    pub fn request_allocation(
+        ctx: Context<RequestAllocation>,
+        pool: AllocationPool,
+        amount_usd: u64,
+    ) -> Result<()> {
+        let allocation = &mut ctx.accounts.allocation;
+        let staker = &ctx.accounts.staker_account;
+        let launch = &ctx.accounts.launch;
+        let clock = Clock::get()?;
+        
+        if matches!(pool, AllocationPool::WeightedLottery | AllocationPool::PublicLottery) {
+            ctx.accounts.protocol.require_feature(FEATURE_LOTTERY_POOLS)?;
+        }
+        
+        // Validate pool access based on tier
+        match pool {
+            AllocationPool::Guaranteed => {
+                require!(
+                    staker.tier == StakingTier::Diamond || staker.tier == StakingTier::Gold,
+                    DiamondPadError::TierTooLow
+                );
+            },
+            AllocationPool::WeightedLottery => {
+                require!(
+                    staker.tier != StakingTier::Public,
+                    DiamondPadError::StakingRequired
+                );
+            },
+            _ => {} // Public pools open to all
+        }
+        
+        // Calculate weight based on tier
+        let weight = get_tier_weight(staker.tier);
+        
+        allocation.owner = ctx.accounts.requester.key();
+        allocation.launch = launch.key();
+        allocation.pool = pool;
+        allocation.requested_amount_usd = amount_usd;
+        allocation.weight = weight;
+        allocation.status = AllocationStatus::Pending;
+        allocation.requested_at = clock.unix_timestamp;
+        allocation.bump = ctx.bumps.allocation;
+
+        emit!(AllocationRequested {
+            owner: allocation.owner,
+            launch_id: launch.launch_id,
+            pool,
+            amount_usd,
+            weight,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Fulfill allocation (called by protocol after lottery/distribution)
+    pub fn fulfill_allocation(
+        ctx: Context<FulfillAllocation>,
+        allocated_tokens: u64,
+        vesting_cliff_days: u16,
+        vesting_duration_days: u16,
+        tge_unlock_bps: u16,
+    ) -> Result<()> {
+        let allocation = &mut ctx.accounts.allocation;
+        let clock = Clock::get()?;
+        
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.protocol.authority,
+            DiamondPadError::Unauthorized
+        );
+        
+        // Re-fulfilling replaces the schedule, so drop the old one first
+        let summary = &mut ctx.accounts.vesting_summary;
+        summary.open(allocation.owner, ctx.bumps.vesting_summary);
+        summary.untrack(allocation);
+        
+        allocation.allocated_tokens = allocated_tokens;
+        allocation.vesting_start = clock.unix_timestamp;
+        allocation.vesting_cliff_days = vesting_cliff_days;
+        allocation.vesting_duration_days = vesting_duration_days;
+        allocation.tge_unlock_bps = tge_unlock_bps;
+        allocation.tokens_claimed = 0;
+        allocation.status = if allocated_tokens > 0 {
+            AllocationStatus::Won
+        } else {
+            AllocationStatus::Lost
+        };
+
+        summary.track_schedule(allocation, clock.unix_timestamp);
+        if allocation.status == AllocationStatus::Won {
+            // Scheduled for the cliff, so wallets can remind the owner then
+            let unlocks_at =
+                allocation.vesting_start + allocation.vesting_cliff_days as i64 * 86400;
+            notify(
+                allocation.owner,
+                allocation.launch,
+                NotificationKind::VestingUnlock,
+                allocation.vested(unlocks_at),
+                unlocks_at,
+            );
+        }
+        summary.emit_update();
+
+        // A schedule for the creator is the dev allocation's vesting
+        if let Some(checklist) = ctx.accounts.checklist.as_mut() {
+            if allocation.status == AllocationStatus::Won && allocation.owner == checklist.creator {
+                checklist.complete(CHECKLIST_VESTING_FUNDED, clock.unix_timestamp);
+            }
+        }
+
+        emit!(AllocationFulfilled {
+            owner: allocation.owner,
+            launch: allocation.launch,
+            allocated_tokens,
+            status: allocation.status,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::FulfillAllocation,
+            allocation.owner,
+            AuditValue::None,
+            AuditValue::Amount(allocated_tokens),
+        )?;
+
+        Ok(())
+    }
+
+    /// Claim vested tokens from allocation
+    pub fn claim_allocation(ctx: Context<ClaimAllocation>) -> Result<()> {
+        let allocation = &mut ctx.accounts.allocation;
+        let clock = Clock::get()?;
+        
+        require!(
+            allocation.status == AllocationStatus::Won,
+            DiamondPadError::NoAllocation
+        );
+        
+        // Calculate claimable amount based on vesting, less any OTC buyer's share
+        let vested = allocation.vested(clock.unix_timestamp);
+        let claimable =
+            (vested - allocation.otc_vested(vested)).saturating_sub(allocation.tokens_claimed);
+        
+        require!(claimable > 0, DiamondPadError::NothingToClaim);
+        
+        let summary = &mut ctx.accounts.vesting_summary;
+        summary.open(allocation.owner, ctx.bumps.vesting_summary);
+        summary.untrack(allocation);
+        allocation.tokens_claimed = allocation.tokens_claimed.checked_add(claimable).unwrap();
+        summary.total_released = summary.total_released.checked_add(claimable).unwrap();
+        summary.track_schedule(allocation, clock.unix_timestamp);
+        summary.emit_update();
+        
+        // Token transfer would happen here via CPI
+        
+        emit!(AllocationClaimed {
+            owner: allocation.owner,
+            launch: allocation.launch,
+            claimed: claimable,
+            total_claimed: allocation.tokens_claimed,
+            remaining: (allocation.allocated_tokens - allocation.otc_tokens)
+                .saturating_sub(allocation.tokens_claimed + allocation.tokens_forfeited),
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Stop a vesting schedule where it stands. Whatever has vested can
+    /// still be claimed; the rest is forfeited and, never having been
+    /// minted, stays in the launch's dev bucket. An OTC buyer gets back the
+    /// price of their unvested share, so a sold schedule needs its sale
+    /// and buyer passed. Needs a council majority.
+    pub fn revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require_council_majority(&ctx.accounts.council, ctx.remaining_accounts, now)?;
+
+        let allocation = &mut ctx.accounts.allocation;
+        require!(
+            allocation.status == AllocationStatus::Won,
+            DiamondPadError::NoAllocation
+        );
+        require!(allocation.revoked_at == 0, DiamondPadError::VestingAlreadyRevoked);
+
+        let vested = calculate_vested_amount(
+            allocation.allocated_tokens,
+            allocation.vesting_start,
+            allocation.vesting_cliff_days,
+            allocation.vesting_duration_days,
+            allocation.tge_unlock_bps,
+            now,
+        );
+        let summary = &mut ctx.accounts.vesting_summary;
+        summary.open(allocation.owner, ctx.bumps.vesting_summary);
+        summary.untrack(allocation);
+        allocation.revoked_at = now;
+        allocation.tokens_forfeited = allocation.allocated_tokens - vested;
+        summary.track_schedule(allocation, now);
+        summary.emit_update();
+
+        let mut otc_refunded = 0;
+        if allocation.otc_tokens > 0 {
+            let sale = ctx.accounts.otc_sale.as_mut().ok_or(DiamondPadError::OtcSaleRequired)?;
+            let buyer = ctx.accounts.otc_buyer.as_ref().ok_or(DiamondPadError::OtcSaleRequired)?;
+            require_keys_eq!(buyer.key(), sale.buyer, DiamondPadError::NotOtcBuyer);
+            otc_refunded = sale.price - sale.earned(allocation.otc_vested(vested));
+            sale.refunded = otc_refunded;
+            sale.sub_lamports(otc_refunded)?;
+            buyer.add_lamports(otc_refunded)?;
+        }
+
+        emit!(VestingRevoked {
+            owner: allocation.owner,
+            launch: allocation.launch,
+            vested,
+            forfeited: allocation.tokens_forfeited,
+            schema_version: EVENT_SCHEMA_VERSION,
+            otc_refunded,
+        });
+        audit_admin_action(
+            ctx.accounts.submitter.key(),
+            AdminAction::RevokeVesting,
+            allocation.owner,
+            AuditValue::Amount(allocation.allocated_tokens),
+            AuditValue::Amount(vested),
+        )?;
+
+        Ok(())
+    }
+
+    /// Recompute the caller's vesting summary from their schedules, passed
+    /// in `remaining_accounts` sorted by address. Restores totals from
+    /// before the summary existed and cliffs `track_schedule` let go of.
+    // `Option::is_none_or` is newer than the SBF toolchain's rustc
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn rebuild_vesting_summary<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RebuildVestingSummary<'info>>,
+    ) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let now = Clock::get()?.unix_timestamp;
+        let summary = &mut ctx.accounts.vesting_summary;
+        summary.open(owner, ctx.bumps.vesting_summary);
+        summary.schedule_count = 0;
+        summary.total_locked = 0;
+        summary.total_released = 0;
+        summary.next_unlock_at = 0;
+        summary.fully_vested_at = 0;
+
+        let mut previous: Option<Pubkey> = None;
+        for info in ctx.remaining_accounts {
+            require!(
+                previous.map_or(true, |previous| previous < info.key()),
+                DiamondPadError::InvalidVestingSchedules
+            );
+            previous = Some(info.key());
+            let allocation = Account::<Allocation>::try_from(info)?;
+            require_keys_eq!(allocation.owner, owner, DiamondPadError::InvalidVestingSchedules);
+            if allocation.status == AllocationStatus::Won {
+                summary.total_released =
+                    summary.total_released.checked_add(allocation.tokens_claimed).unwrap();
+            }
+            summary.track_schedule(&allocation, now);
+        }
+        summary.emit_update();
+
+        Ok(())
+    }
+
+    /// Read an allocation's vesting progress from the owner's side, as
+    /// return data. Meant for simulation, so wallets can show progress
+    /// without reimplementing the schedule.
+    pub fn preview_vesting(ctx: Context<PreviewVesting>) -> Result<VestingPreview> {
+        let allocation = &ctx.accounts.allocation;
+        let now = Clock::get()?.unix_timestamp;
+        if allocation.status != AllocationStatus::Won {
+            return Ok(VestingPreview::default());
+        }
+
+        let vested = allocation.vested(now);
+        let owner_vested = vested - allocation.otc_vested(vested);
+        let total = if allocation.revoked_at == 0 {
+            allocation.allocated_tokens - allocation.otc_tokens
+        } else {
+            owner_vested
+        };
+        let cliff_end = allocation.vesting_start + allocation.vesting_cliff_days as i64 * 86400;
+
+        Ok(VestingPreview {
+            total,
+            released: allocation.tokens_claimed,
+            releasable_now: owner_vested.saturating_sub(allocation.tokens_claimed),
+            next_unlock_at: if allocation.revoked_at == 0 && cliff_end > now { cliff_end } else { 0 },
+        })
+    }
+
+    // ============ OTC Sales ============
+
+    /// Offer part of a launch creator's still-unvested allocation for sale.
+    /// The buyer pays up front and their tokens vest on the allocation's
+    /// own schedule from then on. `buyer` restricts who can take the
+    /// offer; the default key leaves it open.
+    pub fn list_otc_sale(
+        ctx: Context<ListOtcSale>,
+        tokens: u64,
+        price: u64,
+        buyer: Pubkey,
+    ) -> Result<()> {
+        let allocation = &ctx.accounts.allocation;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(
+            allocation.status == AllocationStatus::Won && allocation.revoked_at == 0,
+            DiamondPadError::NoAllocation
+        );
+        require!(
+            tokens > 0 && price > 0 && tokens <= allocation.allocated_tokens - allocation.vested(now),
+            DiamondPadError::InvalidOtcSale
+        );
+
+        let sale = &mut ctx.accounts.otc_sale;
+        sale.allocation = allocation.key();
+        sale.launch = allocation.launch;
+        sale.seller = allocation.owner;
+        sale.buyer = buyer;
+        sale.tokens = tokens;
+        sale.price = price;
+        sale.status = OtcSaleStatus::Listed;
+        sale.listed_at = now;
+        sale.bump = ctx.bumps.otc_sale;
+
+        emit!(OtcSaleListed {
+            launch: sale.launch,
+            allocation: sale.allocation,
+            seller: sale.seller,
+            buyer,
+            tokens,
+            price,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw an OTC offer nobody has taken
+    pub fn cancel_otc_sale(ctx: Context<CancelOtcSale>) -> Result<()> {
+        emit!(OtcSaleCancelled {
+            launch: ctx.accounts.otc_sale.launch,
+            allocation: ctx.accounts.otc_sale.allocation,
+            seller: ctx.accounts.seller.key(),
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Take an OTC offer: the tokens are carved out of the seller's
+    /// allocation and the price is held on the sale, released to the seller
+    /// as the buyer's tokens vest. If the council later revokes the
+    /// schedule, the price of the buyer's unvested share is refunded.
+    pub fn accept_otc_sale(ctx: Context<AcceptOtcSale>) -> Result<()> {
+        let sale = &mut ctx.accounts.otc_sale;
+        let allocation = &mut ctx.accounts.allocation;
+        let buyer = ctx.accounts.buyer.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(sale.status == OtcSaleStatus::Listed, DiamondPadError::OtcSaleNotOpen);
+        require!(
+            sale.buyer == Pubkey::default() || sale.buyer == buyer,
+            DiamondPadError::NotOtcBuyer
+        );
+        require!(allocation.revoked_at == 0, DiamondPadError::VestingAlreadyRevoked);
+
+        // Vesting has moved on since listing; what's sold must still be unvested
+        let vested = allocation.vested(now);
+        require!(
+            sale.tokens <= allocation.allocated_tokens - vested,
+            DiamondPadError::InvalidOtcSale
+        );
+
+        transfer_lamports(
+            ctx.accounts.buyer.to_account_info(),
+            sale.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            sale.price,
+            &[],
+        )?;
+
+        let summary = &mut ctx.accounts.vesting_summary;
+        summary.open(allocation.owner, ctx.bumps.vesting_summary);
+        summary.untrack(allocation);
+        allocation.otc_tokens = sale.tokens;
+        allocation.otc_vested_at_sale = vested;
+        summary.track_schedule(allocation, now);
+        summary.emit_update();
+        sale.buyer = buyer;
+        sale.status = OtcSaleStatus::Filled;
+        sale.filled_at = now;
+
+        emit!(OtcSaleFilled {
+            launch: sale.launch,
+            allocation: sale.allocation,
+            seller: sale.seller,
+            buyer,
+            tokens: sale.tokens,
+            price: sale.price,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Claim the part of a bought OTC allocation that has vested so far,
+    /// minted from the launch's dev bucket once the launch has graduated
+    pub fn claim_otc_tokens(ctx: Context<ClaimOtcTokens>) -> Result<()> {
+        let allocation = &ctx.accounts.allocation;
+        let now = Clock::get()?.unix_timestamp;
+
+        let claimable =
+            allocation.otc_vested(allocation.vested(now)) - ctx.accounts.otc_sale.tokens_claimed;
+        require!(claimable > 0, DiamondPadError::NothingToClaim);
+        ctx.accounts.launch.issue_supply(SupplyBucket::Dev, claimable)?;
+
+        let launch_key = ctx.accounts.launch.key();
+        let seeds = &[
+            b"launch_authority".as_ref(),
+            launch_key.as_ref(),
+            &[ctx.bumps.launch_authority],
+        ];
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::MintTo {
+                    mint: ctx.accounts.launch_mint.to_account_info(),
+                    to: ctx.accounts.buyer_token_account.to_account_info(),
+                    authority: ctx.accounts.launch_authority.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            claimable,
+        )?;
+
+        let sale = &mut ctx.accounts.otc_sale;
+        sale.tokens_claimed += claimable;
+
+        emit!(OtcTokensClaimed {
+            launch: sale.launch,
+            allocation: sale.allocation,
+            buyer: sale.buyer,
+            claimed: claimable,
+            total_claimed: sale.tokens_claimed,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Seller withdraws the part of an OTC price the buyer's vested tokens
+    /// have earned so far
+    pub fn withdraw_otc_proceeds(ctx: Context<WithdrawOtcProceeds>) -> Result<()> {
+        let allocation = &ctx.accounts.allocation;
+        let sale = &mut ctx.accounts.otc_sale;
+        let now = Clock::get()?.unix_timestamp;
+
+        let earned = sale.earned(allocation.otc_vested(allocation.vested(now)));
+        let amount = earned - sale.proceeds_withdrawn;
+        require!(amount > 0, DiamondPadError::NothingToClaim);
+
+        sale.proceeds_withdrawn = earned;
+        sale.sub_lamports(amount)?;
+        ctx.accounts.seller.add_lamports(amount)?;
+
+        emit!(OtcProceedsWithdrawn {
+            launch: sale.launch,
+            allocation: sale.allocation,
+            seller: sale.seller,
+            amount,
+            total_withdrawn: sale.proceeds_withdrawn,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Trading ============
+
+    /// Buy launch tokens from the bonding curve with SOL
+    pub fn buy(
+        ctx: Context<Buy>,
+        quote_amount: u64,
+        min_tokens_out: u64,
+    ) -> Result<()> {
+        require!(quote_amount > 0, DiamondPadError::InvalidAmount);
+
+        let launch = &mut ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(
+            quote_amount >= launch.config.min_contribution,
+            DiamondPadError::ContributionTooSmall
+        );
+
+        let screening = screen_buy(
+            launch,
+            &ctx.accounts.protocol,
+            &mut ctx.accounts.position,
+            &ctx.accounts.buyer.key(),
+            ctx.accounts.priority_launch.as_deref(),
+            ctx.accounts.priority_position.as_ref(),
+            ctx.accounts.holder_profile.as_ref(),
+            ctx.accounts.gateway_token.as_deref(),
+            ctx.accounts.wallet_risk.as_deref(),
+            &clock,
+        )?;
+
+        // Trade fee comes off the top; the curve prices the remainder
+        let BuyFee {
+            discount_bps: fee_discount_bps,
+            fee,
+            reward_fee,
+            protocol_fee,
+            channel_fee,
+        } = buy_fee(
+            launch,
+            &ctx.accounts.protocol,
+            ctx.accounts.channel.as_deref_mut(),
+            screening.proven_rank,
+            quote_amount,
+        )?;
+        let net_quote = quote_amount.checked_sub(fee).unwrap();
+
+        let tokens_out = launch
+            .config
+            .curve
+            .tokens_out(&launch.curve_state(), net_quote)
+            .ok_or(DiamondPadError::CurveQuoteFailed)?;
+        require!(tokens_out > 0, DiamondPadError::InvalidAmount);
+        require!(
+            launch.tokens_sold.checked_add(tokens_out).unwrap() <= launch.sale_supply,
+            DiamondPadError::SaleSupplyExhausted
+        );
+        require!(
+            launch.total_raised.checked_add(net_quote).unwrap() <= launch.hard_cap,
+            DiamondPadError::HardCapReached
+        );
+        require!(tokens_out >= min_tokens_out, DiamondPadError::SlippageExceeded);
+        require_within_position_cap(launch, ctx.accounts.position.balance, tokens_out)?;
+
+        // Net SOL into the launch vault, fee split between rewards and treasury
+        let buyer = ctx.accounts.buyer.to_account_info();
+        let system = ctx.accounts.system_program.to_account_info();
+        transfer_lamports(
+            buyer.clone(),
+            ctx.accounts.sol_vault.to_account_info(),
+            system.clone(),
+            net_quote,
+            &[],
+        )?;
+        transfer_lamports(
+            buyer.clone(),
+            ctx.accounts.reward_vault.to_account_info(),
+            system.clone(),
+            reward_fee,
+            &[],
+        )?;
+        if let Some(channel) = ctx.accounts.channel.as_ref() {
+            transfer_lamports(
+                buyer.clone(),
+                channel.to_account_info(),
+                system.clone(),
+                channel_fee,
+                &[],
+            )?;
+        }
+        transfer_lamports(
+            buyer,
+            ctx.accounts.treasury.to_account_info(),
+            system,
+            protocol_fee - channel_fee,
+            &[],
+        )?;
+
+        launch.virtual_sol_reserves = launch.virtual_sol_reserves.checked_add(net_quote).unwrap();
+        launch.virtual_token_reserves = launch.virtual_token_reserves.checked_sub(tokens_out).unwrap();
+        launch.tokens_sold = launch.tokens_sold.checked_add(tokens_out).unwrap();
+        launch.total_raised = launch.total_raised.checked_add(net_quote).unwrap();
+        launch.total_buy_volume = launch.total_buy_volume.checked_add(quote_amount).unwrap();
+        launch.reward_vault_funded = launch.reward_vault_funded.checked_add(reward_fee).unwrap();
+        distribute_rewards(launch, reward_fee);
+
+        launch.record_price(clock.unix_timestamp);
+        trip_circuit_breaker(launch, clock.unix_timestamp);
+        ctx.accounts.protocol_stats.record_trade(
+            TradeSide::Buy,
+            quote_amount,
+            fee,
+            reward_fee,
+            clock.unix_timestamp,
+        );
+
+        if launch.status == LaunchStatus::Pending {
+            launch.status = LaunchStatus::Active;
+        }
+
+        let position = &mut ctx.accounts.position;
+        track_wash_trading(position, launch, TradeSide::Buy, clock.unix_timestamp);
+        position.set_flag(POSITION_RISK_DEBOOSTED, screening.risk_deboosted);
+        if let Some(referral_code) = ctx.accounts.referral_code.as_ref() {
+            // Attribution sticks to the first code a wallet bought through
+            if position.referral_code == Pubkey::default()
+                && referral_code.owner != ctx.accounts.buyer.key()
+            {
+                position.referral_code = referral_code.key();
+            }
+        }
+        if let Some(stats) = ctx.accounts.referral_stats.as_mut() {
+            require_keys_eq!(
+                stats.referral_code,
+                position.referral_code,
+                DiamondPadError::ReferralStatsMismatch
+            );
+            // Tracked volume must reach the board or the bonus can't be claimed
+            let board = ctx
+                .accounts
+                .referral_board
+                .as_mut()
+                .ok_or(DiamondPadError::ReferralBoardRequired)?;
+            stats.referred_volume = stats.referred_volume.checked_add(quote_amount).unwrap();
+            stats.referred_buys += 1;
+            board.record(stats.referral_code, stats.referred_volume);
+        }
+        credit_position(
+            position,
+            launch,
+            ctx.accounts.buyer.key(),
+            ctx.bumps.position,
+            tokens_out,
+            clock.unix_timestamp,
+        );
+        position.last_buy_slot = clock.slot;
+
+        if let (Some(season), Some(score)) = (
+            ctx.accounts.season.as_mut(),
+            ctx.accounts.season_score.as_mut(),
+        ) {
+            require_keys_eq!(score.season, season.key(), DiamondPadError::SeasonEnded);
+            record_season_activity(
+                season,
+                score,
+                quote_amount,
+                position.multiplier_bps,
+                clock.unix_timestamp,
+            );
+        }
+
+        let contribution = &mut ctx.accounts.contribution;
+        // Bonus allocations can open a contribution before its first buy
+        if contribution.first_buy_slot == 0 {
+            launch.unique_buyers += 1;
+        }
+        if contribution.quote_contributed == 0 {
+            contribution.contributor = ctx.accounts.buyer.key();
+            contribution.launch = launch.key();
+            contribution.bump = ctx.bumps.contribution;
+        }
+        if contribution.first_buy_slot == 0 {
+            contribution.first_buy_slot = clock.slot;
+            contribution.funded_by =
+                screening.wallet_risk.map_or(Pubkey::default(), |risk| risk.funded_by);
+        }
+        contribution.quote_contributed = contribution.quote_contributed.checked_add(net_quote).unwrap();
+        contribution.tokens_allocated = contribution.tokens_allocated.checked_add(tokens_out).unwrap();
+        contribution.last_contribution_timestamp = clock.unix_timestamp;
+
+        emit!(TokensBought {
+            launch_id: launch.launch_id,
+            buyer: ctx.accounts.buyer.key(),
+            quote_amount,
+            tokens_out,
+            fee,
+            reward_fee,
+            virtual_sol_reserves: launch.virtual_sol_reserves,
+            virtual_token_reserves: launch.virtual_token_reserves,
+            schema_version: EVENT_SCHEMA_VERSION,
+            fee_discount_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Sell launch tokens back into the bonding curve for SOL
+    pub fn sell(
+        ctx: Context<Sell>,
+        token_amount: u64,
+        min_quote_out: u64,
+    ) -> Result<()> {
+        require!(token_amount > 0, DiamondPadError::InvalidAmount);
+        flashloan::require_no_buy_in_transaction(
+            &ctx.accounts.instructions_sysvar,
+            &ctx.accounts.launch.key(),
+        )?;
+
+        let launch = &mut ctx.accounts.launch;
+        let position = &mut ctx.accounts.position;
+        let clock = Clock::get()?;
+
+        position.consume_rate_limit(
+            ctx.accounts.protocol.max_position_actions_per_minute,
+            clock.unix_timestamp,
+        )?;
+        require!(launch.status == LaunchStatus::Active, DiamondPadError::LaunchNotOpen);
+        require!(!launch.is_paused(PAUSE_TRADING), DiamondPadError::TradingHalted);
+        require!(
+            clock.unix_timestamp >= launch.circuit_breaker_until,
+            DiamondPadError::CircuitBreakerActive
+        );
+        require!(position.balance >= token_amount, DiamondPadError::InsufficientBalance);
+        // Bonus allocations were never paid into the vault, so only tokens
+        // bought from the curve can be sold back into it
+        require!(
+            token_amount <= ctx.accounts.contribution.curve_tokens(),
+            DiamondPadError::BonusNotSellable
+        );
+        // No buying and selling through the curve in one slot (and so one
+        // transaction): closes off atomic sandwiches and wash round-trips
+        require!(clock.slot > position.last_buy_slot, DiamondPadError::SameSlotRoundTrip);
+
+        let gross_quote = launch
+            .config
+            .curve
+            .quote_out(&launch.curve_state(), token_amount)
+            .ok_or(DiamondPadError::CurveQuoteFailed)?;
+        require!(gross_quote <= launch.total_raised, DiamondPadError::InsufficientLiquidity);
+
+        let fee = calculate_trade_fee(gross_quote, ctx.accounts.protocol.trade_fee_bps);
+        let quote_out = gross_quote.checked_sub(fee).unwrap();
+        let (reward_fee, protocol_fee) = split_trade_fee(launch, fee);
+        let channel_fee = accrue_channel_fee(launch, ctx.accounts.channel.as_mut(), protocol_fee)?;
+        require!(quote_out > 0, DiamondPadError::InvalidAmount);
+        require!(quote_out >= min_quote_out, DiamondPadError::SlippageExceeded);
+
+        launch.virtual_sol_reserves = launch.virtual_sol_reserves.checked_sub(gross_quote).unwrap();
+        launch.virtual_token_reserves = launch.virtual_token_reserves.checked_add(token_amount).unwrap();
+        launch.tokens_sold = launch.tokens_sold.checked_sub(token_amount).unwrap();
+        launch.total_raised = launch.total_raised.checked_sub(gross_quote).unwrap();
+        launch.total_sell_volume = launch.total_sell_volume.checked_add(gross_quote).unwrap();
+        launch.reward_vault_funded = launch.reward_vault_funded.checked_add(reward_fee).unwrap();
+        distribute_rewards(launch, reward_fee);
+
+        launch.record_price(clock.unix_timestamp);
+        trip_circuit_breaker(launch, clock.unix_timestamp);
+        ctx.accounts.protocol_stats.record_trade(
+            TradeSide::Sell,
+            gross_quote,
+            fee,
+            reward_fee,
+            clock.unix_timestamp,
+        );
+
+        let contribution = &mut ctx.accounts.contribution;
+        contribution.quote_contributed = contribution.quote_contributed.saturating_sub(quote_out);
+        contribution.tokens_allocated =
+            contribution.tokens_allocated.checked_sub(token_amount).unwrap();
+
+        let launch_key = launch.key();
+        let seeds = &[b"sol_vault".as_ref(), launch_key.as_ref(), &[ctx.bumps.sol_vault]];
+        let signer = &[&seeds[..]];
+
+        let vault = ctx.accounts.sol_vault.to_account_info();
+        let system = ctx.accounts.system_program.to_account_info();
+        transfer_lamports(
+            vault.clone(),
+            ctx.accounts.seller.to_account_info(),
+            system.clone(),
+            quote_out,
+            signer,
+        )?;
+        transfer_lamports(
+            vault.clone(),
+            ctx.accounts.reward_vault.to_account_info(),
+            system.clone(),
+            reward_fee,
+            signer,
+        )?;
+        if let Some(channel) = ctx.accounts.channel.as_ref() {
+            transfer_lamports(
+                vault.clone(),
+                channel.to_account_info(),
+                system.clone(),
+                channel_fee,
+                signer,
+            )?;
+        }
+        transfer_lamports(
+            vault,
+            ctx.accounts.treasury.to_account_info(),
+            system,
+            protocol_fee - channel_fee,
+            signer,
+        )?;
+
+        track_wash_trading(position, launch, TradeSide::Sell, clock.unix_timestamp);
+        let rank_reset = debit_position(position, launch, token_amount, clock.unix_timestamp);
+
+        emit!(TokensSold {
+            launch_id: launch.launch_id,
+            seller: ctx.accounts.seller.key(),
+            token_amount,
+            quote_out,
+            fee,
+            reward_fee,
+            rank_reset,
+            virtual_sol_reserves: launch.virtual_sol_reserves,
+            virtual_token_reserves: launch.virtual_token_reserves,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Rewards ============
+
+    /// Credit a referrer once the buyer they referred reaches Bronze or above.
+    /// Permissionless: the rank check is against on-chain position state.
+    /// The credit counts toward the referrer's boost on this launch only.
+    pub fn qualify_referral(ctx: Context<QualifyReferral>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        let referral_code = &mut ctx.accounts.referral_code;
+
+        require!(
+            !position.has_flag(POSITION_REFERRAL_QUALIFIED),
+            DiamondPadError::ReferralAlreadyQualified
+        );
+        require!(position.balance > 0, DiamondPadError::ReferralNotQualified);
+        let now = Clock::get()?.unix_timestamp;
+        let threshold_days = &ctx.accounts.launch.rank_thresholds();
+        let rank = calculate_diamond_rank(position.rank_since(), now, threshold_days);
+        require!(rank != DiamondRank::Paper, DiamondPadError::ReferralNotQualified);
+
+        position.set_flag(POSITION_REFERRAL_QUALIFIED, true);
+        referral_code.qualified_referrals += 1;
+        let stats = &mut ctx.accounts.referral_stats;
+        stats.qualified_referrals += 1;
+
+        emit!(ReferralQualified {
+            code: referral_code.code.clone(),
+            referred: position.holder,
+            launch: position.launch,
+            qualified_referrals: referral_code.qualified_referrals,
+            schema_version: EVENT_SCHEMA_VERSION,
+            launch_qualified_referrals: stats.qualified_referrals,
+        });
+
+        Ok(())
+    }
+
+    /// Claim accrued holder rewards from the launch's reward vault. Passing
+    /// the holder's LiquidityPosition refreshes their hold-plus-LP boost.
+    /// Rewards go to `destination`: the holder, or any wallet linked to them
+    /// in their HolderProfile (e.g. a cold wallet). Claiming in the launch
+    /// token instead buys through the curve at claim time, fee-free, bounded
+    /// by `min_tokens_out`.
+    pub fn claim_rewards(
+        ctx: Context<ClaimRewards>,
+        denomination: RewardDenomination,
+        min_tokens_out: u64,
+    ) -> Result<()> {
+        process_claim(ctx.accounts, &ctx.bumps, denomination, min_tokens_out)?;
+        Ok(())
+    }
+
+    /// Claim rewards to the holder and swap them through Jupiter in the
+    /// same instruction, so rewards from many launches can be consolidated
+    /// into one asset. The route (data plus remaining accounts) comes from
+    /// the Jupiter API; `min_amount_out` is checked against what actually
+    /// lands in `output_token_account`.
+    pub fn claim_and_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimAndSwap<'info>>,
+        route_data: Vec<u8>,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.claim.destination.key(),
+            ctx.accounts.claim.holder.key(),
+            DiamondPadError::InvalidRewardDestination
+        );
+
+        let claimed = process_claim(
+            &mut ctx.accounts.claim,
+            &ctx.bumps.claim,
+            RewardDenomination::Quote,
+            0,
+        )?;
+
+        let balance_before = ctx.accounts.output_token_account.amount;
+        jupiter::swap(
+            &ctx.accounts.jupiter_program.to_account_info(),
+            ctx.remaining_accounts,
+            route_data,
+        )?;
+        ctx.accounts.output_token_account.reload()?;
+        let received = ctx
+            .accounts
+            .output_token_account
+            .amount
+            .saturating_sub(balance_before);
+        require!(received >= min_amount_out, DiamondPadError::SlippageExceeded);
+
+        emit!(RewardsSwapped {
+            launch_id: ctx.accounts.claim.launch.launch_id,
+            holder: ctx.accounts.claim.holder.key(),
+            claimed,
+            output_mint: ctx.accounts.output_token_account.mint,
+            received,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Creator turns claim gas sponsorship on or off: each claim refunds
+    /// the holder up to `refund_per_claim` lamports from the launch's gas
+    /// pool, and no more than `cap_per_epoch` per wallet per reward epoch
+    pub fn set_gas_sponsorship(
+        ctx: Context<UpdateLaunchTerms>,
+        refund_per_claim: u64,
+        cap_per_epoch: u64,
+    ) -> Result<()> {
+        require!(
+            refund_per_claim <= MAX_GAS_REFUND_PER_CLAIM && cap_per_epoch >= refund_per_claim,
+            DiamondPadError::InvalidGasSponsorship
+        );
+
+        let launch = &mut ctx.accounts.launch;
+        launch.gas_refund_per_claim = refund_per_claim;
+        launch.gas_refund_cap_per_epoch = cap_per_epoch;
+
+        emit!(GasSponsorshipUpdated {
+            launch_id: launch.launch_id,
+            refund_per_claim,
+            cap_per_epoch,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Top up a launch's gas pool; anyone can sponsor
+    pub fn fund_gas_pool(ctx: Context<FundGasPool>, amount: u64) -> Result<()> {
+        require!(amount > 0, DiamondPadError::InvalidAmount);
+        transfer_lamports(
+            ctx.accounts.funder.to_account_info(),
+            ctx.accounts.gas_pool.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            amount,
+            &[],
+        )?;
+
+        emit!(GasPoolFunded {
+            launch_id: ctx.accounts.launch.launch_id,
+            funder: ctx.accounts.funder.key(),
+            amount,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Estimate the reward APR a position of `balance` tokens at `rank`
+    /// would earn on a launch, as return data for frontends to simulate.
+    /// Assumes each epoch for a year emits what one does now (the epoch
+    /// budget out of the pot, or the last epoch's emission when uncapped),
+    /// shared with the launch's current weight, and values the position at
+    /// the last recorded price. Claim boosts aren't included.
+    pub fn preview_apr(
+        ctx: Context<PreviewApr>,
+        rank: DiamondRank,
+        balance: u64,
+    ) -> Result<AprPreview> {
+        let launch = &ctx.accounts.launch;
+
+        let per_epoch = match launch.config.reward_epoch_budget {
+            0 => launch.last_epoch_emission,
+            budget => budget.min(launch.undistributed_rewards),
+        };
+        let weight = balance as u128 * get_diamond_multiplier_bps(rank) as u128 / 10000;
+        if weight == 0 {
+            return Ok(AprPreview::default());
+        }
+        let pool_weight = launch.total_reward_weight + launch.pending_reward_weight + weight;
+
+        let epochs_per_year = (365 * 86400 / REWARD_EPOCH_SECONDS) as u128;
+        let yearly_rewards = per_epoch as u128 * epochs_per_year * weight / pool_weight;
+        let value = launch
+            .price_history
+            .latest()
+            .map_or(0, |observation| balance as u128 * observation.price as u128 / PRICE_PRECISION);
+
+        Ok(AprPreview {
+            yearly_rewards: yearly_rewards.min(u64::MAX as u128) as u64,
+            apr_bps: match value {
+                0 => 0,
+                value => (yearly_rewards * 10000 / value).min(u64::MAX as u128) as u64,
+            },
+        })
+    }
+
+    // ============ Quests ============
+
+    /// Define a quest on a launch and fund its reward budget. The protocol
+    /// authority or the launch creator can create quests; the lamports sit
+    /// in the quest account until paid out or reclaimed.
+    pub fn create_quest(
+        ctx: Context<CreateQuest>,
+        quest_id: u32,
+        condition: QuestCondition,
+        reward: u64,
+        budget: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        let creator = ctx.accounts.creator.key();
+        require!(
+            creator == ctx.accounts.protocol.authority || creator == ctx.accounts.launch.creator,
+            DiamondPadError::Unauthorized
+        );
+        require!(reward > 0 && budget >= reward, DiamondPadError::InvalidQuest);
+        if let QuestCondition::HoldFor { min_balance, days } = condition {
+            require!(min_balance > 0 && days > 0, DiamondPadError::InvalidQuest);
+        }
+        let now = Clock::get()?.unix_timestamp;
+        require!(expires_at == 0 || expires_at > now, DiamondPadError::InvalidQuest);
+
+        transfer_lamports(
+            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.quest.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            budget,
+            &[],
+        )?;
+
+        let quest = &mut ctx.accounts.quest;
+        quest.launch = ctx.accounts.launch.key();
+        quest.creator = creator;
+        quest.quest_id = quest_id;
+        quest.condition = condition;
+        quest.reward = reward;
+        quest.budget_remaining = budget;
+        quest.completions = 0;
+        quest.expires_at = expires_at;
+        quest.bump = ctx.bumps.quest;
+
+        emit!(QuestCreated {
+            quest: quest.key(),
+            launch: quest.launch,
+            creator,
+            condition,
+            reward,
+            budget,
+            expires_at,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Complete a quest: the holder's position must meet the condition now,
+    /// and each holder can complete each quest once
+    pub fn complete_quest(ctx: Context<CompleteQuest>) -> Result<()> {
+        let quest = &mut ctx.accounts.quest;
+        let launch = &ctx.accounts.launch;
+        let position = &ctx.accounts.position;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(
+            quest.expires_at == 0 || now < quest.expires_at,
+            DiamondPadError::QuestExpired
+        );
+        require!(
+            quest.budget_remaining >= quest.reward,
+            DiamondPadError::QuestBudgetExhausted
+        );
+
+        let met = !position.has_flag(POSITION_INSIDER)
+            && position.balance > 0
+            && match quest.condition {
+                QuestCondition::HoldFor { min_balance, days } => {
+                    position.balance >= min_balance
+                        && now - position.rank_since() >= days as i64 * 86400
+                }
+                QuestCondition::ReachRankBeforeGraduation { rank } => {
+                    let cutoff = if launch.status == LaunchStatus::Graduated {
+                        launch.graduated_at
+                    } else {
+                        now
+                    };
+                    let threshold_days = &launch.rank_thresholds();
+                    calculate_diamond_rank(position.rank_since(), cutoff, threshold_days) >= rank
+                }
+            };
+        require!(met, DiamondPadError::QuestConditionNotMet);
+
+        quest.sub_lamports(quest.reward)?;
+        ctx.accounts.holder.add_lamports(quest.reward)?;
+        quest.budget_remaining -= quest.reward;
+        quest.completions += 1;
+
+        let completion = &mut ctx.accounts.completion;
+        completion.quest = quest.key();
+        completion.holder = ctx.accounts.holder.key();
+        completion.completed_at = now;
+        completion.bump = ctx.bumps.completion;
+
+        emit!(QuestCompleted {
+            quest: quest.key(),
+            holder: completion.holder,
+            reward: quest.reward,
+            budget_remaining: quest.budget_remaining,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Close an expired or exhausted quest, returning what's left of the
+    /// budget (and the rent) to whoever funded it
+    pub fn close_quest(ctx: Context<CloseQuest>) -> Result<()> {
+        let quest = &ctx.accounts.quest;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            (quest.expires_at != 0 && now >= quest.expires_at)
+                || quest.budget_remaining < quest.reward,
+            DiamondPadError::QuestStillActive
+        );
+
+        emit!(QuestClosed {
+            quest: quest.key(),
+            completions: quest.completions,
+            refunded: quest.budget_remaining,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Graduation ============
+
+    /// Close the bonding phase once the sale has (nearly) sold out and
+    /// record the DEX pool the liquidity was migrated to
+    pub fn graduate_launch(ctx: Context<GraduateLaunch>, pool_token_reserve: u64) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(launch.status == LaunchStatus::Active, DiamondPadError::LaunchNotOpen);
+        let sold_out = (launch.tokens_sold as u128) * 10000
+            >= (launch.sale_supply as u128) * GRADUATION_SOLD_BPS as u128;
+        require!(
+            sold_out || launch.total_raised >= launch.hard_cap,
+            DiamondPadError::GraduationNotReached
+        );
+        require!(pool_token_reserve > 0, DiamondPadError::InvalidAmount);
+        launch.issue_supply(SupplyBucket::Liquidity, pool_token_reserve)?;
+
+        launch.status = LaunchStatus::Graduated;
+        launch.graduated_at = clock.unix_timestamp;
+        launch.claim_deadline = clock.unix_timestamp + SALE_CLAIM_WINDOW_DAYS * 86400;
+        launch.lp_mint = ctx.accounts.lp_mint.key();
+        launch.lp_pool_token_reserve = pool_token_reserve;
+        launch.lp_supply_at_graduation = ctx.accounts.lp_mint.supply;
+        // Bonus allocations credited before graduation already drew on the
+        // airdrop bucket; the graduation airdrop gets what they left
+        launch.airdrop_reserve = launch.supply.remaining(SupplyBucket::Airdrop);
+
+        // Part of the raise goes back to holders through the reward pot
+        let topup = ((launch.total_raised as u128)
+            * launch.config.graduation_reward_bps as u128
+            / 10000) as u64;
+        let vault = &ctx.accounts.sol_vault;
+        let topup = topup.min(withdrawable_lamports(vault)?);
+        let launch_key = launch.key();
+        let vault_seeds = &[b"sol_vault".as_ref(), launch_key.as_ref(), &[ctx.bumps.sol_vault]];
+        transfer_lamports(
+            vault.to_account_info(),
+            ctx.accounts.reward_vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            topup,
+            &[vault_seeds],
+        )?;
+        launch.graduation_reward_topup = topup;
+        launch.reward_vault_funded = launch.reward_vault_funded.checked_add(topup).unwrap();
+        distribute_rewards(launch, topup);
+
+        emit!(LaunchGraduated {
+            launch_id: launch.launch_id,
+            lp_mint: launch.lp_mint,
+            total_raised: launch.total_raised,
+            tokens_sold: launch.tokens_sold,
+            timestamp: clock.unix_timestamp,
+            schema_version: EVENT_SCHEMA_VERSION,
+            reward_topup: topup,
+        });
+        notify(
+            Pubkey::default(),
+            launch.key(),
+            NotificationKind::Graduation,
+            launch.total_raised,
+            clock.unix_timestamp,
+        );
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::GraduateLaunch,
+            launch.key(),
+            AuditValue::None,
+            AuditValue::Key(launch.lp_mint),
+        )?;
+
+        if let Some(checklist) = ctx.accounts.checklist.as_mut() {
+            if launch.lp_lock_in_force(clock.unix_timestamp) {
+                checklist.complete(CHECKLIST_LP_LOCKED, clock.unix_timestamp);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Commit the graduation airdrop: a merkle root over the top holders by
+    /// rank × balance at graduation, computed off-chain from Position
+    /// accounts, splitting the supply reserved at graduation. Set once.
+    pub fn commit_graduation_airdrop(
+        ctx: Context<CommitGraduationAirdrop>,
+        root: [u8; 32],
+        recipients: u16,
+    ) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        require!(launch.airdrop_reserve > 0, DiamondPadError::NoGraduationAirdrop);
+        require!(launch.airdrop_root == [0; 32], DiamondPadError::AirdropAlreadyCommitted);
+        require!(
+            root != [0; 32]
+                && recipients > 0
+                && recipients <= launch.config.graduation_airdrop_recipients,
+            DiamondPadError::InvalidAirdropCommitment
+        );
+
+        launch.airdrop_root = root;
+
+        emit!(GraduationAirdropCommitted {
+            launch_id: launch.launch_id,
+            root,
+            recipients,
+            reserve: launch.airdrop_reserve,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::CommitGraduationAirdrop,
+            launch.key(),
+            AuditValue::None,
+            AuditValue::Amount(recipients as u64),
+        )
+    }
+
+    /// Claim a graduation airdrop allocation. The tokens are owed on the
+    /// holder's contribution and minted by `claim_tokens`; the position
+    /// counts them at once.
+    pub fn claim_graduation_airdrop(
+        ctx: Context<ClaimGraduationAirdrop>,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let holder = ctx.accounts.holder.key();
+        require!(launch.airdrop_root != [0; 32], DiamondPadError::NoGraduationAirdrop);
+        require!(
+            merkle::verify(&proof, &launch.airdrop_root, merkle::leaf(&holder, amount)),
+            DiamondPadError::InvalidAirdropProof
+        );
+        let claimed = launch.airdrop_claimed.checked_add(amount).unwrap();
+        require!(claimed <= launch.airdrop_reserve, DiamondPadError::InvalidAirdropProof);
+        launch.airdrop_claimed = claimed;
+        launch.issue_supply(SupplyBucket::Airdrop, amount)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let receipt = &mut ctx.accounts.airdrop_claim;
+        receipt.holder = holder;
+        receipt.launch = launch.key();
+        receipt.amount = amount;
+        receipt.claimed_at = now;
+        receipt.bump = ctx.bumps.airdrop_claim;
+
+        credit_bonus_allocation(
+            &mut ctx.accounts.contribution,
+            launch.key(),
+            holder,
+            ctx.bumps.contribution,
+            amount,
+        );
+        let position = &mut ctx.accounts.position;
+        let bump = position.bump;
+        credit_position(position, launch, holder, bump, amount, now);
+
+        emit!(GraduationAirdropClaimed {
+            launch_id: launch.launch_id,
+            holder,
+            amount,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Open positions for a list of holders in one go, e.g. to seed an
+    /// airdropped community with their original entry times. Each entry's
+    /// position and contribution PDAs come in `remaining_accounts` as
+    /// pairs, in order; the position must not exist yet. Balances are drawn
+    /// from the airdrop bucket and owed on the contribution, the same way
+    /// `claim_graduation_airdrop` backs its credit, so they can't be sold
+    /// back into the curve. Insiders can't be seeded.
+    pub fn seed_positions<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SeedPositions<'info>>,
+        entries: Vec<SeedPosition>,
+    ) -> Result<()> {
+        require!(
+            !entries.is_empty()
+                && entries.len() <= MAX_SEED_POSITIONS
+                && entries.len() * 2 == ctx.remaining_accounts.len(),
+            DiamondPadError::InvalidSeedPositions
+        );
+
+        let launch = &mut ctx.accounts.launch;
+        require!(
+            launch.status == LaunchStatus::Pending || launch.status == LaunchStatus::Active,
+            DiamondPadError::LaunchNotOpen
+        );
+        let launch_key = launch.key();
+        let now = Clock::get()?.unix_timestamp;
+        let creator = ctx.accounts.creator.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let mut total_balance: u64 = 0;
+
+        for (entry, accounts) in entries.iter().zip(ctx.remaining_accounts.chunks(2)) {
+            let (info, contribution_info) = (&accounts[0], &accounts[1]);
+            require!(
+                entry.balance > 0
+                    && entry.entry_timestamp >= launch.created_at
+                    && entry.entry_timestamp <= now,
+                DiamondPadError::InvalidSeedPositions
+            );
+            require!(!launch.is_insider(&entry.holder), DiamondPadError::InsiderNotEligible);
+            let (address, bump) = Pubkey::find_program_address(
+                &[b"position", launch_key.as_ref(), entry.holder.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(info.key(), address, DiamondPadError::InvalidSeedPositions);
+            require!(info.data_is_empty(), DiamondPadError::PositionAlreadyExists);
+            let (contribution_address, contribution_bump) = Pubkey::find_program_address(
+                &[b"contribution", launch_key.as_ref(), entry.holder.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(
+                contribution_info.key(),
+                contribution_address,
+                DiamondPadError::InvalidSeedPositions
+            );
+
+            create_pda_account(
+                &creator,
+                info,
+                &system_program,
+                Position::SIZE,
+                &[b"position", launch_key.as_ref(), entry.holder.as_ref(), &[bump]],
+            )?;
+            let mut contribution = if contribution_info.data_is_empty() {
+                create_pda_account(
+                    &creator,
+                    contribution_info,
+                    &system_program,
+                    Contribution::SIZE,
+                    &[
+                        b"contribution",
+                        launch_key.as_ref(),
+                        entry.holder.as_ref(),
+                        &[contribution_bump],
+                    ],
+                )?;
+                Account::<Contribution>::try_from_unchecked(contribution_info)?
+            } else {
+                Account::<Contribution>::try_from(contribution_info)?
+            };
+
+            launch.issue_supply(SupplyBucket::Airdrop, entry.balance)?;
+            credit_bonus_allocation(
+                &mut contribution,
+                launch_key,
+                entry.holder,
+                contribution_bump,
+                entry.balance,
+            );
+            contribution.exit(&crate::ID)?;
+            let mut position = Account::<Position>::try_from_unchecked(info)?;
+            credit_position_since(
+                &mut position,
+                launch,
+                entry.holder,
+                bump,
+                entry.balance,
+                entry.entry_timestamp,
+                now,
+            );
+            position.exit(&crate::ID)?;
+            total_balance = total_balance.checked_add(entry.balance).unwrap();
+        }
+
+        emit!(PositionsSeeded {
+            launch_id: launch.launch_id,
+            count: entries.len() as u16,
+            total_balance,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Commit the holder list of the token this launch relaunches: a merkle
+    /// root of `(holder, balance, hold_start)` taken from the old token.
+    /// Set once.
+    pub fn commit_position_import(ctx: Context<UpdateLaunchTerms>, root: [u8; 32]) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        require!(
+            launch.status == LaunchStatus::Pending || launch.status == LaunchStatus::Active,
+            DiamondPadError::LaunchNotOpen
+        );
+        require!(launch.import_root == [0; 32], DiamondPadError::ImportAlreadyCommitted);
+        require!(root != [0; 32], DiamondPadError::InvalidImportProof);
+
+        launch.import_root = root;
+
+        emit!(PositionImportCommitted {
+            launch_id: launch.launch_id,
+            root,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Carry a holding of the relaunched token over into this launch before
+    /// it graduates. The balance is drawn from the airdrop bucket and owed
+    /// on the holder's contribution like `seed_positions`, so it can't be
+    /// sold back into the curve, and the position counts as held
+    /// since the old token's `hold_start`, so long-time holders keep the
+    /// rank they earned. The start is clamped to at most
+    /// `MAX_IMPORT_HOLD_DAYS` before the launch was created and no later
+    /// than its creation. A position that's already open keeps whichever
+    /// start is earlier. Insiders can't import.
+    pub fn import_position(
+        ctx: Context<ImportPosition>,
+        balance: u64,
+        hold_start: i64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let holder = ctx.accounts.holder.key();
+        let now = Clock::get()?.unix_timestamp;
+        require!(launch.import_root != [0; 32], DiamondPadError::NoPositionImport);
+        // After graduation the rest of the airdrop bucket is the graduation
+        // airdrop's reserve
+        require!(
+            launch.status == LaunchStatus::Pending || launch.status == LaunchStatus::Active,
+            DiamondPadError::LaunchNotOpen
+        );
+        require!(
+            balance > 0
+                && hold_start > 0
+                && hold_start <= now
+                && merkle::verify(
+                    &proof,
+                    &launch.import_root,
+                    merkle::import_leaf(&holder, balance, hold_start)
+                ),
+            DiamondPadError::InvalidImportProof
+        );
+        require!(!launch.is_insider(&holder), DiamondPadError::InsiderNotEligible);
+        let hold_start = hold_start.clamp(
+            launch.created_at - MAX_IMPORT_HOLD_DAYS * 86400,
+            launch.created_at,
+        );
+        launch.issue_supply(SupplyBucket::Airdrop, balance)?;
+        credit_bonus_allocation(
+            &mut ctx.accounts.contribution,
+            launch.key(),
+            holder,
+            ctx.bumps.contribution,
+            balance,
+        );
+
+        let receipt = &mut ctx.accounts.position_import;
+        receipt.holder = holder;
+        receipt.launch = launch.key();
+        receipt.balance = balance;
+        receipt.hold_start = hold_start;
+        receipt.imported_at = now;
+        receipt.bump = ctx.bumps.position_import;
+
+        let position = &mut ctx.accounts.position;
+        if position.balance > 0 {
+            position.first_buy_timestamp = position.first_buy_timestamp.min(hold_start as u32);
+        }
+        credit_position_since(
+            position,
+            launch,
+            holder,
+            ctx.bumps.position,
+            balance,
+            hold_start,
+            now,
+        );
+
+        emit!(PositionImported {
+            launch_id: launch.launch_id,
+            holder,
+            balance,
+            hold_start,
+            diamond_rank: position.diamond_rank,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Commit a snapshot of holders of one of the creator's earlier
+    /// graduated launches: a merkle root of `(holder, amount)` bonus
+    /// allocations in this launch, computed off-chain from the source
+    /// launch's positions. Set once.
+    pub fn commit_loyalty_snapshot(
+        ctx: Context<CommitLoyaltySnapshot>,
+        root: [u8; 32],
+    ) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        require!(
+            launch.status == LaunchStatus::Pending || launch.status == LaunchStatus::Active,
+            DiamondPadError::LaunchNotOpen
+        );
+        require!(
+            launch.loyalty_root == [0; 32],
+            DiamondPadError::LoyaltySnapshotAlreadyCommitted
+        );
+        require!(root != [0; 32], DiamondPadError::InvalidLoyaltyProof);
+
+        let source = &ctx.accounts.source_launch;
+        launch.loyalty_source = source.key();
+        launch.loyalty_root = root;
+
+        emit!(LoyaltySnapshotCommitted {
+            launch_id: launch.launch_id,
+            source_launch_id: source.launch_id,
+            root,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Claim a loyalty bonus into the holder's position while the launch
+    /// is still open. Like the graduation airdrop it's drawn from the
+    /// airdrop bucket and owed on the holder's contribution until
+    /// `claim_tokens`; it counts for rank but can't be sold back into the
+    /// curve, which it never paid into. The holder
+    /// must still hold a position in the source launch opened before it
+    /// graduated, and can't be an insider.
+    pub fn claim_loyalty_allocation(
+        ctx: Context<ClaimLoyaltyAllocation>,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let holder = ctx.accounts.holder.key();
+        require!(launch.loyalty_root != [0; 32], DiamondPadError::NoLoyaltySnapshot);
+        require!(
+            amount > 0
+                && merkle::verify(&proof, &launch.loyalty_root, merkle::leaf(&holder, amount)),
+            DiamondPadError::InvalidLoyaltyProof
+        );
+        require!(
+            launch.status == LaunchStatus::Pending || launch.status == LaunchStatus::Active,
+            DiamondPadError::LaunchNotOpen
+        );
+        require!(!launch.is_insider(&holder), DiamondPadError::InsiderNotEligible);
+        let source_position = &ctx.accounts.source_position;
+        require!(
+            source_position.balance > 0
+                && (source_position.first_buy_timestamp as i64)
+                    < ctx.accounts.source_launch.graduated_at,
+            DiamondPadError::NotLoyalHolder
+        );
+        launch.issue_supply(SupplyBucket::Airdrop, amount)?;
+        credit_bonus_allocation(
+            &mut ctx.accounts.contribution,
+            launch.key(),
+            holder,
+            ctx.bumps.contribution,
+            amount,
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let receipt = &mut ctx.accounts.loyalty_claim;
+        receipt.holder = holder;
+        receipt.launch = launch.key();
+        receipt.amount = amount;
+        receipt.claimed_at = now;
+        receipt.bump = ctx.bumps.loyalty_claim;
+
+        let position = &mut ctx.accounts.position;
+        credit_position(position, launch, holder, ctx.bumps.position, amount, now);
+
+        emit!(LoyaltyAllocationClaimed {
+            launch_id: launch.launch_id,
+            source_launch: launch.loyalty_source,
+            holder,
+            amount,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Deposit LP tokens for a graduated launch to build an LP diamond rank
+    pub fn deposit_lp(ctx: Context<DepositLp>, amount: u64) -> Result<()> {
+        require!(amount > 0, DiamondPadError::InvalidAmount);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.provider_lp_account.to_account_info(),
+            to: ctx.accounts.lp_vault.to_account_info(),
+            authority: ctx.accounts.provider.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let lp_position = &mut ctx.accounts.lp_position;
+        if lp_position.lp_amount == 0 {
+            lp_position.provider = ctx.accounts.provider.key();
+            lp_position.launch = ctx.accounts.launch.key();
+            lp_position.first_deposit_timestamp = now;
+            lp_position.bump = ctx.bumps.lp_position;
+        }
+        lp_position.lp_amount = lp_position.lp_amount.checked_add(amount).unwrap();
+        lp_position.last_activity_timestamp = now;
+        refresh_lp_rank(lp_position, &ctx.accounts.launch, now);
+
+        emit!(LiquidityPositionUpdated {
+            provider: lp_position.provider,
+            launch: lp_position.launch,
+            lp_amount: lp_position.lp_amount,
+            diamond_rank: lp_position.diamond_rank,
+            multiplier_bps: lp_position.multiplier_bps,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw deposited LP tokens; the launch's sell policy applies to the
+    /// LP rank. The provider's holder position, if any, gives up whatever
+    /// LP boost the withdrawn LP no longer covers.
+    pub fn withdraw_lp(ctx: Context<WithdrawLp>, amount: u64) -> Result<()> {
+        let launch = &ctx.accounts.launch;
+        let lp_position = &mut ctx.accounts.lp_position;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(amount > 0, DiamondPadError::InvalidAmount);
+        require!(lp_position.lp_amount >= amount, DiamondPadError::InsufficientBalance);
+
+        let seeds = &[b"protocol".as_ref(), &[ctx.accounts.protocol.bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.lp_vault.to_account_info(),
+            to: ctx.accounts.provider_lp_account.to_account_info(),
+            authority: ctx.accounts.protocol.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token::transfer(cpi_ctx, amount)?;
+
+        match launch.config.sell_policy {
+            SellPolicy::ResetToPaper => lp_position.first_deposit_timestamp = now,
+            SellPolicy::ProportionalAging => {
+                let held_for = (now - lp_position.first_deposit_timestamp).max(0) as u128;
+                let shift = held_for * amount as u128 / lp_position.lp_amount as u128;
+                lp_position.first_deposit_timestamp += shift as i64;
+            }
+        }
+        lp_position.lp_amount -= amount;
+        lp_position.last_activity_timestamp = now;
+        refresh_lp_rank(lp_position, &ctx.accounts.launch, now);
+
+        // The boost was sized on claim; resync it now so the same LP can't
+        // boost this position and then another wallet's
+        let position_info = ctx.accounts.position.to_account_info();
+        if !position_info.data_is_empty() {
+            let mut data = position_info.try_borrow_mut_data()?;
+            let mut position = Position::try_deserialize(&mut &data[..])?;
+            let launch = &mut ctx.accounts.launch;
+            settle_rewards(&mut position, launch, now);
+            position.lp_boost_bps = position
+                .lp_boost_bps
+                .min(calculate_lp_boost_bps(launch, &position, Some(lp_position)));
+            sync_reward_weight(&mut position, launch);
+            position.try_serialize(&mut &mut data[..])?;
+        }
+
+        emit!(LiquidityPositionUpdated {
+            provider: lp_position.provider,
+            launch: lp_position.launch,
+            lp_amount: lp_position.lp_amount,
+            diamond_rank: lp_position.diamond_rank,
+            multiplier_bps: lp_position.multiplier_bps,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ DEX Trade Reporting ============
+
+    /// Allowlist a key (a reporter program's signing PDA or an indexer
+    /// keypair) to report post-graduation DEX trades
+    pub fn add_trade_reporter(ctx: Context<AddTradeReporter>, key: Pubkey) -> Result<()> {
+        let reporter = &mut ctx.accounts.reporter_registration;
+        reporter.key = key;
+        reporter.added_at = Clock::get()?.unix_timestamp;
+        reporter.trades_reported = 0;
+        reporter.bump = ctx.bumps.reporter_registration;
+
+        emit!(TradeReporterUpdated {
+            key,
+            active: true,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::AddTradeReporter,
+            key,
+            AuditValue::Bool(false),
+            AuditValue::Bool(true),
+        )?;
+
+        Ok(())
+    }
+
+    /// Revoke a trade reporter
+    pub fn remove_trade_reporter(ctx: Context<RemoveTradeReporter>) -> Result<()> {
+        emit!(TradeReporterUpdated {
+            key: ctx.accounts.reporter_registration.key,
+            active: false,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::RemoveTradeReporter,
+            ctx.accounts.reporter_registration.key,
+            AuditValue::Bool(true),
+            AuditValue::Bool(false),
+        )?;
+
+        Ok(())
+    }
+
+    /// An allowlisted reporter feeds a DEX trade for a graduated launch into
+    /// the holder's position, so rank and rewards keep tracking it. Sells
+    /// only count against tokens the position knows about.
+    pub fn report_trade(
+        ctx: Context<ReportTrade>,
+        side: TradeSide,
+        token_amount: u64,
+        quote_amount: u64,
+    ) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let position = &mut ctx.accounts.position;
+        let holder = ctx.accounts.holder.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(token_amount > 0, DiamondPadError::InvalidAmount);
+
+        let recorded = match side {
+            TradeSide::Buy => {
+                credit_position(position, launch, holder, ctx.bumps.position, token_amount, now);
+                launch.total_buy_volume = launch.total_buy_volume.saturating_add(quote_amount);
+                token_amount
+            }
+            TradeSide::Sell => {
+                let amount = token_amount.min(position.balance);
+                if amount > 0 {
+                    debit_position(position, launch, amount, now);
+                }
+                launch.total_sell_volume = launch.total_sell_volume.saturating_add(quote_amount);
+                amount
+            }
+        };
+        if recorded > 0 {
+            track_wash_trading(position, launch, side, now);
+        }
+
+        let reporter = &mut ctx.accounts.reporter_registration;
+        reporter.trades_reported += 1;
+
+        emit!(DexTradeReported {
+            reporter: reporter.key,
+            launch: launch.key(),
+            holder,
+            side,
+            token_amount: recorded,
+            quote_amount,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Refunds ============
+
+    /// Mark a launch as failed and open its refund window
+    pub fn fail_launch(ctx: Context<FailLaunch>) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+
+        require!(
+            launch.status == LaunchStatus::Pending || launch.status == LaunchStatus::Active,
+            DiamondPadError::LaunchNotOpen
+        );
+
+        mark_launch_failed(launch, Clock::get()?.unix_timestamp);
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::FailLaunch,
+            launch.key(),
+            AuditValue::None,
+            AuditValue::None,
+        )?;
+
+        Ok(())
+    }
+
+    /// Creator pushes the sale deadline back once, before it passes, by at
+    /// most the protocol's configured maximum
+    pub fn extend_sale(ctx: Context<ExtendSale>, days: u16) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let now = Clock::get()?.unix_timestamp;
+
+        launch.require_unfrozen(FREEZE_SALE_WINDOW)?;
+        require!(
+            launch.status == LaunchStatus::Pending || launch.status == LaunchStatus::Active,
+            DiamondPadError::LaunchNotOpen
+        );
+        require!(
+            launch.end_time != 0 && now < launch.end_time,
+            DiamondPadError::SaleEnded
+        );
+        require!(!launch.sale_extended, DiamondPadError::SaleAlreadyExtended);
+        require!(
+            days > 0 && days <= ctx.accounts.protocol.max_sale_extension_days,
+            DiamondPadError::InvalidSaleExtension
+        );
+
+        let previous_end_time = launch.end_time;
+        launch.end_time += days as i64 * 86400;
+        launch.sale_extended = true;
+
+        emit!(SaleExtended {
+            launch_id: launch.launch_id,
+            previous_end_time,
+            new_end_time: launch.end_time,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless: fail a launch that missed its soft cap by the
+    /// deadline, or never got a single buy within INACTIVITY_EXPIRY_DAYS,
+    /// so contributors can refund without waiting on the authority
+    pub fn expire_launch(ctx: Context<ExpireLaunch>) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let now = Clock::get()?.unix_timestamp;
+
+        let missed_soft_cap = launch.end_time != 0
+            && now >= launch.end_time
+            && launch.total_raised < launch.config.soft_cap;
+        let never_bought = launch.status == LaunchStatus::Pending
+            && now >= launch.created_at + INACTIVITY_EXPIRY_DAYS * 86400;
+
+        require!(
+            launch.status == LaunchStatus::Pending || launch.status == LaunchStatus::Active,
+            DiamondPadError::LaunchNotOpen
+        );
+        require!(missed_soft_cap || never_bought, DiamondPadError::LaunchNotExpired);
+
+        mark_launch_failed(launch, now);
+
+        Ok(())
+    }
+
+    /// Claim back the SOL contributed to a failed launch
+    pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let contribution = &mut ctx.accounts.contribution;
+        let clock = Clock::get()?;
+
+        require!(launch.status == LaunchStatus::Failed, DiamondPadError::LaunchNotFailed);
+        require!(
+            clock.unix_timestamp <= launch.refund_deadline,
+            DiamondPadError::RefundWindowClosed
+        );
+        require!(
+            contribution.quote_contributed > 0 && !contribution.refunded,
+            DiamondPadError::NothingToRefund
+        );
+
+        let amount = contribution.quote_contributed;
+        contribution.refunded = true;
+        launch.total_refunded = launch.total_refunded.checked_add(amount).unwrap();
+
+        let launch_key = launch.key();
+        let seeds = &[b"sol_vault".as_ref(), launch_key.as_ref(), &[ctx.bumps.sol_vault]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.sol_vault.to_account_info(),
+            to: ctx.accounts.contributor.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        system_program::transfer(cpi_ctx, amount)?;
+
+        emit!(RefundClaimed {
+            launch_id: launch.launch_id,
+            contributor: contribution.contributor,
+            amount,
+            total_refunded: launch.total_refunded,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Move refunds nobody claimed to the treasury once the window closes
+    pub fn sweep_unclaimed(ctx: Context<SweepUnclaimed>) -> Result<()> {
+        let launch = &ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(launch.status == LaunchStatus::Failed, DiamondPadError::LaunchNotFailed);
+        require!(
+            clock.unix_timestamp > launch.refund_deadline,
+            DiamondPadError::RefundWindowOpen
+        );
+
+        let amount = ctx.accounts.sol_vault.lamports();
+        require!(amount > 0, DiamondPadError::NothingToRefund);
+
+        let launch_key = launch.key();
+        let seeds = &[b"sol_vault".as_ref(), launch_key.as_ref(), &[ctx.bumps.sol_vault]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.sol_vault.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        system_program::transfer(cpi_ctx, amount)?;
+
+        emit!(UnclaimedRefundsSwept {
+            launch_id: launch.launch_id,
+            treasury: ctx.accounts.treasury.key(),
+            amount,
+            total_refunded: launch.total_refunded,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::SweepUnclaimed,
+            launch_key,
+            AuditValue::None,
+            AuditValue::Amount(amount),
+        )?;
+
+        Ok(())
+    }
+
+    // ============ Sale Claims ============
+
+    /// Claim the sale tokens a contribution bought, between graduation and
+    /// the launch's claim deadline. They're minted to the contributor's
+    /// associated token account, so on a launch that freezes transfers
+    /// until graduation the mint has to be thawed first.
+    pub fn claim_tokens(ctx: Context<ClaimTokens>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now <= ctx.accounts.launch.claim_deadline,
+            DiamondPadError::ClaimWindowClosed
+        );
+        let contribution = &mut ctx.accounts.contribution;
+        let amount = contribution.tokens_allocated - contribution.tokens_claimed;
+        require!(amount > 0, DiamondPadError::NothingToClaim);
+        // Bonus allocations were issued from the airdrop bucket when credited
+        let sale_amount = amount - contribution.bonus_unclaimed;
+        ctx.accounts.launch.issue_supply(SupplyBucket::Sale, sale_amount)?;
+
+        let launch_key = ctx.accounts.launch.key();
+        let seeds = &[
+            b"launch_authority".as_ref(),
+            launch_key.as_ref(),
+            &[ctx.bumps.launch_authority],
+        ];
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::MintTo {
+                    mint: ctx.accounts.launch_mint.to_account_info(),
+                    to: ctx.accounts.contributor_token_account.to_account_info(),
+                    authority: ctx.accounts.launch_authority.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+
+        contribution.tokens_claimed = contribution.tokens_allocated;
+        contribution.bonus_unclaimed = 0;
+
+        // Bring the position up to date as its tokens leave custody; any
+        // later outflow from the wallet is caught by reconcile_position
+        let launch = &mut ctx.accounts.launch;
+        let position = &mut ctx.accounts.position;
+        settle_rewards(position, launch, now);
+        refresh_rank(position, launch, now);
+        sync_reward_weight(position, launch);
+
+        emit!(SaleTokensClaimed {
+            launch_id: launch.launch_id,
+            contributor: contribution.contributor,
+            amount,
+            claim_deadline: launch.claim_deadline,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless: once a graduated launch's claim window has closed,
+    /// write off a contribution's unclaimed tokens instead of leaving them
+    /// owed forever. Nothing is minted: the sale part was never issued and
+    /// stays in the sale bucket, and any bonus part goes back to the
+    /// airdrop bucket. `reconcile_position` then drops them from the
+    /// holder's position.
+    pub fn sweep_unclaimed_allocation(ctx: Context<SweepUnclaimedAllocation>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let launch = &mut ctx.accounts.launch;
+        require!(now > launch.claim_deadline, DiamondPadError::ClaimWindowOpen);
+
+        let contribution = &mut ctx.accounts.contribution;
+        let amount = contribution.tokens_allocated - contribution.tokens_claimed;
+        require!(amount > 0 && !contribution.swept, DiamondPadError::NothingToSweep);
+        launch.release_supply(SupplyBucket::Airdrop, contribution.bonus_unclaimed);
+
+        contribution.swept = true;
+        contribution.bonus_unclaimed = 0;
+        launch.allocations_swept = launch.allocations_swept.checked_add(amount).unwrap();
+
+        emit!(UnclaimedAllocationSwept {
+            launch_id: launch.launch_id,
+            contributor: contribution.contributor,
+            amount,
+            total_swept: launch.allocations_swept,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Listing Deposit ============
+
+    /// Permissionless: pay out a launch's listing deposit once its outcome
+    /// is known. Graduation refunds it in full, after a hold long enough
+    /// for a rug ruling to reach it; a failed launch that saw buys gets the
+    /// protocol's failure share back; a launch that failed without a single
+    /// buy, or was flagged as spam, forfeits it.
+    pub fn settle_listing_deposit(ctx: Context<SettleListingDeposit>) -> Result<()> {
+        let launch = &ctx.accounts.launch;
+        let deposit = launch.listing_deposit;
+        require!(deposit > 0, DiamondPadError::NoListingDeposit);
+
+        let refunded = match launch.status {
+            _ if launch.spam_flagged => 0,
+            LaunchStatus::Graduated => {
+                let releases_at = launch.graduated_at + LISTING_DEPOSIT_HOLD_DAYS * 86400;
+                require!(
+                    Clock::get()?.unix_timestamp >= releases_at,
+                    DiamondPadError::ListingDepositHeld
+                );
+                deposit
+            }
+            LaunchStatus::Failed if launch.unique_buyers > 0 => {
+                let refund_bps = ctx.accounts.protocol.listing_deposit_failure_refund_bps;
+                (deposit as u128 * refund_bps as u128 / 10000) as u64
+            }
+            LaunchStatus::Failed => 0,
+            _ => return err!(DiamondPadError::LaunchNotSettled),
+        };
+
+        release_listing_deposit(
+            &mut ctx.accounts.launch,
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            refunded,
+        )
+    }
+
+    // ============ Insurance ============
+
+    /// Add lamports to a launch's insurance vault. Anyone can fund it, at
+    /// any time; only holders of the launch can ever be paid from it.
+    pub fn fund_insurance(ctx: Context<FundInsurance>, amount: u64) -> Result<()> {
+        require!(amount > 0, DiamondPadError::InvalidAmount);
+
+        let vault = &mut ctx.accounts.insurance_vault;
+        if vault.launch == Pubkey::default() {
+            vault.launch = ctx.accounts.launch.key();
+            vault.bump = ctx.bumps.insurance_vault;
+        }
+        require!(!vault.settled, DiamondPadError::InsuranceSettled);
+
+        transfer_lamports(
+            ctx.accounts.funder.to_account_info(),
+            vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            amount,
+            &[],
+        )?;
+        vault.total_funded = vault.total_funded.checked_add(amount).unwrap();
+
+        emit!(InsuranceFunded {
+            launch: vault.launch,
+            funder: ctx.accounts.funder.key(),
+            amount,
+            total_funded: vault.total_funded,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Open the claims window on a rug-flagged launch
+    pub fn open_insurance_claims(ctx: Context<OpenInsuranceClaims>) -> Result<()> {
+        let vault = &mut ctx.accounts.insurance_vault;
+        require!(vault.claims_deadline == 0, DiamondPadError::InsuranceClaimsAlreadyOpen);
+        if vault.launch == Pubkey::default() {
+            vault.launch = ctx.accounts.launch.key();
+            vault.bump = ctx.bumps.insurance_vault;
+        }
+        vault.claims_deadline =
+            Clock::get()?.unix_timestamp + INSURANCE_CLAIM_WINDOW_DAYS * 86400;
+
+        emit!(InsuranceClaimsOpened {
+            launch: vault.launch,
+            claims_deadline: vault.claims_deadline,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.adjudicator.key(),
+            AdminAction::OpenInsuranceClaims,
+            vault.launch,
+            AuditValue::None,
+            AuditValue::Timestamp(vault.claims_deadline),
+        )?;
+
+        Ok(())
+    }
+
+    /// File a claim against a rug-flagged launch for the caller's position
+    /// as it stood at the ruling. Only a position opened before the ruling
+    /// and untouched since can file, so its balance is the one held when
+    /// the launch was ruled. Payouts are pro rata to that balance.
+    pub fn file_insurance_claim(ctx: Context<FileInsuranceClaim>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let vault = &mut ctx.accounts.insurance_vault;
+        require!(
+            vault.claims_deadline != 0 && now < vault.claims_deadline,
+            DiamondPadError::InsuranceClaimsClosed
+        );
+
+        let position = &ctx.accounts.position;
+        let rugged_at = ctx.accounts.launch.rugged_at;
+        require!(!position.has_flag(POSITION_INSIDER), DiamondPadError::Unauthorized);
+        require!(position.balance > 0, DiamondPadError::InsufficientBalance);
+        require!(
+            (position.first_buy_timestamp as i64) < rugged_at
+                && position.last_activity_timestamp as i64 <= rugged_at,
+            DiamondPadError::PositionChangedSinceRuling
+        );
+
+        let claim = &mut ctx.accounts.claim;
+        claim.launch = vault.launch;
+        claim.holder = position.holder;
+        claim.balance = position.balance;
+        claim.diamond_rank = position.diamond_rank;
+        claim.first_buy_timestamp = position.first_buy_timestamp as i64;
+        claim.filed_at = now;
+        claim.status = InsuranceClaimStatus::Pending;
+        claim.bump = ctx.bumps.claim;
+
+        vault.claims_filed += 1;
+        vault.claims_pending += 1;
+        vault.total_filed = vault.total_filed.checked_add(claim.balance).unwrap();
+
+        emit!(InsuranceClaimFiled {
+            launch: claim.launch,
+            holder: claim.holder,
+            balance: claim.balance,
+            diamond_rank: claim.diamond_rank,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Approve or deny a batch of pending claims, passed as writable
+    /// remaining accounts
+    pub fn adjudicate_insurance_claims<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AdjudicateInsuranceClaims<'info>>,
+        approve: bool,
+    ) -> Result<()> {
+        let vault = &mut ctx.accounts.insurance_vault;
+        require!(!vault.settled, DiamondPadError::InsuranceSettled);
+
+        for info in ctx.remaining_accounts {
+            let mut claim = Account::<InsuranceClaim>::try_from(info)?;
+            require_keys_eq!(claim.launch, vault.launch, DiamondPadError::InsuranceClaimMismatch);
+            require!(
+                claim.status == InsuranceClaimStatus::Pending,
+                DiamondPadError::InsuranceClaimNotPending
+            );
+
+            vault.claims_pending -= 1;
+            if approve {
+                claim.status = InsuranceClaimStatus::Approved;
+                vault.claims_approved += 1;
+                vault.total_approved = vault.total_approved.checked_add(claim.balance).unwrap();
+            } else {
+                claim.status = InsuranceClaimStatus::Denied;
+            }
+
+            emit!(InsuranceClaimAdjudicated {
+                launch: claim.launch,
+                holder: claim.holder,
+                approved: approve,
+                balance: claim.balance,
+                schema_version: EVENT_SCHEMA_VERSION,
+            });
+            claim.exit(&crate::ID)?;
+        }
+
+        Ok(())
+    }
+
+    /// Pay an approved claim its pro-rata share of the vault. The first
+    /// payout fixes the pool, so it can only happen once the window has
+    /// closed and every claim has been decided. A position traded since the
+    /// ruling is no longer covered.
+    pub fn claim_insurance_payout(ctx: Context<ClaimInsurancePayout>) -> Result<()> {
+        let vault = &mut ctx.accounts.insurance_vault;
+        let claim = &mut ctx.accounts.claim;
+        require!(
+            claim.status == InsuranceClaimStatus::Approved,
+            DiamondPadError::InsuranceClaimNotApproved
+        );
+        require!(
+            ctx.accounts.position.last_activity_timestamp as i64 <= ctx.accounts.launch.rugged_at,
+            DiamondPadError::PositionChangedSinceRuling
+        );
+
+        if !vault.settled {
+            require!(
+                Clock::get()?.unix_timestamp >= vault.claims_deadline,
+                DiamondPadError::InsuranceClaimsStillOpen
+            );
+            require!(vault.claims_pending == 0, DiamondPadError::InsuranceClaimsPending);
+
+            let rent = Rent::get()?.minimum_balance(InsuranceVault::SIZE);
+            vault.payout_pool = vault.to_account_info().lamports().saturating_sub(rent);
+            vault.dpad_payout_pool = match &ctx.accounts.insurance_token_vault {
+                Some(token_vault) => token_vault.amount,
+                None => {
+                    require!(vault.dpad_funded == 0, DiamondPadError::InsuranceTokenAccountsRequired);
+                    0
+                }
+            };
+            vault.settled = true;
+        }
+
+        let amount = (vault.payout_pool as u128 * claim.balance as u128
+            / vault.total_approved as u128) as u64;
+        let dpad_amount = (vault.dpad_payout_pool as u128 * claim.balance as u128
+            / vault.total_approved as u128) as u64;
+        if amount >= LARGE_INSURANCE_PAYOUT {
+            let council = ctx
+                .accounts
+                .council
+                .as_ref()
+                .ok_or(DiamondPadError::CouncilMajorityRequired)?;
+            require_council_majority(council, ctx.remaining_accounts, Clock::get()?.unix_timestamp)?;
+            audit_admin_action(
+                ctx.accounts.holder.key(),
+                AdminAction::PayLargeInsuranceClaim,
+                claim.key(),
+                AuditValue::None,
+                AuditValue::Amount(amount),
+            )?;
+        }
+        claim.status = InsuranceClaimStatus::Paid;
+        claim.payout = amount;
+        claim.dpad_payout = dpad_amount;
+        vault.total_paid = vault.total_paid.checked_add(amount).unwrap();
+
+        vault.sub_lamports(amount)?;
+        ctx.accounts.holder.add_lamports(amount)?;
+
+        // Slashed creator DPAD is split the same way as the lamports
+        if dpad_amount > 0 {
+            let (Some(token_vault), Some(holder_token_account), Some(token_program)) = (
+                &ctx.accounts.insurance_token_vault,
+                &ctx.accounts.holder_token_account,
+                &ctx.accounts.token_program,
+            ) else {
+                return err!(DiamondPadError::InsuranceTokenAccountsRequired);
+            };
+
+            let seeds = &[b"insurance_vault".as_ref(), vault.launch.as_ref(), &[vault.bump]];
+            let signer = &[&seeds[..]];
+            let cpi_accounts = Transfer {
+                from: token_vault.to_account_info(),
+                to: holder_token_account.to_account_info(),
+                authority: vault.to_account_info(),
+            };
+            let cpi_program = token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token::transfer(cpi_ctx, dpad_amount)?;
+        }
+
+        emit!(InsurancePaid {
+            launch: vault.launch,
+            holder: claim.holder,
+            amount,
+            dpad_amount,
+            total_paid: vault.total_paid,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Council ============
+
+    /// Seat a council member for `term_days`. Council rulings need
+    /// signatures from active members holding a majority of all seats.
+    pub fn appoint_council_member(
+        ctx: Context<AppointCouncilMember>,
+        wallet: Pubkey,
+        term_days: u16,
+    ) -> Result<()> {
+        require!(
+            term_days > 0 && term_days <= MAX_COUNCIL_TERM_DAYS,
+            DiamondPadError::InvalidCouncilTerm
+        );
+
+        let council = &mut ctx.accounts.council;
+        require!(
+            council.member_count < MAX_COUNCIL_MEMBERS,
+            DiamondPadError::CouncilFull
+        );
+        council.member_count += 1;
+        council.bump = ctx.bumps.council;
+
+        let member = &mut ctx.accounts.member;
+        seat_council_member(member, wallet, term_days, ctx.bumps.member)?;
+
+        emit!(CouncilMemberAppointed {
+            wallet,
+            term_ends_at: member.term_ends_at,
+            member_count: council.member_count,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::AppointCouncilMember,
+            wallet,
+            AuditValue::None,
+            AuditValue::Amount(term_days as u64),
+        )
+    }
+
+    /// Hand a seat to a new member. A member whose term has ended can be
+    /// replaced by the authority alone; replacing a sitting member also
+    /// needs a council majority.
+    pub fn replace_council_member(
+        ctx: Context<ReplaceCouncilMember>,
+        wallet: Pubkey,
+        term_days: u16,
+    ) -> Result<()> {
+        require!(
+            term_days > 0 && term_days <= MAX_COUNCIL_TERM_DAYS,
+            DiamondPadError::InvalidCouncilTerm
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let previous = &ctx.accounts.previous_member;
+        if previous.term_ends_at > now {
+            require_council_majority(&ctx.accounts.council, ctx.remaining_accounts, now)?;
+        }
+
+        let member = &mut ctx.accounts.member;
+        seat_council_member(member, wallet, term_days, ctx.bumps.member)?;
+
+        emit!(CouncilMemberReplaced {
+            previous: previous.wallet,
+            wallet,
+            term_ends_at: member.term_ends_at,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::ReplaceCouncilMember,
+            wallet,
+            AuditValue::Key(previous.wallet),
+            AuditValue::Key(wallet),
+        )
+    }
+
+    // ============ Launch Governance ============
+
+    /// Open a holder proposal on a launch. The proposer needs voting power
+    /// of their own; the proposal text lives off-chain under its hash. An
+    /// `action` other than `None` can be executed once the proposal passes.
+    pub fn create_launch_proposal(
+        ctx: Context<CreateLaunchProposal>,
+        proposal_id: u32,
+        description_hash: [u8; 32],
+        action: ProposalAction,
+    ) -> Result<()> {
+        let launch = &ctx.accounts.launch;
+        require!(
+            matches!(launch.status, LaunchStatus::Active | LaunchStatus::Graduated),
+            DiamondPadError::LaunchNotOpen
+        );
+        require!(ctx.accounts.position.reward_weight > 0, DiamondPadError::NoVotingPower);
+        match action {
+            ProposalAction::SetRankThresholds { threshold_days } => {
+                validate_rank_thresholds(&threshold_days)?
+            }
+            ProposalAction::ScheduleFeatureFlags { .. } => require!(
+                ctx.accounts.protocol.governed_by(launch),
+                DiamondPadError::NotProtocolGovernance
+            ),
+            ProposalAction::SetInterestRate { rate_bps } => {
+                validate_interest_rate(launch, rate_bps)?
+            }
+            _ => {}
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.launch = launch.key();
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.proposal_id = proposal_id;
+        proposal.description_hash = description_hash;
+        proposal.action = action;
+        proposal.created_at = now;
+        proposal.voting_ends_at = now + LAUNCH_PROPOSAL_VOTING_DAYS * 86400;
+        proposal.total_weight = launch.total_reward_weight;
+        proposal.quorum_weight =
+            launch.total_reward_weight * launch.config.governance_quorum_bps as u128 / 10000;
+        proposal.bump = ctx.bumps.proposal;
+
+        emit!(LaunchProposalCreated {
+            proposal: proposal.key(),
+            launch: proposal.launch,
+            proposer: proposal.proposer,
+            proposal_id,
+            description_hash,
+            voting_ends_at: proposal.voting_ends_at,
+            schema_version: EVENT_SCHEMA_VERSION,
+            action,
+            quorum_weight: proposal.quorum_weight,
+        });
+
+        Ok(())
+    }
+
+    /// Vote a position's rank-weighted power on a proposal. Cast by the
+    /// holder, or by their delegate while a delegation is in place. Weight
+    /// gained this epoch doesn't vote until the epoch closes, the same as it
+    /// doesn't earn, and a position that changed after the proposal opened
+    /// sits it out, so tokens bought for the vote don't count.
+    pub fn cast_launch_vote(ctx: Context<CastLaunchVote>, support: bool) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let proposal = &mut ctx.accounts.proposal;
+        require!(now < proposal.voting_ends_at, DiamondPadError::ProposalVotingClosed);
+
+        let position = &ctx.accounts.position;
+        require!(
+            position.last_activity_timestamp as i64 <= proposal.created_at,
+            DiamondPadError::PositionChangedSinceSnapshot
+        );
+        let voter = ctx.accounts.voter.key();
+        let delegate = read_vote_delegate(&ctx.accounts.delegation)?;
+        require!(
+            voter == delegate.unwrap_or(position.holder),
+            DiamondPadError::Unauthorized
+        );
+
+        let weight = position.reward_weight;
+        require!(weight > 0, DiamondPadError::NoVotingPower);
+        if support {
+            proposal.votes_for += weight;
+        } else {
+            proposal.votes_against += weight;
+        }
+
+        let vote = &mut ctx.accounts.vote;
+        vote.proposal = proposal.key();
+        vote.holder = position.holder;
+        vote.voter = voter;
+        vote.support = support;
+        vote.weight = weight;
+        vote.bump = ctx.bumps.vote;
+
+        emit!(LaunchVoteCast {
+            proposal: vote.proposal,
+            holder: vote.holder,
+            voter,
+            support,
+            weight,
+            votes_for: proposal.votes_for,
+            votes_against: proposal.votes_against,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless: carry out a passed proposal's action once voting has
+    /// ended. Each proposal executes at most once.
+    pub fn execute_launch_proposal(ctx: Context<ExecuteLaunchProposal>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let launch = &mut ctx.accounts.launch;
+
+        require!(
+            Clock::get()?.unix_timestamp >= proposal.voting_ends_at,
+            DiamondPadError::ProposalVotingOpen
+        );
+        require!(!proposal.executed, DiamondPadError::ProposalAlreadyExecuted);
+        require!(
+            proposal.votes_for + proposal.votes_against >= proposal.quorum_weight,
+            DiamondPadError::ProposalQuorumNotMet
+        );
+        require!(
+            proposal.votes_for > proposal.votes_against,
+            DiamondPadError::ProposalNotPassed
+        );
+
+        match proposal.action {
+            ProposalAction::None => return err!(DiamondPadError::ProposalHasNoAction),
+            ProposalAction::ExtendLpLock { new_unlock_timestamp } => {
+                extend_launch_lp_lock(launch, new_unlock_timestamp)?
+            }
+            ProposalAction::RaiseHolderRewards { holder_rewards_bps } => {
+                raise_launch_holder_rewards(launch, holder_rewards_bps)?
+            }
+            ProposalAction::SetRankThresholds { threshold_days } => {
+                emit!(RankThresholdsUpdated {
+                    launch_id: launch.launch_id,
+                    previous_threshold_days: launch.rank_thresholds(),
+                    threshold_days,
+                    schema_version: EVENT_SCHEMA_VERSION,
+                });
+                launch.rank_threshold_days = threshold_days;
+            }
+            ProposalAction::ScheduleFeatureFlags { disabled_features } => {
+                let protocol = &mut ctx.accounts.protocol;
+                schedule_feature_flags(protocol, disabled_features, proposal.key())?
+            }
+            ProposalAction::SetInterestRate { rate_bps } => {
+                let (Some(launch_mint), Some(launch_authority), Some(bump), Some(token_program)) = (
+                    ctx.accounts.launch_mint.as_ref(),
+                    ctx.accounts.launch_authority.as_ref(),
+                    ctx.bumps.launch_authority,
+                    ctx.accounts.token_program.as_ref(),
+                ) else {
+                    return err!(DiamondPadError::InterestRateAccountsRequired);
+                };
+                set_launch_interest_rate(
+                    launch,
+                    launch_mint.to_account_info(),
+                    launch_authority.to_account_info(),
+                    bump,
+                    token_program.to_account_info(),
+                    rate_bps,
+                    proposal.key(),
+                )?
+            }
+        }
+        proposal.executed = true;
+
+        emit!(LaunchProposalExecuted {
+            proposal: proposal.key(),
+            launch: launch.key(),
+            action: proposal.action,
+            votes_for: proposal.votes_for,
+            votes_against: proposal.votes_against,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Hand the caller's voting power on a launch to another wallet, or
+    /// move it to a new delegate. The holder can't vote themselves until
+    /// they revoke.
+    pub fn delegate_votes(ctx: Context<DelegateVotes>, delegate: Pubkey) -> Result<()> {
+        let holder = ctx.accounts.holder.key();
+        require!(
+            delegate != holder && delegate != Pubkey::default(),
+            DiamondPadError::InvalidDelegate
+        );
+
+        let delegation = &mut ctx.accounts.delegation;
+        delegation.launch = ctx.accounts.launch.key();
+        delegation.holder = holder;
+        delegation.delegate = delegate;
+        delegation.delegated_at = Clock::get()?.unix_timestamp;
+        delegation.bump = ctx.bumps.delegation;
+
+        emit!(VotesDelegated {
+            launch: delegation.launch,
+            holder,
+            delegate,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Take voting power back from a delegate
+    pub fn revoke_vote_delegation(ctx: Context<RevokeVoteDelegation>) -> Result<()> {
+        let delegation = &ctx.accounts.delegation;
+
+        emit!(VoteDelegationRevoked {
+            launch: delegation.launch,
+            holder: delegation.holder,
+            delegate: delegation.delegate,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Holder Tracking ============
+
+    /// Allowlist an aggregator/router program to CPI into record_position
+    pub fn add_router(ctx: Context<AddRouter>, program: Pubkey) -> Result<()> {
+        let router = &mut ctx.accounts.router;
+        router.program = program;
+        router.added_at = Clock::get()?.unix_timestamp;
+        router.bump = ctx.bumps.router;
+
+        emit!(RouterUpdated {
+            program,
+            active: true,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::AddRouter,
+            program,
+            AuditValue::Bool(false),
+            AuditValue::Bool(true),
+        )?;
+
+        Ok(())
+    }
+
+    /// Remove a router program from the allowlist
+    pub fn remove_router(ctx: Context<RemoveRouter>) -> Result<()> {
+        emit!(RouterUpdated {
+            program: ctx.accounts.router.program,
+            active: false,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::RemoveRouter,
+            ctx.accounts.router.program,
+            AuditValue::Bool(true),
+            AuditValue::Bool(false),
+        )?;
+
+        Ok(())
+    }
+
+    /// Record a holder's position (called on buy). The recorded balance can
+    /// only grow into what the holder's token account actually holds, so an
+    /// inflow can't be fabricated or recorded twice.
+    pub fn record_position(
+        ctx: Context<RecordPosition>,
+        amount: u64,
+    ) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        let launch = &mut ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(!launch.is_paused(PAUSE_TRADING), DiamondPadError::TradingHalted);
+        require!(amount > 0, DiamondPadError::InvalidAmount);
+        let actual = read_token_balance(&ctx.accounts.holder_token_account)?;
+        require!(
+            position.balance.checked_add(amount).is_some_and(|recorded| recorded <= actual),
+            DiamondPadError::RecordExceedsBalance
+        );
+        position.consume_rate_limit(
+            ctx.accounts.protocol.max_position_actions_per_minute,
+            clock.unix_timestamp,
+        )?;
+
+        // Routed through an aggregator: only allowlisted router programs
+        if let Some(caller) = routing::cpi_caller(&ctx.accounts.instructions_sysvar)? {
+            let router = ctx
+                .accounts
+                .router
+                .as_ref()
+                .ok_or(DiamondPadError::RouterNotAllowed)?;
+            require_keys_eq!(router.program, caller, DiamondPadError::RouterNotAllowed);
+        }
+
+        credit_position(
+            position,
+            launch,
+            ctx.accounts.holder.key(),
+            ctx.bumps.position,
+            amount,
+            clock.unix_timestamp,
+        );
+
+        Ok(())
+    }
+
+    /// Record tokens leaving the holder's wallet off the curve (a transfer
+    /// or a DEX sell), applying the launch's sell policy as a curve sell would
+    pub fn record_sell(ctx: Context<RecordSell>, amount: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        let launch = &mut ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(amount > 0, DiamondPadError::InvalidAmount);
+        require!(position.balance >= amount, DiamondPadError::InsufficientBalance);
+        position.consume_rate_limit(
+            ctx.accounts.protocol.max_position_actions_per_minute,
+            clock.unix_timestamp,
+        )?;
+
+        // Routed through an aggregator: only allowlisted router programs
+        if let Some(caller) = routing::cpi_caller(&ctx.accounts.instructions_sysvar)? {
+            let router = ctx
+                .accounts
+                .router
+                .as_ref()
+                .ok_or(DiamondPadError::RouterNotAllowed)?;
+            require_keys_eq!(router.program, caller, DiamondPadError::RouterNotAllowed);
+        }
+
+        let rank_reset = debit_position(position, launch, amount, clock.unix_timestamp);
+
+        emit!(SellRecorded {
+            launch_id: launch.launch_id,
+            holder: position.holder,
+            amount,
+            rank_reset,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless: bring a position down to what the holder actually
+    /// has, applying the sell policy to the difference: their associated
+    /// token account for the launch mint plus whatever their contribution
+    /// still owes them through `claim_tokens`. A closed token account
+    /// counts as empty. Positions are never raised this way.
+    pub fn reconcile_position(ctx: Context<ReconcilePosition>) -> Result<()> {
+        let actual = held_balance(ctx.accounts)?;
+
+        let position = &mut ctx.accounts.position;
+        let launch = &mut ctx.accounts.launch;
+        let recorded = position.balance;
+        require!(recorded > actual, DiamondPadError::PositionInSync);
+
+        let rank_reset = debit_position(position, launch, recorded - actual, Clock::get()?.unix_timestamp);
+
+        emit!(PositionReconciled {
+            launch_id: launch.launch_id,
+            holder: position.holder,
+            recorded,
+            actual,
+            rank_reset,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Gift part of a position to another wallet. The giver keeps their rank
+    /// on what they retain; gifted tokens start at Paper for the recipient
+    /// (blended into an existing position's entry time pro rata). What moves
+    /// is the giver's unclaimed sale allocation, so the recipient claims the
+    /// tokens; tokens already claimed change hands as token transfers.
+    pub fn gift_position(ctx: Context<GiftPosition>, amount: u64) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let giver = &mut ctx.accounts.giver_position;
+        let recipient = &mut ctx.accounts.recipient_position;
+        let recipient_key = ctx.accounts.recipient.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(amount > 0, DiamondPadError::InvalidAmount);
+        require!(recipient_key != giver.holder, DiamondPadError::InvalidGiftRecipient);
+        require!(launch.status != LaunchStatus::Failed, DiamondPadError::LaunchNotOpen);
+        require!(!giver.has_flag(POSITION_INSIDER), DiamondPadError::InsiderCannotGift);
+        require!(giver.balance >= amount, DiamondPadError::InsufficientBalance);
+
+        // Sale tokens go first, then bonus allocations, as on a sell
+        let giver_contribution = &mut ctx.accounts.giver_contribution;
+        let unclaimed = giver_contribution.tokens_allocated - giver_contribution.tokens_claimed;
+        require!(
+            !giver_contribution.swept && unclaimed >= amount,
+            DiamondPadError::InsufficientBalance
+        );
+        let bonus = amount.saturating_sub(unclaimed - giver_contribution.bonus_unclaimed);
+        giver_contribution.tokens_allocated -= amount;
+        giver_contribution.bonus_unclaimed -= bonus;
+
+        let recipient_contribution = &mut ctx.accounts.recipient_contribution;
+        open_contribution(
+            recipient_contribution,
+            launch.key(),
+            recipient_key,
+            ctx.bumps.recipient_contribution,
+        );
+        recipient_contribution.tokens_allocated =
+            recipient_contribution.tokens_allocated.checked_add(amount).unwrap();
+        recipient_contribution.bonus_unclaimed += bonus;
+
+        settle_rewards(giver, launch, now);
+        giver.balance -= amount;
+        giver.last_activity_timestamp = now as u32;
+        refresh_rank(giver, launch, now);
+        sync_reward_weight(giver, launch);
+        if giver.balance == 0 {
+            launch.holder_count = launch.holder_count.saturating_sub(1);
+        }
+        if !launch.holds_verified_balance(giver.balance) {
+            set_verified_holder(giver, launch, false);
+        }
+
+        emit!(PositionUpdated {
+            holder: giver.holder,
+            launch: giver.launch,
+            balance: giver.balance,
+            diamond_rank: giver.diamond_rank,
+            multiplier_bps: giver.multiplier_bps,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        if recipient.balance > 0 {
+            let held_for = (now - recipient.first_buy_timestamp as i64).max(0) as u128;
+            let shift = held_for * amount as u128 / (recipient.balance as u128 + amount as u128);
+            recipient.first_buy_timestamp += shift as u32;
+        }
+        credit_position(
+            recipient,
+            launch,
+            recipient_key,
+            ctx.bumps.recipient_position,
+            amount,
+            now,
+        );
+
+        emit!(PositionGifted {
+            launch_id: launch.launch_id,
+            from: giver.holder,
+            to: recipient_key,
+            amount,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Designate (or schedule a change of) the key that can recover this
+    /// position. The first designation applies immediately; changing an
+    /// existing one waits out RECOVERY_TIMELOCK_DAYS so a thief can't
+    /// swap it and recover straight away.
+    pub fn set_recovery(ctx: Context<SetRecovery>, recovery: Pubkey) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        let now = Clock::get()?.unix_timestamp;
+
+        if position.recovery == Pubkey::default() {
+            position.recovery = recovery;
+        } else {
+            position.pending_recovery = recovery;
+            position.recovery_change_at = (now + RECOVERY_TIMELOCK_DAYS * 86400) as u32;
+        }
+
+        emit!(RecoveryUpdated {
+            holder: position.holder,
+            launch: position.launch,
+            recovery,
+            effective_at: (position.recovery_change_at as i64).max(now),
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Apply a scheduled recovery key change once its timelock has passed
+    pub fn apply_recovery_change(ctx: Context<SetRecovery>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+
+        require!(
+            position.pending_recovery != Pubkey::default(),
+            DiamondPadError::NoPendingRecovery
+        );
+        require!(
+            Clock::get()?.unix_timestamp >= position.recovery_change_at as i64,
+            DiamondPadError::RecoveryTimelocked
+        );
+
+        position.recovery = position.pending_recovery;
+        position.pending_recovery = Pubkey::default();
+        position.recovery_change_at = 0;
+
+        Ok(())
+    }
+
+    /// Recovery key moves the whole position (rank, entry time, unclaimed
+    /// rewards) and its contribution (allocation still to claim, refund
+    /// rights) to a fresh wallet and closes the old ones
+    pub fn recover_position(ctx: Context<RecoverPosition>) -> Result<()> {
+        let old_position = &ctx.accounts.old_position;
+        let new_wallet = ctx.accounts.new_wallet.key();
+
+        take_over_contribution(
+            &mut ctx.accounts.new_contribution,
+            &ctx.accounts.old_contribution,
+            new_wallet,
+            ctx.bumps.new_contribution,
+        );
+
+        let mut migrated = (**old_position).clone();
+        migrated.holder = new_wallet;
+        migrated.pending_recovery = Pubkey::default();
+        migrated.recovery_change_at = 0;
+        migrated.bump = ctx.bumps.new_position;
+        ctx.accounts.new_position.set_inner(migrated);
+
+        emit!(PositionRecovered {
+            launch: old_position.launch,
+            from: old_position.holder,
+            to: new_wallet,
+            balance: old_position.balance,
+            accrued_rewards: old_position.accrued_rewards,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless: close a dust position that has sat idle for the
+    /// protocol's inactivity period. Its remaining balance is dropped and
+    /// the rent is split between the cranker and the holder. A position
+    /// with rewards to claim, or with sale tokens still to claim (which
+    /// `claim_tokens` needs it for), is never collected.
+    pub fn gc_position(ctx: Context<GcPosition>) -> Result<()> {
+        let protocol = &ctx.accounts.protocol;
+        let position = &mut ctx.accounts.position;
+        let launch = &mut ctx.accounts.launch;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(
+            protocol.gc_dust_threshold > 0
+                && position.balance < protocol.gc_dust_threshold
+                && now - position.last_activity_timestamp as i64
+                    >= protocol.gc_inactivity_days as i64 * 86400,
+            DiamondPadError::PositionNotCollectable
+        );
+
+        require!(
+            read_unclaimed_allocation(&ctx.accounts.contribution)? == 0,
+            DiamondPadError::PositionNotCollectable
+        );
+
+        settle_rewards(position, launch, now);
+        require!(position.accrued_rewards == 0, DiamondPadError::PositionNotCollectable);
+        let balance = position.balance;
+        position.balance = 0;
+        sync_reward_weight(position, launch);
+        if balance > 0 {
+            launch.holder_count = launch.holder_count.saturating_sub(1);
+        }
+        set_verified_holder(position, launch, false);
+
+        let rent = position.to_account_info().lamports();
+        let cranker_share = (rent as u128 * protocol.gc_cranker_share_bps as u128 / 10000) as u64;
+        position.sub_lamports(cranker_share)?;
+        ctx.accounts.cranker.add_lamports(cranker_share)?;
+
+        emit!(PositionCollected {
+            launch_id: launch.launch_id,
+            holder: position.holder,
+            balance,
+            cranker: ctx.accounts.cranker.key(),
+            cranker_share,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless: grow a position written under an older layout to
+    /// the current one, with anyone paying the extra rent. Older positions
+    /// can't be loaded by the other instructions until they are upgraded.
+    /// Layout 1 predates the version byte and is recognised by its size.
+    pub fn upgrade_position(ctx: Context<UpgradePosition>) -> Result<()> {
+        let info = ctx.accounts.position.to_account_info();
+        require!(info.data_len() < Position::SIZE, DiamondPadError::PositionLayoutCurrent);
+        let legacy = if info.data_len() == LEGACY_POSITION_SIZE {
+            let data = info.try_borrow_data()?;
+            require!(
+                data[..8] == Position::DISCRIMINATOR,
+                ErrorCode::AccountDiscriminatorMismatch
+            );
+            Some(LegacyPosition::deserialize(&mut &data[8..])?)
+        } else {
+            None
+        };
+
+        let rent = Rent::get()?.minimum_balance(Position::SIZE);
+        transfer_lamports(
+            ctx.accounts.payer.to_account_info(),
+            info.clone(),
+            ctx.accounts.system_program.to_account_info(),
+            rent.saturating_sub(info.lamports()),
+            &[],
+        )?;
+        info.realloc(Position::SIZE, true)?;
+
+        // New fields start zeroed; backfill the rank timeline from the
+        // rank the position already holds
+        let mut data = info.try_borrow_mut_data()?;
+        if legacy.is_some() {
+            data[8..].fill(0);
+        }
+        let mut position = Position::try_deserialize(&mut &data[..])?;
+        let from_version = match legacy {
+            Some(legacy) => {
+                legacy.carry_over(&mut position);
+                1
+            }
+            None => position.layout_version,
+        };
+        let launch = &ctx.accounts.launch;
+        require_keys_eq!(position.launch, launch.key(), DiamondPadError::PositionLaunchMismatch);
+        position.layout_version = POSITION_LAYOUT_VERSION;
+        position.record_rank_timeline(&launch.rank_thresholds());
+        position.try_serialize(&mut &mut data[..])?;
+
+        emit!(PositionUpgraded {
+            launch: position.launch,
+            holder: position.holder,
+            from_version,
+            to_version: POSITION_LAYOUT_VERSION,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless: rewrite a launch from before launches carried a
+    /// config into the current layout, with anyone paying the extra rent.
+    /// Such launches can't be loaded by the other instructions until then.
+    pub fn upgrade_launch(ctx: Context<UpgradeLaunch>) -> Result<()> {
+        let info = ctx.accounts.launch.to_account_info();
+        let legacy = {
+            let data = info.try_borrow_data()?;
+            require!(data.len() == LEGACY_LAUNCH_SIZE, DiamondPadError::LaunchLayoutCurrent);
+            require!(
+                data[..8] == Launch::DISCRIMINATOR,
+                ErrorCode::AccountDiscriminatorMismatch
+            );
+            LegacyLaunch::deserialize(&mut &data[8..])?
+        };
+
+        let rent = Rent::get()?.minimum_balance(Launch::SIZE);
+        transfer_lamports(
+            ctx.accounts.payer.to_account_info(),
+            info.clone(),
+            ctx.accounts.system_program.to_account_info(),
+            rent.saturating_sub(info.lamports()),
+            &[],
+        )?;
+        info.realloc(Launch::SIZE, true)?;
+
+        let mut data = info.try_borrow_mut_data()?;
+        data[8..].fill(0);
+        let mut launch = Launch::try_deserialize(&mut &data[..])?;
+        legacy.carry_over(&mut launch);
+        launch.try_serialize(&mut &mut data[..])?;
+
+        emit!(LaunchUpgraded {
+            launch: info.key(),
+            launch_id: launch.launch_id,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless: re-check a position against the launch's sybil
+    /// filters (minimum balance, minimum hold, never flagged) and count or
+    /// uncount it in the verified holder count
+    pub fn refresh_verified_holder(ctx: Context<RefreshVerifiedHolder>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        let launch = &mut ctx.accounts.launch;
+        let now = Clock::get()?.unix_timestamp;
+
+        let held_for = now - position.first_buy_timestamp as i64;
+        let verified = launch.holds_verified_balance(position.balance)
+            && held_for >= launch.config.verified_min_hold_days as i64 * 86400
+            && ctx.accounts.bundler.data_is_empty()
+            && !position.has_flag(POSITION_INSIDER)
+            && !position.has_flag(POSITION_RISK_DEBOOSTED);
+
+        if set_verified_holder(position, launch, verified) {
+            emit!(VerifiedHolderUpdated {
+                launch_id: launch.launch_id,
+                holder: position.holder,
+                verified,
+                verified_holder_count: launch.verified_holder_count,
+                schema_version: EVENT_SCHEMA_VERSION,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Permissionless: re-rank a batch of positions, passed as writable
+    /// remaining accounts, against the launch's current rank schedule. A
+    /// schedule voted in by holders then applies to every position rather
+    /// than only to those that transact next.
+    pub fn recalculate_rank<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RecalculateRank<'info>>,
+    ) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let now = Clock::get()?.unix_timestamp;
+
+        for info in ctx.remaining_accounts {
+            let mut position = Account::<Position>::try_from(info)?;
+            require_keys_eq!(
+                position.launch,
+                launch.key(),
+                DiamondPadError::PositionLaunchMismatch
+            );
+
+            let previous_rank = position.diamond_rank;
+            settle_rewards(&mut position, launch, now);
+            refresh_rank(&mut position, launch, now);
+            sync_reward_weight(&mut position, launch);
+
+            if position.diamond_rank != previous_rank {
+                emit!(PositionRankRecalculated {
+                    launch_id: launch.launch_id,
+                    holder: position.holder,
+                    previous_rank,
+                    rank: position.diamond_rank,
+                    multiplier_bps: position.multiplier_bps,
+                    schema_version: EVENT_SCHEMA_VERSION,
+                });
+            }
+            position.exit(&crate::ID)?;
+        }
+
+        Ok(())
+    }
+
+    /// Permissionless health check: recompute a launch's key invariants and
+    /// emit an AuditReport with an AUDIT_* bit set for each that fails.
+    /// Positions to sample go in `remaining_accounts`, sorted by address
+    /// so none is counted twice. Changes nothing.
+    // `Option::is_none_or` is newer than the SBF toolchain's rustc
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn audit_launch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AuditLaunch<'info>>,
+    ) -> Result<()> {
+        let launch = &ctx.accounts.launch;
+        let now = Clock::get()?.unix_timestamp;
+        let mut violations = 0u8;
+
+        if launch.supply_ledger().check(launch.total_supply).is_err() {
+            violations |= AUDIT_SUPPLY;
+        }
+
+        // The curve's raise sits in the SOL vault until graduation, and
+        // what hasn't been refunded stays there through the refund window
+        let sol_vault_balance = withdrawable_lamports(&ctx.accounts.sol_vault)?;
+        let sol_vault_expected = match launch.status {
+            LaunchStatus::Pending | LaunchStatus::Active => launch.total_raised,
+            LaunchStatus::Failed if now <= launch.refund_deadline => {
+                launch.total_raised.saturating_sub(launch.total_refunded)
+            }
+            _ => 0,
+        };
+        if sol_vault_balance < sol_vault_expected {
+            violations |= AUDIT_SOL_VAULT;
+        }
+
+        // Everything routed to rewards and not yet paid out is owed
+        let reward_vault_balance = withdrawable_lamports(&ctx.accounts.reward_vault)?;
+        let reward_liabilities =
+            launch.reward_vault_funded.saturating_sub(launch.rewards_distributed);
+        if reward_vault_balance < reward_liabilities {
+            violations |= AUDIT_REWARD_SOLVENCY;
+        }
+
+        require!(
+            ctx.remaining_accounts.len() <= MAX_AUDIT_SAMPLE,
+            DiamondPadError::InvalidAuditSample
+        );
+        let mut previous: Option<Pubkey> = None;
+        let mut sampled_live = 0u64;
+        let mut sampled_weight = 0u128;
+        for info in ctx.remaining_accounts {
+            require!(
+                previous.map_or(true, |previous| previous < info.key()),
+                DiamondPadError::InvalidAuditSample
+            );
+            previous = Some(info.key());
+
+            let position = Account::<Position>::try_from(info)?;
+            require_keys_eq!(
+                position.launch,
+                launch.key(),
+                DiamondPadError::PositionLaunchMismatch
+            );
+            if position.balance > 0 {
+                sampled_live += 1;
+            }
+            sampled_weight += position.reward_weight;
+        }
+        if sampled_live > launch.holder_count {
+            violations |= AUDIT_HOLDER_COUNT;
+        }
+        if sampled_weight > launch.total_reward_weight {
+            violations |= AUDIT_REWARD_WEIGHT;
+        }
+
+        emit!(AuditReport {
+            launch_id: launch.launch_id,
+            status: launch.status,
+            violations,
+            sol_vault_balance,
+            sol_vault_expected,
+            reward_vault_balance,
+            reward_liabilities,
+            holder_count: launch.holder_count,
+            sampled_positions: ctx.remaining_accounts.len() as u16,
+            sampled_live,
+            timestamp: now,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Holder Profiles ============
+
+    /// Open a HolderProfile for lifetime stats without linking any wallet
+    pub fn create_holder_profile(ctx: Context<CreateHolderProfile>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let profile_key = ctx.accounts.profile.key();
+
+        let owner_link = &mut ctx.accounts.owner_link;
+        owner_link.wallet = owner;
+        owner_link.profile = profile_key;
+        owner_link.bump = ctx.bumps.owner_link;
+
+        let profile = &mut ctx.accounts.profile;
+        profile.owner = owner;
+        profile.wallets.push(owner);
+        profile.bump = ctx.bumps.profile;
+
+        Ok(())
+    }
+
+    /// Fold a position into its profile's lifetime stats: counts the launch
+    /// once, and keeps the best rank and longest hold seen. Permissionless;
+    /// the position must belong to one of the profile's wallets.
+    pub fn sync_holder_stats(ctx: Context<SyncHolderStats>) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let position = &mut ctx.accounts.position;
+        let profile = &mut ctx.accounts.profile;
+        require!(
+            profile.wallets.contains(&position.holder),
+            DiamondPadError::WalletNotLinked
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        settle_rewards(position, launch, now);
+        refresh_rank(position, launch, now);
+        sync_reward_weight(position, launch);
+        profile.record_position(position, now);
+
+        Ok(())
+    }
+
+    /// Unlock an achievement on the caller's profile. Evidence is passed as
+    /// `(launch, position)` pairs in the remaining accounts, each position
+    /// held by one of the profile's wallets: one pair for FirstDiamond and
+    /// DrawdownSurvivor, GRADUATED_LAUNCHES_FOR_VETERAN distinct launches
+    /// for GraduationVeteran.
+    pub fn unlock_achievement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UnlockAchievement<'info>>,
+        achievement: Achievement,
+    ) -> Result<()> {
+        let profile = &mut ctx.accounts.profile;
+        require!(
+            profile.achievements & achievement.bit() == 0,
+            DiamondPadError::AchievementAlreadyUnlocked
+        );
+        let pairs = ctx.remaining_accounts.chunks_exact(2);
+        require!(
+            pairs.len() > 0 && pairs.remainder().is_empty(),
+            DiamondPadError::AchievementNotEarned
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut evidence = Vec::new();
+        for pair in pairs {
+            let launch = Account::<Launch>::try_from(&pair[0])?;
+            let position = Account::<Position>::try_from(&pair[1])?;
+            require_keys_eq!(position.launch, launch.key(), DiamondPadError::AchievementNotEarned);
+            require!(
+                profile.wallets.contains(&position.holder),
+                DiamondPadError::WalletNotLinked
+            );
+            evidence.push((launch, position));
+        }
+
+        let earned = match achievement {
+            Achievement::FirstDiamond => evidence.iter().any(|(launch, position)| {
+                let threshold_days = &launch.rank_thresholds();
+                position.balance > 0
+                    && calculate_diamond_rank(position.rank_since(), now, threshold_days)
+                        == DiamondRank::Diamond
+            }),
+            Achievement::DrawdownSurvivor => evidence.iter().any(|(launch, position)| {
+                position.balance > 0
+                    && launch.price_history.max_drawdown_bps_since(position.rank_since())
+                        >= ACHIEVEMENT_DRAWDOWN_BPS
+            }),
+            Achievement::GraduationVeteran => {
+                let mut graduated: Vec<Pubkey> = evidence
+                    .iter()
+                    .filter(|(launch, _)| launch.status == LaunchStatus::Graduated)
+                    .map(|(launch, _)| launch.key())
+                    .collect();
+                graduated.sort();
+                graduated.dedup();
+                graduated.len() >= GRADUATED_LAUNCHES_FOR_VETERAN
+            }
+        };
+        require!(earned, DiamondPadError::AchievementNotEarned);
+
+        profile.achievements |= achievement.bit();
+
+        emit!(AchievementUnlocked {
+            owner: profile.owner,
+            achievement,
+            achievements: profile.achievements,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Usernames ============
+
+    /// Claim a unique handle for the caller's profile. Handles are stored
+    /// lowercase, so the PDA seed is the canonical name.
+    pub fn register_username(ctx: Context<RegisterUsername>, name: String) -> Result<()> {
+        validate_username(&name)?;
+        let profile = &mut ctx.accounts.profile;
+        require!(profile.username.is_empty(), DiamondPadError::UsernameAlreadySet);
+
+        let now = Clock::get()?.unix_timestamp;
+        let username = &mut ctx.accounts.username;
+        username.name = name.clone();
+        username.profile = profile.key();
+        username.registered_at = now;
+        username.locked_until = now + USERNAME_LOCK_DAYS * 86400;
+        username.bump = ctx.bumps.username;
+        profile.username = name.clone();
+
+        emit!(UsernameUpdated {
+            name,
+            profile: username.profile,
+            previous_profile: Pubkey::default(),
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Give up the profile's handle so anyone can register it
+    pub fn release_username(ctx: Context<ReleaseUsername>) -> Result<()> {
+        let username = &ctx.accounts.username;
+        require!(
+            Clock::get()?.unix_timestamp >= username.locked_until,
+            DiamondPadError::UsernameLocked
+        );
+        ctx.accounts.profile.username.clear();
+
+        emit!(UsernameUpdated {
+            name: username.name.clone(),
+            profile: Pubkey::default(),
+            previous_profile: username.profile,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Hand the profile's handle to another profile. The receiving owner
+    /// signs too, and must not already have a handle.
+    pub fn transfer_username(ctx: Context<TransferUsername>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let username = &mut ctx.accounts.username;
+        require!(now >= username.locked_until, DiamondPadError::UsernameLocked);
+        require!(
+            ctx.accounts.new_profile.username.is_empty(),
+            DiamondPadError::UsernameAlreadySet
+        );
+
+        let previous_profile = username.profile;
+        username.profile = ctx.accounts.new_profile.key();
+        username.locked_until = now + USERNAME_LOCK_DAYS * 86400;
+        ctx.accounts.new_profile.username = username.name.clone();
+        ctx.accounts.profile.username.clear();
+
+        emit!(UsernameUpdated {
+            name: username.name.clone(),
+            profile: username.profile,
+            previous_profile,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Linked Wallets ============
+
+    /// Link `wallet` into the owner's HolderProfile. Both keys sign, and a
+    /// wallet can only ever belong to one profile.
+    pub fn link_wallet(ctx: Context<LinkWallet>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let wallet = ctx.accounts.wallet.key();
+        let profile_key = ctx.accounts.profile.key();
+        require_keys_neq!(owner, wallet, DiamondPadError::WalletAlreadyLinked);
+
+        let owner_link = &mut ctx.accounts.owner_link;
+        if owner_link.wallet == Pubkey::default() {
+            owner_link.wallet = owner;
+            owner_link.profile = profile_key;
+            owner_link.bump = ctx.bumps.owner_link;
+        }
+        require_keys_eq!(owner_link.profile, profile_key, DiamondPadError::WalletAlreadyLinked);
+
+        let profile = &mut ctx.accounts.profile;
+        if profile.owner == Pubkey::default() {
+            profile.owner = owner;
+            profile.wallets.push(owner);
+            profile.bump = ctx.bumps.profile;
+        }
+        require!(
+            profile.wallets.len() < MAX_LINKED_WALLETS,
+            DiamondPadError::TooManyLinkedWallets
+        );
+        profile.wallets.push(wallet);
+
+        let wallet_link = &mut ctx.accounts.wallet_link;
+        wallet_link.wallet = wallet;
+        wallet_link.profile = profile_key;
+        wallet_link.bump = ctx.bumps.wallet_link;
+
+        emit!(WalletLinked {
+            profile: profile_key,
+            owner,
+            wallet,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Take a wallet back out of a HolderProfile, signed by the profile
+    /// owner or the wallet itself. The owner's own wallet stays. Entries
+    /// positions borrowed through the link can then be dropped with
+    /// `sync_linked_rank`.
+    pub fn unlink_wallet(ctx: Context<UnlinkWallet>) -> Result<()> {
+        let signer = ctx.accounts.signer.key();
+        let profile = &mut ctx.accounts.profile;
+        let wallet = ctx.accounts.wallet_link.wallet;
+        require!(
+            signer == profile.owner || signer == wallet,
+            DiamondPadError::Unauthorized
+        );
+        require_keys_neq!(wallet, profile.owner, DiamondPadError::ProfileOwnerWallet);
+        profile.wallets.retain(|linked| *linked != wallet);
+
+        emit!(WalletUnlinked {
+            profile: profile.key(),
+            wallet,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Let a position rank from the entry of a linked wallet's position in
+    /// the same launch, averaged with its own by the balance behind each.
+    /// Permissionless: passing the position it borrowed from after that
+    /// one sold, fell below MIN_LINKED_SOURCE_BPS or was unlinked drops
+    /// the borrowed entry again.
+    pub fn sync_linked_rank(ctx: Context<SyncLinkedRank>) -> Result<()> {
+        let profile = &ctx.accounts.profile;
+        let source_key = ctx.accounts.source_position.key();
+        let source = &ctx.accounts.source_position;
+        let launch = &mut ctx.accounts.launch;
+        let position = &mut ctx.accounts.position;
+
+        let linked = profile.wallets.contains(&position.holder)
+            && profile.wallets.contains(&source.holder);
+        let min_source_balance = (position.balance as u128 * MIN_LINKED_SOURCE_BPS as u128
+            / 10000) as u64;
+        let lends = linked && source.balance > 0 && source.balance >= min_source_balance;
+
+        let now = Clock::get()?.unix_timestamp;
+        settle_rewards(position, launch, now);
+        if position.linked_source == source_key
+            && (!lends || source.balance < position.linked_source_balance)
+        {
+            position.clear_linked_entry();
+        } else {
+            require!(linked, DiamondPadError::WalletNotLinked);
+            require!(
+                position.balance > 0 && lends,
+                DiamondPadError::InsufficientBalance
+            );
+            // A dust position can only pull the average so far
+            let (own, lent) = (position.balance as u128, source.balance as u128);
+            let since = (position.first_buy_timestamp as u128 * own
+                + source.first_buy_timestamp as u128 * lent)
+                / (own + lent);
+            if position.linked_source == source_key || (since as i64) < position.rank_since() {
+                position.linked_since = since as u32;
+                position.linked_source = source_key;
+                position.linked_source_balance = source.balance;
+            }
+        }
+        refresh_rank(position, launch, now);
+        sync_reward_weight(position, launch);
+
+        emit!(PositionUpdated {
+            holder: position.holder,
+            launch: position.launch,
+            balance: position.balance,
+            diamond_rank: position.diamond_rank,
+            multiplier_bps: position.multiplier_bps,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Bundler Flags ============
+
+    /// Create the bundler statistics rollup; anyone can pay for it once
+    pub fn init_bundler_stats(ctx: Context<InitBundlerStats>) -> Result<()> {
+        ctx.accounts.bundler_stats.load_init()?.bump = ctx.bumps.bundler_stats;
+        Ok(())
+    }
+
+    /// Grant a key (e.g. an off-chain detection service) bundler-flagging powers only
+    pub fn add_flagger(ctx: Context<AddFlagger>, key: Pubkey) -> Result<()> {
+        let flagger = &mut ctx.accounts.flagger_registration;
+        flagger.key = key;
+        flagger.added_at = Clock::get()?.unix_timestamp;
+        flagger.bump = ctx.bumps.flagger_registration;
+
+        emit!(FlaggerUpdated {
+            key,
+            active: true,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::AddFlagger,
+            key,
+            AuditValue::Bool(false),
+            AuditValue::Bool(true),
+        )?;
+
+        Ok(())
+    }
+
+    /// Revoke a delegated flagger key
+    pub fn remove_flagger(ctx: Context<RemoveFlagger>) -> Result<()> {
+        emit!(FlaggerUpdated {
+            key: ctx.accounts.flagger_registration.key,
+            active: false,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::RemoveFlagger,
+            ctx.accounts.flagger_registration.key,
+            AuditValue::Bool(true),
+            AuditValue::Bool(false),
+        )?;
+
+        Ok(())
+    }
+
+    /// Open a pending bundler flag; counts as the proposer's approval. A
+    /// per-launch moderator can open one too, but it starts with no
+    /// approvals and waits on the protocol's flaggers.
+    pub fn propose_flag(
+        ctx: Context<ProposeFlag>,
+        evidence_hash: [u8; 32],
+        evidence_uri: String,
+    ) -> Result<()> {
+        let flagger = ctx.accounts.flagger.key();
+        let approves = is_flag_signer(
+            &ctx.accounts.protocol,
+            &flagger,
+            ctx.accounts.flagger_registration.is_some(),
+        );
+        require!(
+            approves || ctx.accounts.mod_assignment.is_some(),
+            DiamondPadError::NotAFlagger
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let pending_flag = &mut ctx.accounts.pending_flag;
+        pending_flag.wallet = ctx.accounts.flagged_wallet.key();
+        pending_flag.proposer = flagger;
+        pending_flag.evidence = evidence_entry(flagger, evidence_hash, evidence_uri, now)?;
+        pending_flag.approvals = if approves { vec![flagger] } else { vec![] };
+        pending_flag.proposed_at = now;
+        pending_flag.bump = ctx.bumps.pending_flag;
+
+        emit!(FlagProposed {
+            wallet: pending_flag.wallet,
+            proposer: flagger,
+            threshold: ctx.accounts.protocol.flag_threshold,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Add another flagger's approval to a pending flag
+    pub fn cosign_flag(ctx: Context<CosignFlag>) -> Result<()> {
+        let flagger = ctx.accounts.flagger.key();
+        require!(
+            is_flag_signer(
+                &ctx.accounts.protocol,
+                &flagger,
+                ctx.accounts.flagger_registration.is_some()
+            ),
+            DiamondPadError::NotAFlagger
+        );
+
+        let pending_flag = &mut ctx.accounts.pending_flag;
+        require!(
+            !pending_flag.is_expired(Clock::get()?.unix_timestamp),
+            DiamondPadError::FlagExpired
+        );
+        require!(
+            !pending_flag.approvals.contains(&flagger),
+            DiamondPadError::AlreadyApprovedFlag
+        );
+        require!(
+            pending_flag.approvals.len() < MAX_FLAG_APPROVALS,
+            DiamondPadError::TooManyFlagApprovals
+        );
+        pending_flag.approvals.push(flagger);
+
+        emit!(FlagCosigned {
+            wallet: pending_flag.wallet,
+            flagger,
+            approvals: pending_flag.approvals.len() as u8,
+            threshold: ctx.accounts.protocol.flag_threshold,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Relay a bundler report signed off-chain by the protocol's detector.
+    /// The report counts as the detector's approval on the wallet's pending
+    /// flag, opening one if needed; enforcement still follows the threshold.
+    /// Each signed report can be relayed only once.
+    pub fn submit_detection_report(
+        ctx: Context<SubmitDetectionReport>,
+        wallet: Pubkey,
+        evidence_hash: [u8; 32],
+        evidence_uri: String,
+        reported_at: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            reported_at <= now && now - reported_at <= MAX_REPORT_AGE_SECONDS,
+            DiamondPadError::StaleDetectionReport
+        );
+
+        // The default key means no feed has been delegated; never treat it
+        // as a signer
+        let detector = ctx.accounts.protocol.detector;
+        require!(detector != Pubkey::default(), DiamondPadError::DetectorNotSet);
+        let message =
+            detection::report_message(&wallet, &evidence_hash, &evidence_uri, reported_at);
+        detection::verify_signed_report(&ctx.accounts.instructions, &detector, &message)?;
+
+        let receipt = &mut ctx.accounts.report_receipt;
+        receipt.wallet = wallet;
+        receipt.detector = detector;
+        receipt.report_hash =
+            detection::report_hash(&wallet, &evidence_hash, &evidence_uri, reported_at);
+        receipt.submitted_at = now;
+        receipt.bump = ctx.bumps.report_receipt;
+
+        let threshold = ctx.accounts.protocol.flag_threshold;
+        let pending_flag = &mut ctx.accounts.pending_flag;
+        if pending_flag.wallet == Pubkey::default() {
+            pending_flag.wallet = wallet;
+            pending_flag.proposer = ctx.accounts.relayer.key();
+            pending_flag.evidence = evidence_entry(detector, evidence_hash, evidence_uri, now)?;
+            pending_flag.approvals = vec![detector];
+            pending_flag.proposed_at = now;
+            pending_flag.bump = ctx.bumps.pending_flag;
+
+            emit!(FlagProposed {
+                wallet,
+                proposer: detector,
+                threshold,
+                schema_version: EVENT_SCHEMA_VERSION,
+            });
+        } else {
+            require!(!pending_flag.is_expired(now), DiamondPadError::FlagExpired);
+            require!(
+                !pending_flag.approvals.contains(&detector),
+                DiamondPadError::AlreadyApprovedFlag
+            );
+            require!(
+                pending_flag.approvals.len() < MAX_FLAG_APPROVALS,
+                DiamondPadError::TooManyFlagApprovals
+            );
+            pending_flag.approvals.push(detector);
+
+            emit!(FlagCosigned {
+                wallet,
+                flagger: detector,
+                approvals: pending_flag.approvals.len() as u8,
+                threshold,
+                schema_version: EVENT_SCHEMA_VERSION,
+            });
+        }
+
+        emit!(DetectionReportAccepted {
+            wallet,
+            detector,
+            evidence_hash,
+            reported_at,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Enforce a pending flag that has reached the approval threshold. A
+    /// confirmed bundler forfeits holder rewards outright; a suspected one
+    /// keeps a share that halves every reward epoch. Approvers' flagger
+    /// registrations go in `remaining_accounts`; approvals from flaggers
+    /// removed since they signed don't count.
+    pub fn flag_bundler(ctx: Context<FlagBundler>, severity: BundlerSeverity) -> Result<()> {
+        let pending_flag = &ctx.accounts.pending_flag;
+        let protocol = &mut ctx.accounts.protocol;
+
+        require!(
+            is_flag_signer(
+                protocol,
+                &ctx.accounts.flagger.key(),
+                ctx.accounts.flagger_registration.is_some()
+            ),
+            DiamondPadError::NotAFlagger
+        );
+        let now = Clock::get()?.unix_timestamp;
+        require!(!pending_flag.is_expired(now), DiamondPadError::FlagExpired);
+        let approvals =
+            current_flag_approvals(protocol, &pending_flag.approvals, ctx.remaining_accounts)?;
+        require!(
+            approvals >= protocol.flag_threshold as usize,
+            DiamondPadError::FlagThresholdNotMet
+        );
+
+        let bundler = &mut ctx.accounts.bundler;
+        bundler.wallet = pending_flag.wallet;
+        bundler.flagged_at = now;
+        bundler.evidence = vec![pending_flag.evidence.clone()];
+        bundler.incident_count = 1;
+        bundler.bump = ctx.bumps.bundler;
+        bundler.severity = severity;
+
+        ctx.accounts.bundler_stats.load_mut()?.record_flag(bundler.flagged_at);
+
+        emit!(BundlerFlagged {
+            wallet: bundler.wallet,
+            evidence: pending_flag.evidence.clone(),
+            schema_version: EVENT_SCHEMA_VERSION,
+            severity,
+        });
+        audit_admin_action(
+            ctx.accounts.flagger.key(),
+            AdminAction::FlagBundler,
+            bundler.wallet,
+            AuditValue::Bool(false),
+            AuditValue::Bool(true),
+        )?;
+
+        Ok(())
+    }
+
+    /// Drop a pending flag without enforcing it, refunding its rent to the
+    /// proposer. Also frees the wallet's flag PDA once a flag has expired.
+    pub fn cancel_flag(ctx: Context<CancelFlag>) -> Result<()> {
+        let pending_flag = &ctx.accounts.pending_flag;
+        let now = Clock::get()?.unix_timestamp;
+
+        emit!(FlagCancelled {
+            wallet: pending_flag.wallet,
+            cancelled_by: ctx.accounts.authority.key(),
+            expired: pending_flag.is_expired(now),
+            approvals: pending_flag.approvals.len() as u8,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::CancelFlag,
+            pending_flag.wallet,
+            AuditValue::Timestamp(pending_flag.proposed_at),
+            AuditValue::None,
+        )?;
+
+        Ok(())
+    }
+
+    /// Append a new piece of evidence to an enforced bundler record
+    pub fn add_evidence(
+        ctx: Context<AddEvidence>,
+        evidence_hash: [u8; 32],
+        evidence_uri: String,
+    ) -> Result<()> {
+        let flagger = ctx.accounts.flagger.key();
+        require!(
+            is_flag_signer(
+                &ctx.accounts.protocol,
+                &flagger,
+                ctx.accounts.flagger_registration.is_some()
+            ),
+            DiamondPadError::NotAFlagger
+        );
+
+        let bundler = &mut ctx.accounts.bundler;
+        require!(
+            bundler.evidence.len() < MAX_EVIDENCE_ENTRIES,
+            DiamondPadError::EvidenceLogFull
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let entry = evidence_entry(flagger, evidence_hash, evidence_uri, now)?;
+        bundler.evidence.push(entry.clone());
+        bundler.incident_count += 1;
+
+        emit!(EvidenceAdded {
+            wallet: bundler.wallet,
+            evidence: entry,
+            incident_count: bundler.incident_count,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Look up a wallet in the bundler registry. Meant for CPI from other
+    /// launchpads and routers: the answer comes back as return data, so
+    /// callers don't need to deserialize DiamondPad accounts themselves.
+    pub fn check_bundler(ctx: Context<CheckBundler>, _wallet: Pubkey) -> Result<BundlerStatus> {
+        let record = &ctx.accounts.bundler;
+        if record.owner != &crate::ID || record.data_is_empty() {
+            return Ok(BundlerStatus {
+                flagged: false,
+                flagged_at: 0,
+                incident_count: 0,
+                severity: BundlerSeverity::Confirmed,
+            });
+        }
+
+        let bundler = Bundler::try_deserialize(&mut &record.data.borrow()[..])?;
+        Ok(BundlerStatus {
+            flagged: true,
+            flagged_at: bundler.flagged_at,
+            incident_count: bundler.incident_count,
+            severity: bundler.severity,
+        })
+    }
+
+    // ============ Referrals ============
+
+    /// Claim a human-readable referral code that pays out to `payout_wallet`
+    pub fn register_referral_code(
+        ctx: Context<RegisterReferralCode>,
+        code: String,
+        payout_wallet: Pubkey,
+    ) -> Result<()> {
+        require!(
+            !code.is_empty() && code.len() <= MAX_REFERRAL_CODE_LEN,
+            DiamondPadError::InvalidReferralCode
+        );
+        require!(
+            code.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'),
+            DiamondPadError::InvalidReferralCode
+        );
+
+        let referral = &mut ctx.accounts.referral_code;
+        require!(
+            referral.owner == Pubkey::default(),
+            DiamondPadError::ReferralCodeTaken
+        );
+
+        referral.owner = ctx.accounts.owner.key();
+        referral.payout_wallet = payout_wallet;
+        referral.code = code.clone();
+        referral.created_at = Clock::get()?.unix_timestamp;
+        referral.bump = ctx.bumps.referral_code;
+
+        emit!(ReferralCodeRegistered {
+            code,
+            owner: referral.owner,
+            payout_wallet,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Open a launch's referral leaderboard; anyone can pay for it
+    pub fn open_referral_board(ctx: Context<OpenReferralBoard>) -> Result<()> {
+        let board = &mut ctx.accounts.referral_board;
+        board.launch = ctx.accounts.launch.key();
+        board.bump = ctx.bumps.referral_board;
+        Ok(())
+    }
+
+    /// Start tracking a referral code's referred volume on a launch. Only
+    /// buys made while the stats account exists are counted.
+    pub fn open_referral_stats(ctx: Context<OpenReferralStats>) -> Result<()> {
+        let stats = &mut ctx.accounts.referral_stats;
+        stats.launch = ctx.accounts.launch.key();
+        stats.referral_code = ctx.accounts.referral_code.key();
+        stats.bump = ctx.bumps.referral_stats;
+        Ok(())
+    }
+
+    /// Bring a code's referred volume on the board up to date with its
+    /// stats, for volume the board missed. Anyone can call it until the
+    /// first bonus is paid.
+    pub fn sync_referral_board(ctx: Context<SyncReferralBoard>) -> Result<()> {
+        let stats = &ctx.accounts.referral_stats;
+        let board = &mut ctx.accounts.referral_board;
+        require!(board.bonus_claimed == 0, DiamondPadError::ReferralBoardFinal);
+        board.record(stats.referral_code, stats.referred_volume);
+        Ok(())
+    }
+
+    /// Creator adds lamports to the bonus pool the top referrers split at
+    /// graduation
+    pub fn fund_referral_bonus(ctx: Context<FundReferralBonus>, amount: u64) -> Result<()> {
+        require!(amount > 0, DiamondPadError::InvalidAmount);
+        let launch = &ctx.accounts.launch;
+        require!(
+            launch.status == LaunchStatus::Pending || launch.status == LaunchStatus::Active,
+            DiamondPadError::LaunchNotOpen
+        );
+        transfer_lamports(
+            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.referral_board.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            amount,
+            &[],
+        )?;
+
+        let board = &mut ctx.accounts.referral_board;
+        board.bonus_pool = board.bonus_pool.checked_add(amount).unwrap();
+
+        emit!(ReferralBonusFunded {
+            launch_id: launch.launch_id,
+            amount,
+            bonus_pool: board.bonus_pool,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Pay a leaderboard referrer their share of the bonus pool, pro rata
+    /// to referred volume among the leaders. The board is final once the
+    /// launch graduates, since curve buys stop then.
+    pub fn claim_referral_bonus(ctx: Context<ClaimReferralBonus>) -> Result<()> {
+        let stats = &mut ctx.accounts.referral_stats;
+        let board = &mut ctx.accounts.referral_board;
+        require!(!stats.bonus_claimed, DiamondPadError::NothingToClaim);
+        let leader = board
+            .leaders
+            .iter()
+            .find(|leader| leader.referral_code == stats.referral_code)
+            .ok_or(DiamondPadError::NotReferralLeader)?;
+        require!(
+            leader.volume == stats.referred_volume,
+            DiamondPadError::ReferralStatsMismatch
+        );
+
+        let amount = (board.bonus_pool as u128 * leader.volume as u128
+            / board.leader_volume() as u128) as u64;
+        stats.bonus_claimed = true;
+        board.bonus_claimed = board.bonus_claimed.checked_add(amount).unwrap();
+        board.sub_lamports(amount)?;
+        ctx.accounts.payout_wallet.add_lamports(amount)?;
+
+        emit!(ReferralBonusClaimed {
+            launch_id: ctx.accounts.launch.launch_id,
+            referral_code: stats.referral_code,
+            referred_volume: stats.referred_volume,
+            amount,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Creator takes back a bonus pool nobody can claim: the launch failed,
+    /// or it graduated with no referred volume
+    pub fn reclaim_referral_bonus(ctx: Context<ReclaimReferralBonus>) -> Result<()> {
+        let launch = &ctx.accounts.launch;
+        let board = &mut ctx.accounts.referral_board;
+        require!(
+            launch.status == LaunchStatus::Failed
+                || (launch.status == LaunchStatus::Graduated && board.leaders.is_empty()),
+            DiamondPadError::NothingToClaim
+        );
+        let amount = board.bonus_pool - board.bonus_claimed;
+        require!(amount > 0, DiamondPadError::NothingToClaim);
+
+        board.bonus_claimed = board.bonus_pool;
+        board.sub_lamports(amount)?;
+        ctx.accounts.creator.add_lamports(amount)?;
+
+        emit!(ReferralBonusReclaimed {
+            launch_id: launch.launch_id,
+            amount,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // ============ Seasons ============
+
+    /// Open the next protocol-wide season once the current one has ended.
+    /// Scores are kept per season, so lifetime stats are never reset.
+    pub fn start_season(ctx: Context<StartSeason>, starts_at: i64, ends_at: i64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let protocol = &mut ctx.accounts.protocol;
+        require!(now >= protocol.season_ends_at, DiamondPadError::SeasonStillActive);
+        require!(
+            starts_at >= now && ends_at > starts_at,
+            DiamondPadError::InvalidSeasonWindow
+        );
+
+        protocol.current_season += 1;
+        protocol.season_ends_at = ends_at;
+
+        let season = &mut ctx.accounts.season;
+        season.season_id = protocol.current_season;
+        season.starts_at = starts_at;
+        season.ends_at = ends_at;
+        season.bump = ctx.bumps.season;
+
+        emit!(SeasonStarted {
+            season_id: season.season_id,
+            starts_at,
+            ends_at,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+        audit_admin_action(
+            ctx.accounts.authority.key(),
+            AdminAction::StartSeason,
+            season.key(),
+            AuditValue::None,
+            AuditValue::Amount(season.season_id as u64),
+        )
+    }
+
+    /// Open the caller's score account for the current season
+    pub fn join_season(ctx: Context<JoinSeason>) -> Result<()> {
+        let season = &mut ctx.accounts.season;
+        require!(
+            Clock::get()?.unix_timestamp < season.ends_at,
+            DiamondPadError::SeasonEnded
+        );
+        season.participants += 1;
+
+        let score = &mut ctx.accounts.season_score;
+        score.season = season.key();
+        score.wallet = ctx.accounts.wallet.key();
+        score.bump = ctx.bumps.season_score;
+
+        emit!(SeasonJoined {
+            season_id: season.season_id,
+            wallet: score.wallet,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+
+        Ok(())
+    }
+}
+
+// ============ Constants ============
+
+/// LP lock extension that earns one step of the relock incentive
+pub const RELOCK_MIN_EXTENSION_DAYS: i64 = 90;
+/// Holder rewards boost per RELOCK_MIN_EXTENSION_DAYS of combined extension
+pub const RELOCK_BONUS_BPS: u16 = 50;
+/// Holder rewards boost for permanently burning the LP lock
+pub const LP_BURN_BONUS_BPS: u16 = 200;
+/// Hard cap on holder_rewards_bps, including relock boosts
+pub const MAX_HOLDER_REWARDS_BPS: u16 = 2000;
+/// How long creator stake stays locked after backing a new launch
+pub const CREATOR_STAKE_LOCK_DAYS: i64 = 180;
+/// Most launch applications the review queue can hold at once
+pub const MAX_REVIEW_QUEUE: usize = 32;
+/// Days reviewers have to decide on a launch application
+pub const REVIEW_WINDOW_DAYS: i64 = 7;
+/// Most insider wallets (creator included) a launch can declare
+pub const MAX_INSIDERS: usize = 8;
+/// Longest launch or template name
+pub const MAX_NAME_LEN: usize = 32;
+/// Longest launch ticker symbol
+pub const MAX_SYMBOL_LEN: usize = 10;
+// Feature flags: subsystems governance can switch off without an upgrade.
+// The protocol stores the bits switched off, so zero means all on.
+pub const FEATURE_TOKEN2022_MINTS: u32 = 1 << 0;
+pub const FEATURE_TRANSFER_FEES: u32 = 1 << 1;
+pub const FEATURE_LOTTERY_POOLS: u32 = 1 << 2;
+/// Delay between scheduling and applying a feature flag change
+pub const FEATURE_FLAG_TIMELOCK_DAYS: i64 = 2;
+/// Schema version stamped on every event
+pub const EVENT_SCHEMA_VERSION: u8 = 1;
+/// Spare bytes allocated on every account so later fields fit without a realloc
+pub const ACCOUNT_RESERVE: usize = 64;
+/// Current Position layout; written when a position is opened. Layout 1 is
+/// the original, which has no version byte.
+pub const POSITION_LAYOUT_VERSION: u8 = 5;
+/// Share of the borrowing position's balance (bps) a linked position must
+/// hold to lend it its entry
+pub const MIN_LINKED_SOURCE_BPS: u64 = 1000;
+/// Size of a layout 1 Position
+pub const LEGACY_POSITION_SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 2 + 8 + 1 + 64;
+/// Size of a Launch from before launches carried a config
+pub const LEGACY_LAUNCH_SIZE: usize =
+    8 + 32 + 36 + 14 + 8 + 2 + 2 + 2 + 2 + 8 + 8 + 1 + 8 + 8 + 2 * 7 + 1 + 64;
+// Position flag bits
+/// Permanent tag for declared team wallets (zero reward multiplier)
+pub const POSITION_INSIDER: u8 = 1 << 0;
+pub const POSITION_REWARDS_FORFEITED: u8 = 1 << 1;
+/// Bought while above the launch's risk threshold; earns no rank boost
+pub const POSITION_RISK_DEBOOSTED: u8 = 1 << 2;
+pub const POSITION_REFERRAL_QUALIFIED: u8 = 1 << 3;
+/// Already counted in the holder's profile launch tally
+pub const POSITION_COUNTED_IN_PROFILE: u8 = 1 << 4;
+/// Counted in the launch's verified holder count
+pub const POSITION_VERIFIED: u8 = 1 << 5;
+/// Hard cap on the share of protocol fees rebated to a launch's holders
+pub const MAX_FEE_REBATE_BPS: u16 = 5000;
+/// Hard cap on the share of a launch's protocol fees paid to its channel
+pub const MAX_CHANNEL_FEE_SHARE_BPS: u16 = 5000;
+/// Base fee the runtime charges per transaction signature, in lamports
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5000;
+/// Most a launch's gas pool can refund on one claim: the base fee of a
+/// claim signed by the holder and a separate fee payer
+pub const MAX_GAS_REFUND_PER_CLAIM: u64 = 2 * LAMPORTS_PER_SIGNATURE;
+/// Smallest claim, in lamports, that gets its gas refunded, so the pool
+/// can't be drained one dust claim at a time
+pub const MIN_GAS_REFUNDED_CLAIM: u64 = 1_000_000;
+
+/// Launch parameter groups a creator can freeze with finalize_launch_config
+pub const FREEZE_FEES: u16 = 1 << 0;
+pub const FREEZE_VESTING: u16 = 1 << 1;
+pub const FREEZE_SELL_POLICY: u16 = 1 << 2;
+pub const FREEZE_LP_LOCK: u16 = 1 << 3;
+pub const FREEZE_SALE_WINDOW: u16 = 1 << 4;
+pub const FREEZE_ALL: u16 =
+    FREEZE_FEES | FREEZE_VESTING | FREEZE_SELL_POLICY | FREEZE_LP_LOCK | FREEZE_SALE_WINDOW;
+/// Creator safety steps tracked by CreatorChecklist
+pub const CHECKLIST_METADATA_SET: u8 = 1 << 0;
+pub const CHECKLIST_MINT_AUTHORITY_REVOKED: u8 = 1 << 1;
+pub const CHECKLIST_LP_LOCKED: u8 = 1 << 2;
+pub const CHECKLIST_VESTING_FUNDED: u8 = 1 << 3;
+pub const CHECKLIST_STEPS: usize = 4;
+/// Launch activity moderators can pause independently
+pub const PAUSE_TRADING: u8 = 1 << 0;
+pub const PAUSE_CLAIMS: u8 = 1 << 1;
+/// Bounds on the virtual SOL a constant-product curve starts with
+pub const MIN_VIRTUAL_SOL_RESERVES: u64 = 1_000_000_000;
+pub const MAX_VIRTUAL_SOL_RESERVES: u64 = 1_000_000_000_000;
+/// Bounds on virtual token reserves, as a share of the sale supply
+pub const MIN_VIRTUAL_TOKEN_RESERVES_BPS: u16 = 10000;
+pub const MAX_VIRTUAL_TOKEN_RESERVES_BPS: u16 = 20000;
+/// How long contributors to a failed launch have to claim refunds
+pub const REFUND_WINDOW_DAYS: i64 = 30;
+/// How long after graduation buyers have to claim their sale tokens
+/// before what's left is swept to the reward vault
+pub const SALE_CLAIM_WINDOW_DAYS: i64 = 90;
+/// A launch with no buys at all for this long can be expired by anyone
+pub const INACTIVITY_EXPIRY_DAYS: i64 = 14;
+/// Longest soft cap deadline a launch can set
+pub const MAX_SALE_DURATION_DAYS: u16 = 90;
+/// Longest metadata URI written onto a launch mint
+pub const MAX_METADATA_URI_LEN: usize = 200;
+/// Largest share of supply a launch can reserve for its graduation airdrop
+pub const MAX_GRADUATION_AIRDROP_BPS: u16 = 500;
+/// Largest share of the raise a launch can pay into holder rewards at graduation
+pub const MAX_GRADUATION_REWARD_BPS: u16 = 2000;
+/// Longest hold a launch can require before a position may claim rewards
+pub const MAX_MIN_HOLD_BEFORE_CLAIM_DAYS: u16 = 30;
+/// Largest share of the trade fee a loyalty discount can waive
+pub const MAX_LOYALTY_DISCOUNT_BPS: u16 = 5000;
+/// Longest opening window a launch can reserve for Gold+ holders
+pub const MAX_PRIORITY_ACCESS_HOURS: u16 = 72;
+/// Largest minimum wallet age a launch can require (~30 days of slots)
+pub const MAX_MIN_WALLET_AGE_SLOTS: u64 = 6_480_000;
+/// Longest hold a launch can require before counting a verified holder
+pub const MAX_VERIFIED_MIN_HOLD_DAYS: u16 = 180;
+/// Most top holders a graduation airdrop can be split between
+pub const MAX_AIRDROP_RECIPIENTS: u16 = 1000;
+/// Most positions `seed_positions` can open in one transaction
+pub const MAX_SEED_POSITIONS: usize = 16;
+/// Furthest before a launch's creation an imported position's hold can
+/// count from
+pub const MAX_IMPORT_HOLD_DAYS: i64 = 90;
+/// Most positions `audit_launch` can sample in one transaction
+pub const MAX_AUDIT_SAMPLE: usize = 24;
+// AuditReport violation bits
+/// Supply buckets don't add up to the total supply, or one is overdrawn
+pub const AUDIT_SUPPLY: u8 = 1 << 0;
+/// The SOL vault holds less than the raise it should be holding
+pub const AUDIT_SOL_VAULT: u8 = 1 << 1;
+/// The reward vault holds less than the rewards not yet paid out
+pub const AUDIT_REWARD_SOLVENCY: u8 = 1 << 2;
+/// More live positions were sampled than the launch counts holders
+pub const AUDIT_HOLDER_COUNT: u8 = 1 << 3;
+/// Sampled positions carry more reward weight than the launch's total
+pub const AUDIT_REWARD_WEIGHT: u8 = 1 << 4;
+/// Highest Token-2022 transfer fee a launch mint can charge
+pub const MAX_TRANSFER_FEE_BPS: u16 = 500;
+/// Highest annual interest rate an interest-bearing launch mint can show
+pub const MAX_INTEREST_RATE_BPS: i16 = 1000;
+/// How long holders of a rug-flagged launch have to file insurance claims
+pub const INSURANCE_CLAIM_WINDOW_DAYS: i64 = 30;
+/// Days after graduation the listing deposit stays within reach of a rug ruling
+pub const LISTING_DEPOSIT_HOLD_DAYS: i64 = 30;
+/// Most seats on the arbitration council
+pub const MAX_COUNCIL_MEMBERS: u8 = 9;
+/// Longest single council term
+pub const MAX_COUNCIL_TERM_DAYS: u16 = 365;
+/// How long a launch proposal stays open for votes
+pub const LAUNCH_PROPOSAL_VOTING_DAYS: i64 = 5;
+/// Insurance payouts at or above this many lamports need council sign-off
+pub const LARGE_INSURANCE_PAYOUT: u64 = 100_000_000_000;
+/// Length of a gauge voting epoch
+pub const GAUGE_EPOCH_SECONDS: i64 = 7 * 86400;
+/// Rolling window over which a launch's sell grace allowance applies
+pub const SELL_GRACE_WINDOW_DAYS: i64 = 30;
+/// Largest share of a position that may be sold per window without a rank reset
+pub const MAX_SELL_GRACE_BPS: u16 = 2500;
+/// Longest post-graduation window with extra sell grace
+pub const MAX_GRADUATION_GRACE_HOURS: u16 = 72;
+/// Largest extra sell grace a launch can give just after graduation
+pub const MAX_GRADUATION_GRACE_BPS: u16 = 2500;
+/// Most approvals a pending bundler flag can collect
+pub const MAX_FLAG_APPROVALS: usize = 8;
+/// How long a pending bundler flag stays open for approvals
+pub const PENDING_FLAG_TTL_SECONDS: i64 = 7 * 86400;
+/// Fixed-point scale of the per-launch reward index
+pub const REWARD_INDEX_PRECISION: u128 = 1_000_000_000_000;
+/// Length of a reward epoch; rewards only reach holders when one closes
+pub const REWARD_EPOCH_SECONDS: i64 = 86400;
+/// Epoch start indices kept per launch for lazily checkpointed positions
+pub const REWARD_EPOCH_HISTORY: usize = 16;
+/// Largest base LP boost a launch may offer
+pub const MAX_LP_BOOST_BPS: u16 = 5000;
+/// Share of the sale supply that must be sold before a launch can graduate
+pub const GRADUATION_SOLD_BPS: u16 = 9500;
+/// Highest wallet risk score the oracle can assign
+pub const MAX_RISK_SCORE: u8 = 100;
+/// Oldest a signed detection report can be when relayed
+pub const MAX_REPORT_AGE_SECONDS: i64 = 3600;
+/// Weekly buckets kept in the bundler statistics rollup
+pub const BUNDLER_STATS_WEEKS: usize = 52;
+pub const BUNDLER_STATS_WEEK_SECONDS: i64 = 7 * 86400;
+/// Most evidence entries kept on a bundler record
+pub const MAX_EVIDENCE_ENTRIES: usize = 8;
+/// Longest evidence URI
+pub const MAX_EVIDENCE_URI_LEN: usize = 128;
+/// Longest creator-supplied launch seed (one PDA seed)
+pub const MAX_LAUNCH_SEED_LEN: usize = 32;
+/// Independent launch id counters nonce launches can draw from
+pub const LAUNCH_ID_SHARDS: u8 = 16;
+/// Set on every shard-numbered launch id
+pub const SHARDED_LAUNCH_ID_BIT: u64 = 1 << 63;
+/// Wallets (including the owner) one HolderProfile can aggregate
+pub const MAX_LINKED_WALLETS: usize = 4;
+/// Price fall a position must have held through for DrawdownSurvivor
+pub const ACHIEVEMENT_DRAWDOWN_BPS: u16 = 5000;
+/// Graduated launches needed for GraduationVeteran
+pub const GRADUATED_LAUNCHES_FOR_VETERAN: usize = 5;
+/// Longest username (lowercase a-z, 0-9 and `_`)
+pub const MAX_USERNAME_LEN: usize = 20;
+/// How long a username is held after registering or changing hands
+pub const USERNAME_LOCK_DAYS: i64 = 7;
+/// Delay before a changed recovery key takes effect
+pub const RECOVERY_TIMELOCK_DAYS: i64 = 7;
+/// Reward boost a referrer earns per qualified referral
+pub const REFERRAL_BOOST_PER_REFERRAL_BPS: u16 = 100;
+/// Cap on the referral boost
+pub const MAX_REFERRAL_BOOST_BPS: u16 = 1000;
+/// Longest referral code (fits a single PDA seed)
+pub const MAX_REFERRAL_CODE_LEN: usize = 16;
+/// Referrers a launch's referral bonus pool is split between
+pub const MAX_REFERRAL_LEADERS: usize = 10;
+/// A buy/sell direction change within this many seconds counts as a flip
+pub const WASH_WINDOW_SECONDS: i64 = 300;
+/// Wash score added per rapid flip (score caps at 10000)
+pub const WASH_SCORE_PER_FLIP_BPS: u64 = 1000;
+/// Longest pause a tripped circuit breaker may impose
+pub const MAX_CIRCUIT_BREAKER_COOLDOWN_MINUTES: u16 = 1440;
+/// Share of supply reserved for the liquidity pool at graduation
+pub const LIQUIDITY_POOL_BPS: u16 = 1500;
+/// Smallest share of supply that must be sold through the curve
+pub const MIN_SALE_SUPPLY_BPS: u16 = 2500;
+
+// ============ Helper Functions ============
+
+#[allow(clippy::too_many_arguments)]
+fn init_launch(
+    launch: &mut Account<Launch>,
+    protocol: &Protocol,
+    launch_id: u64,
+    creator: Pubkey,
+    name: String,
+    symbol: String,
+    total_supply: u64,
+    config: LaunchConfig,
+    template: Pubkey,
+    bump: u8,
+) -> Result<()> {
+    launch.creator = creator;
+    launch.insiders = vec![creator];
+    launch.name = pad_bytes(&name);
+    launch.symbol = pad_bytes(&symbol);
+    launch.total_supply = total_supply;
+    launch.template = template;
+    launch.created_at = Clock::get()?.unix_timestamp;
+    launch.lp_unlock_timestamp = launch.created_at + (config.lp_lock_days as i64 * 86400);
+    launch.end_time = if config.sale_duration_days > 0 {
+        launch.created_at + config.sale_duration_days as i64 * 86400
+    } else {
+        0
+    };
+    launch.launch_id = launch_id;
+    launch.status = LaunchStatus::Pending;
+    launch.total_raised = 0;
+    launch.holder_count = 0;
+    launch.verified_holder_count = 0;
+    launch.rank_threshold_days = DiamondRank::DEFAULT_THRESHOLD_DAYS;
+    launch.tier = LaunchTier::Standard;
+    launch.hard_cap = get_launch_tier_hard_cap(LaunchTier::Standard);
+    launch.featured = false;
+    launch.review_status = if protocol.curated_mode {
+        ReviewStatus::Pending
+    } else {
+        ReviewStatus::NotRequired
+    };
+    
+    // Allocation pools (in basis points of total supply)
+    launch.guaranteed_pool_bps = 3000;      // 30%
+    launch.lottery_pool_bps = 2500;         // 25%
+    launch.public_pool_bps = 1000;          // 10%
+    launch.fcfs_pool_bps = 500;             // 5%
+    launch.flipper_pool_bps = 500;          // 5%
+    launch.liquidity_pool_bps = LIQUIDITY_POOL_BPS; // 15%
+    launch.trader_rewards_pool_bps = 1000;  // 10%
+    
+    // Virtual reserves set the constant-product curve's opening price and steepness
+    launch.sale_supply = calculate_sale_supply(
+        total_supply,
+        config.dev_allocation_bps,
+        launch.liquidity_pool_bps,
+        config.holder_rewards_bps,
+        config.graduation_airdrop_bps,
+    );
+    launch.virtual_sol_reserves = config.virtual_sol_reserves;
+    launch.virtual_token_reserves = ((launch.sale_supply as u128)
+        * config.virtual_token_reserves_bps as u128
+        / 10000) as u64;
+    launch.tokens_sold = 0;
+    launch.supply = SupplyLedger::reserve(total_supply, &config, launch.liquidity_pool_bps);
+    launch.supply.check(total_supply)?;
+    
+    launch.config = config;
+    launch.bump = bump;
+
+    emit!(LaunchCreated {
+        launch_id: launch.launch_id,
+        creator: launch.creator,
+        name,
+        symbol,
+        total_supply,
+        dev_allocation_bps: launch.config.dev_allocation_bps,
+        dev_vesting_days: launch.config.dev_vesting_days,
+        template,
+        schema_version: EVENT_SCHEMA_VERSION,
+        launch: launch.key(),
+    });
+
+    Ok(())
+}
+
+/// One entry in the admin audit trail; every privileged instruction emits
+/// this alongside its own event
+fn audit_admin_action(
+    actor: Pubkey,
+    action: AdminAction,
+    target: Pubkey,
+    old_value: AuditValue,
+    new_value: AuditValue,
+) -> Result<()> {
+    emit!(AdminActionAudited {
+        actor,
+        action,
+        target,
+        old_value,
+        new_value,
+        timestamp: Clock::get()?.unix_timestamp,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+    Ok(())
+}
+
+/// Emit a wallet-inbox notification (see `HolderNotification`)
+fn notify(recipient: Pubkey, launch: Pubkey, kind: NotificationKind, value: u64, at: i64) {
+    emit!(HolderNotification {
+        recipient,
+        launch,
+        kind,
+        value,
+        at,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+}
+
+/// Move a launch into Failed and open its refund window
+fn mark_launch_failed(launch: &mut Launch, now: i64) {
+    launch.status = LaunchStatus::Failed;
+    launch.refund_deadline = now + REFUND_WINDOW_DAYS * 86400;
+
+    emit!(LaunchFailed {
+        launch_id: launch.launch_id,
+        total_raised: launch.total_raised,
+        refund_deadline: launch.refund_deadline,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+}
+
+/// Push a launch's LP unlock date further out; shared by the creator's
+/// `extend_lp_lock` and executed governance proposals
+fn extend_launch_lp_lock(launch: &mut Launch, new_unlock_timestamp: i64) -> Result<()> {
+    launch.require_unfrozen(FREEZE_LP_LOCK)?;
+
+    require!(
+        new_unlock_timestamp > launch.lp_unlock_timestamp,
+        DiamondPadError::LpLockNotExtended
+    );
+
+    let previous_unlock_timestamp = launch.lp_unlock_timestamp;
+    launch.lp_unlock_timestamp = new_unlock_timestamp;
+
+    emit!(LpLockExtended {
+        launch_id: launch.launch_id,
+        creator: launch.creator,
+        previous_unlock_timestamp,
+        new_unlock_timestamp,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+
+    grant_relock_incentive(launch);
+
+    Ok(())
+}
+
+/// Schedule a new set of switched-off FEATURE_* bits for
+/// `apply_feature_flags` to apply once FEATURE_FLAG_TIMELOCK_DAYS have
+/// passed. Reached through executed governance proposals.
+fn schedule_feature_flags(
+    protocol: &mut Account<Protocol>,
+    disabled_features: u32,
+    proposal: Pubkey,
+) -> Result<()> {
+    let effective_at = Clock::get()?.unix_timestamp + FEATURE_FLAG_TIMELOCK_DAYS * 86400;
+    protocol.pending_disabled_features = disabled_features;
+    protocol.feature_flags_effective_at = effective_at;
+
+    emit!(FeatureFlagsScheduled {
+        current: protocol.disabled_features,
+        pending: disabled_features,
+        effective_at,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+    audit_admin_action(
+        proposal,
+        AdminAction::ScheduleFeatureFlags,
+        protocol.key(),
+        AuditValue::Amount(protocol.disabled_features as u64),
+        AuditValue::Amount(disabled_features as u64),
+    )
+}
+
+/// Check a proposed rate for an interest-bearing launch mint
+fn validate_interest_rate(launch: &Launch, rate_bps: i16) -> Result<()> {
+    require!(launch.interest_bearing, DiamondPadError::NotInterestBearing);
+    require!(
+        (0..=MAX_INTEREST_RATE_BPS).contains(&rate_bps),
+        DiamondPadError::InvalidInterestRate
+    );
+    Ok(())
+}
+
+/// Move an interest-bearing launch mint's rate for an executed proposal.
+/// The rate authority is the launch authority PDA, so this is the only way
+/// the rate can change.
+fn set_launch_interest_rate<'info>(
+    launch: &mut Account<'info, Launch>,
+    launch_mint: AccountInfo<'info>,
+    launch_authority: AccountInfo<'info>,
+    launch_authority_bump: u8,
+    token_program: AccountInfo<'info>,
+    rate_bps: i16,
+    proposal: Pubkey,
+) -> Result<()> {
+    validate_interest_rate(launch, rate_bps)?;
+
+    let launch_key = launch.key();
+    let seeds = &[
+        b"launch_authority".as_ref(),
+        launch_key.as_ref(),
+        &[launch_authority_bump],
+    ];
+    token_interface::interest_bearing_mint_update_rate(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            token_interface::InterestBearingMintUpdateRate {
+                token_program_id: token_program,
+                mint: launch_mint,
+                rate_authority: launch_authority,
+            },
+            &[&seeds[..]],
+        ),
+        rate_bps,
+    )?;
+
+    let previous = launch.config.interest_rate_bps;
+    launch.config.interest_rate_bps = rate_bps;
+
+    audit_admin_action(
+        proposal,
+        AdminAction::SetInterestRate,
+        launch_key,
+        AuditValue::Amount(previous as u64),
+        AuditValue::Amount(rate_bps as u64),
+    )
+}
+
+/// Ratchet a launch's holder rewards share up; shared by the creator's
+/// `raise_holder_rewards` and executed governance proposals
+fn raise_launch_holder_rewards(launch: &mut Launch, holder_rewards_bps: u16) -> Result<()> {
+    launch.require_unfrozen(FREEZE_FEES)?;
+
+    require!(launch.status != LaunchStatus::Failed, DiamondPadError::LaunchNotOpen);
+    require!(
+        holder_rewards_bps > launch.config.holder_rewards_bps,
+        DiamondPadError::HolderRewardsNotRaised
+    );
+    require!(
+        holder_rewards_bps <= MAX_HOLDER_REWARDS_BPS,
+        DiamondPadError::HolderRewardsTooHigh
+    );
+
+    let previous_bps = launch.config.holder_rewards_bps;
+    launch.config.holder_rewards_bps = holder_rewards_bps;
+
+    emit!(HolderRewardsRaised {
+        launch_id: launch.launch_id,
+        previous_bps,
+        holder_rewards_bps,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+
+    Ok(())
+}
+
+/// Holder rewards boost the launch's LP lock has earned in total:
+/// RELOCK_BONUS_BPS for every full RELOCK_MIN_EXTENSION_DAYS the lock now
+/// runs past its configured length, never more than burning it earns
+fn lp_lock_bonus_bps(launch: &Launch) -> u16 {
+    if launch.lp_burned {
+        return LP_BURN_BONUS_BPS;
+    }
+    let configured_unlock = launch.created_at + launch.config.lp_lock_days as i64 * 86400;
+    let periods = launch.lp_unlock_timestamp.saturating_sub(configured_unlock).max(0)
+        / (RELOCK_MIN_EXTENSION_DAYS * 86400);
+    (periods as u64 * RELOCK_BONUS_BPS as u64).min(LP_BURN_BONUS_BPS as u64) as u16
+}
+
+/// Boost holder rewards after a relock, funded by rebating the same share of
+/// protocol fees back to the launch. Both stay within protocol caps. The
+/// bonus follows the combined lock, so splitting one extension into several
+/// relocks earns no more than making it at once.
+/// Relocking still goes through on a fee-frozen launch, it just earns no bonus
+fn grant_relock_incentive(launch: &mut Launch) {
+    if launch.frozen_params & FREEZE_FEES != 0 {
+        return;
+    }
+
+    let bonus_bps = lp_lock_bonus_bps(launch).saturating_sub(launch.lock_bonus_bps);
+    let holder_rewards_bps = launch
+        .config
+        .holder_rewards_bps
+        .saturating_add(bonus_bps)
+        .min(MAX_HOLDER_REWARDS_BPS);
+    let granted_bps = holder_rewards_bps.saturating_sub(launch.config.holder_rewards_bps);
+    if granted_bps == 0 {
+        return;
+    }
+
+    launch.lock_bonus_bps += granted_bps;
+    launch.config.holder_rewards_bps = holder_rewards_bps;
+    launch.fee_rebate_bps = launch
+        .fee_rebate_bps
+        .saturating_add(granted_bps)
+        .min(MAX_FEE_REBATE_BPS);
+
+    emit!(RelockIncentiveGranted {
+        launch_id: launch.launch_id,
+        bonus_bps: granted_bps,
+        holder_rewards_bps: launch.config.holder_rewards_bps,
+        fee_rebate_bps: launch.fee_rebate_bps,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+}
+
+/// Pause curve trading if the post-trade price has moved too far from the
+/// TWAP over the configured window. The trade that moved it still settles.
+fn trip_circuit_breaker(launch: &mut Account<Launch>, now: i64) {
+    let threshold_bps = launch.config.circuit_breaker_bps;
+    if threshold_bps == 0 {
+        return;
+    }
+
+    let window = launch.config.circuit_breaker_window_minutes as i64 * 60;
+    let (Some(reference_price), Some(latest)) =
+        (launch.price_history.twap(now, window), launch.price_history.latest())
+    else {
+        return;
+    };
+    if reference_price == 0 {
+        return;
+    }
+
+    let move_bps = (latest.price.abs_diff(reference_price) as u128 * 10000
+        / reference_price as u128) as u64;
+    if move_bps <= threshold_bps as u64 {
+        return;
+    }
+
+    let resumes_at = now + launch.config.circuit_breaker_cooldown_minutes as i64 * 60;
+    launch.circuit_breaker_until = resumes_at;
+
+    emit!(CircuitBreakerTripped {
+        launch_id: launch.launch_id,
+        reference_price,
+        price: latest.price,
+        move_bps,
+        resumes_at,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+}
+
+/// Stamp a new launch with the tier its creator's stake unlocks, and lock
+/// that stake for as long as the launch may still need slashing
+fn apply_creator_tier(
+    launch: &mut Account<Launch>,
+    creator_stake: Option<&mut Account<CreatorStake>>,
+) -> Result<()> {
+    let Some(creator_stake) = creator_stake else {
+        return Ok(());
+    };
+
+    launch.tier = creator_stake.tier;
+    launch.hard_cap = get_launch_tier_hard_cap(creator_stake.tier);
+    launch.featured = creator_stake.tier == LaunchTier::Gold;
+
+    creator_stake.locked_until = creator_stake
+        .locked_until
+        .max(launch.created_at + CREATOR_STAKE_LOCK_DAYS * 86400);
+
+    emit!(LaunchTierAssigned {
+        launch_id: launch.launch_id,
+        tier: launch.tier,
+        hard_cap: launch.hard_cap,
+        featured: launch.featured,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+
+    Ok(())
+}
+
+fn evidence_entry(
+    flagger: Pubkey,
+    hash: [u8; 32],
+    uri: String,
+    now: i64,
+) -> Result<EvidenceEntry> {
+    require!(uri.len() <= MAX_EVIDENCE_URI_LEN, DiamondPadError::EvidenceUriTooLong);
+    Ok(EvidenceEntry {
+        timestamp: now,
+        flagger,
+        hash,
+        uri,
+    })
+}
+
+/// Start a council term from now
+fn seat_council_member(member: &mut CouncilMember, wallet: Pubkey, term_days: u16, bump: u8) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    member.wallet = wallet;
+    member.term_started_at = now;
+    member.term_ends_at = now + term_days as i64 * 86400;
+    member.bump = bump;
+    Ok(())
+}
+
+/// Require signatures from active council members holding a majority of
+/// the seats, passed as `(council member account, member wallet)` pairs
+fn require_council_majority(council: &Council, approvals: &[AccountInfo], now: i64) -> Result<()> {
+    let pairs = approvals.chunks_exact(2);
+    require!(pairs.remainder().is_empty(), DiamondPadError::InvalidCouncilApproval);
+
+    let mut signed: Vec<Pubkey> = Vec::new();
+    for pair in pairs {
+        let (member_info, wallet) = (&pair[0], &pair[1]);
+        require!(member_info.owner == &crate::ID, DiamondPadError::InvalidCouncilApproval);
+        let member = CouncilMember::try_deserialize(&mut &member_info.data.borrow()[..])?;
+        require!(
+            wallet.is_signer && member.wallet == wallet.key(),
+            DiamondPadError::InvalidCouncilApproval
+        );
+        if member.term_ends_at > now && !signed.contains(&member.wallet) {
+            signed.push(member.wallet);
+        }
+    }
+
+    require!(
+        signed.len() * 2 > council.member_count as usize,
+        DiamondPadError::CouncilMajorityRequired
+    );
+    Ok(())
+}
+
+/// The rank a buyer proves with a position they (or a wallet linked to
+/// them) hold on a different launch that has since graduated and that they
+/// didn't create. Only time the position held itself counts, not rank
+/// borrowed through a linked position. `None` if nothing qualifies.
+fn proven_rank(
+    launch: &Pubkey,
+    buyer: &Pubkey,
+    priority_launch: Option<&Account<Launch>>,
+    priority_position: Option<&Account<Position>>,
+    holder_profile: Option<&Account<HolderProfile>>,
+    now: i64,
+) -> Option<DiamondRank> {
+    let (priority_launch, position) = (priority_launch?, priority_position?);
+    let controlled_by_buyer = |wallet: &Pubkey| {
+        wallet == buyer
+            || holder_profile.is_some_and(|profile| {
+                profile.wallets.contains(buyer) && profile.wallets.contains(wallet)
+            })
+    };
+    if priority_launch.key() == *launch
+        || priority_launch.status != LaunchStatus::Graduated
+        || position.launch != priority_launch.key()
+        || !controlled_by_buyer(&position.holder)
+        || controlled_by_buyer(&priority_launch.creator)
+    {
+        return None;
+    }
+
+    let own_rank = calculate_diamond_rank(
+        position.first_buy_timestamp as i64,
+        now,
+        &priority_launch.rank_thresholds(),
+    );
+    Some(position.diamond_rank.min(own_rank))
+}
+
+/// What `screen_buy` learned about a buyer
+struct BuyScreening {
+    proven_rank: Option<DiamondRank>,
+    risk_deboosted: bool,
+    wallet_risk: Option<WalletRisk>,
+}
+
+/// Checks every curve buy passes, whether paid with SOL or with reinvested
+/// rewards: the sale is open to this wallet, the priority window, identity
+/// and risk screening, wallet age, and the position's rate limit
+#[allow(clippy::too_many_arguments)]
+fn screen_buy(
+    launch: &Account<Launch>,
+    protocol: &Protocol,
+    position: &mut Position,
+    buyer: &Pubkey,
+    priority_launch: Option<&Account<Launch>>,
+    priority_position: Option<&Account<Position>>,
+    holder_profile: Option<&Account<HolderProfile>>,
+    gateway_token: Option<&AccountInfo>,
+    wallet_risk: Option<&AccountInfo>,
+    clock: &Clock,
+) -> Result<BuyScreening> {
+    require!(
+        launch.status == LaunchStatus::Pending || launch.status == LaunchStatus::Active,
+        DiamondPadError::LaunchNotOpen
+    );
+    require!(!launch.is_paused(PAUSE_TRADING), DiamondPadError::TradingHalted);
+    require!(!launch.rug_flagged, DiamondPadError::LaunchRugFlagged);
+    require!(
+        launch.end_time == 0
+            || clock.unix_timestamp < launch.end_time
+            || launch.total_raised >= launch.config.soft_cap,
+        DiamondPadError::SaleEnded
+    );
+    require!(
+        clock.unix_timestamp >= launch.circuit_breaker_until,
+        DiamondPadError::CircuitBreakerActive
+    );
+    require!(
+        launch.review_status != ReviewStatus::Pending,
+        DiamondPadError::LaunchNotApproved
+    );
+    require!(!launch.is_insider(buyer), DiamondPadError::InsiderCannotBuy);
+    position.consume_rate_limit(protocol.max_position_actions_per_minute, clock.unix_timestamp)?;
+
+    // The opening window is reserved for proven diamond hands
+    let proven_rank = proven_rank(
+        &launch.key(),
+        buyer,
+        priority_launch,
+        priority_position,
+        holder_profile,
+        clock.unix_timestamp,
+    );
+    let priority_ends_at = launch.created_at + launch.config.priority_access_hours as i64 * 3600;
+    if clock.unix_timestamp < priority_ends_at {
+        require!(
+            proven_rank.is_some_and(|rank| rank >= DiamondRank::Gold),
+            DiamondPadError::PriorityAccessRequired
+        );
+    }
+
+    // Identity-gated launches require a valid Civic pass
+    if let Some(gatekeeper_network) = launch.config.gatekeeper_network {
+        let gateway_token = gateway_token.ok_or(DiamondPadError::GatewayTokenRequired)?;
+        gateway::verify_gateway_token(
+            gateway_token,
+            buyer,
+            &gatekeeper_network,
+            clock.unix_timestamp,
+        )?;
+    }
+
+    // Risk-screened launches read the buyer's oracle score (no record = 0)
+    let risk_deboosted = match launch.config.risk_policy {
+        RiskPolicy::Ignore => false,
+        policy => {
+            let wallet_risk = wallet_risk.ok_or(DiamondPadError::WalletRiskRequired)?;
+            let high_risk = read_risk_score(wallet_risk)? > launch.config.max_risk_score;
+            require!(
+                !(high_risk && policy == RiskPolicy::Reject),
+                DiamondPadError::WalletRiskTooHigh
+            );
+            high_risk
+        }
+    };
+
+    // Age-gated launches only sell to wallets the oracle saw sign long
+    // enough ago; an unattested wallet counts as brand new
+    let risk = match wallet_risk {
+        Some(wallet_risk) => read_wallet_risk(wallet_risk)?,
+        None => None,
+    };
+    if launch.config.min_wallet_age_slots > 0 {
+        require!(wallet_risk.is_some(), DiamondPadError::WalletRiskRequired);
+        let first_signature_slot = risk.as_ref().map_or(0, |risk| risk.first_signature_slot);
+        require!(
+            first_signature_slot > 0
+                && clock.slot.saturating_sub(first_signature_slot)
+                    >= launch.config.min_wallet_age_slots,
+            DiamondPadError::WalletTooNew
+        );
+    }
+
+    Ok(BuyScreening {
+        proven_rank,
+        risk_deboosted,
+        wallet_risk: risk,
+    })
+}
+
+/// Cap any single wallet's bag during the launch phase (0 = uncapped)
+fn require_within_position_cap(launch: &Launch, balance: u64, tokens_out: u64) -> Result<()> {
+    if launch.config.max_position_bps > 0 {
+        let max_position = (launch.total_supply as u128)
+            .checked_mul(launch.config.max_position_bps as u128).unwrap()
+            / 10000;
+        let new_balance = balance.checked_add(tokens_out).unwrap();
+        require!(
+            new_balance as u128 <= max_position,
+            DiamondPadError::MaxPositionExceeded
+        );
+    }
+    Ok(())
+}
+
+/// A creator's stake record, or `None` if they never staked
+fn read_creator_stake(creator_stake: &AccountInfo) -> Result<Option<CreatorStake>> {
+    if creator_stake.owner != &crate::ID || creator_stake.data_is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(CreatorStake::try_deserialize(&mut &creator_stake.data.borrow()[..])?))
+}
+
+/// Balance of a token account that may have been closed (closed reads as 0)
+fn read_token_balance(token_account: &AccountInfo) -> Result<u64> {
+    if token_account.data_is_empty() {
+        return Ok(0);
+    }
+    let account =
+        token_interface::TokenAccount::try_deserialize(&mut &token_account.data.borrow()[..])?;
+    Ok(account.amount)
+}
+
+/// What a reconciled position's holder actually has: their token account
+/// plus whatever their contribution still owes them
+fn held_balance(accounts: &ReconcilePosition) -> Result<u64> {
+    Ok(read_token_balance(&accounts.holder_token_account)?
+        .checked_add(read_unclaimed_allocation(&accounts.contribution)?)
+        .unwrap())
+}
+
+/// Tokens a contribution PDA still owes its holder (0 if there's none, or
+/// it was written off after the claim window)
+fn read_unclaimed_allocation(contribution: &AccountInfo) -> Result<u64> {
+    if contribution.data_is_empty() {
+        return Ok(0);
+    }
+    let contribution = Contribution::try_deserialize(&mut &contribution.data.borrow()[..])?;
+    if contribution.swept {
+        return Ok(0);
+    }
+    Ok(contribution.tokens_allocated - contribution.tokens_claimed)
+}
+
+/// A buyer's WalletRisk PDA, if the oracle has ever written one
+fn read_wallet_risk(wallet_risk: &AccountInfo) -> Result<Option<WalletRisk>> {
+    if wallet_risk.owner != &crate::ID || wallet_risk.data_is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(WalletRisk::try_deserialize(&mut &wallet_risk.data.borrow()[..])?))
+}
+
+/// Score from a buyer's WalletRisk PDA; a wallet the oracle never scored reads as 0
+fn read_risk_score(wallet_risk: &AccountInfo) -> Result<u8> {
+    Ok(read_wallet_risk(wallet_risk)?.map_or(0, |risk| risk.score))
+}
+
+/// Keys whose approvals count toward a bundler flag: the protocol's role
+/// keys, plus any key the authority registered as a dedicated flagger
+fn is_flag_signer(protocol: &Protocol, key: &Pubkey, registered_flagger: bool) -> bool {
+    registered_flagger
+        || *key == protocol.authority
+        || *key == protocol.moderator
+        || *key == protocol.verifier
+}
+
+/// Approvals on a pending flag from keys that can still flag: the
+/// protocol's role keys, or registered flaggers whose `Flagger` account is
+/// passed in `registrations`. A removed flagger's account is closed, so it
+/// can't be passed and their approval drops out.
+fn current_flag_approvals(
+    protocol: &Protocol,
+    approvals: &[Pubkey],
+    registrations: &[AccountInfo],
+) -> Result<usize> {
+    let mut registered: Vec<Pubkey> = Vec::new();
+    for info in registrations {
+        require!(info.owner == &crate::ID, DiamondPadError::NotAFlagger);
+        let flagger = Flagger::try_deserialize(&mut &info.data.borrow()[..])?;
+        registered.push(flagger.key);
+    }
+
+    let detector = (protocol.detector != Pubkey::default()).then_some(protocol.detector);
+    Ok(approvals
+        .iter()
+        .filter(|key| {
+            is_flag_signer(protocol, key, registered.contains(key)) || Some(**key) == detector
+        })
+        .count())
+}
+
+/// Attribute a new launch to the partner channel it was created through,
+/// if any
+fn attribute_channel(launch: &mut Account<Launch>, channel: Option<&mut Account<Channel>>) {
+    let Some(channel) = channel else {
+        return;
+    };
+    launch.channel = channel.key();
+    channel.launches_attributed += 1;
+
+    emit!(LaunchAttributedToChannel {
+        launch_id: launch.launch_id,
+        channel: channel.key(),
+        partner: channel.partner,
+        launches_attributed: channel.launches_attributed,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+}
+
+/// Carve the launch's channel share out of a trade's protocol fee,
+/// accruing it to the channel. Returns the share, which the caller moves
+/// onto the channel account instead of the treasury.
+fn accrue_channel_fee(
+    launch: &Launch,
+    channel: Option<&mut Account<Channel>>,
+    protocol_fee: u64,
+) -> Result<u64> {
+    if launch.channel == Pubkey::default() {
+        return Ok(0);
+    }
+    let channel = channel.ok_or(DiamondPadError::ChannelRequired)?;
+    let share = (protocol_fee as u128 * channel.fee_share_bps as u128 / 10000) as u64;
+    channel.fees_accrued = channel.fees_accrued.checked_add(share).unwrap();
+    Ok(share)
+}
+
+/// List a curated launch in the review queue with its review deadline
+fn enqueue_for_review(
+    launch: &Account<Launch>,
+    review_queue: Option<&mut Account<ReviewQueue>>,
+) -> Result<()> {
+    if launch.review_status != ReviewStatus::Pending {
+        return Ok(());
+    }
+
+    let review_queue = review_queue.ok_or(DiamondPadError::ReviewQueueRequired)?;
+    review_queue.prune_expired(Clock::get()?.unix_timestamp);
+    require!(
+        review_queue.entries.len() < MAX_REVIEW_QUEUE,
+        DiamondPadError::ReviewQueueFull
+    );
+
+    let deadline = launch.created_at + REVIEW_WINDOW_DAYS * 86400;
+    review_queue.entries.push(ReviewEntry {
+        launch: launch.key(),
+        deadline,
+    });
+
+    emit!(LaunchSubmittedForReview {
+        launch_id: launch.launch_id,
+        launch: launch.key(),
+        deadline,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+
+    Ok(())
+}
+
+fn calculate_launch_tier(sol_staked: u64, dpad_staked: u64) -> LaunchTier {
+    // 50 SOL / 250k DPAD for Gold, 10 SOL / 50k DPAD for Silver
+    if sol_staked >= 50_000_000_000 || dpad_staked >= 250_000_000_000 {
+        LaunchTier::Gold
+    } else if sol_staked >= 10_000_000_000 || dpad_staked >= 50_000_000_000 {
+        LaunchTier::Silver
+    } else {
+        LaunchTier::Standard
+    }
+}
+
+fn get_launch_tier_hard_cap(tier: LaunchTier) -> u64 {
+    match tier {
+        LaunchTier::Standard => 100_000_000_000,   // 100 SOL
+        LaunchTier::Silver => 500_000_000_000,     // 500 SOL
+        LaunchTier::Gold => 2_000_000_000_000,     // 2,000 SOL
+    }
+}
+
+/// Create a program-owned PDA the way Anchor's `init` does: a plain
+/// `create_account` if the address is empty, otherwise top it up to rent
+/// exemption, allocate and assign, so lamports sent to the address ahead
+/// of time can't block it
+fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let current = account.lamports();
+    if current == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::CreateAccount { from: payer.clone(), to: account.clone() },
+                &[seeds],
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    transfer_lamports(
+        payer.clone(),
+        account.clone(),
+        system_program.clone(),
+        rent.saturating_sub(current),
+        &[],
+    )?;
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate { account_to_allocate: account.clone() },
+            &[seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Assign { account_to_assign: account.clone() },
+            &[seeds],
+        ),
+        &crate::ID,
+    )
+}
+
+/// Move lamports with the system program, signing for a vault PDA if needed
+fn transfer_lamports<'info>(
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let cpi_accounts = system_program::Transfer { from, to };
+    let cpi_ctx = CpiContext::new_with_signer(system_program, cpi_accounts, signer_seeds);
+    system_program::transfer(cpi_ctx, amount)
+}
+
+/// Hold the creator's listing deposit on the launch account itself until
+/// the launch's outcome decides where it goes
+fn escrow_listing_deposit<'info>(
+    creator: &Signer<'info>,
+    launch: &mut Account<'info, Launch>,
+    amount: u64,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    transfer_lamports(
+        creator.to_account_info(),
+        launch.to_account_info(),
+        system_program.to_account_info(),
+        amount,
+        &[],
+    )?;
+    launch.listing_deposit = amount;
+    Ok(())
+}
+
+/// Pay the escrowed listing deposit out of the launch account: `refunded`
+/// back to the creator and the rest to the treasury
+fn release_listing_deposit<'info>(
+    launch: &mut Account<'info, Launch>,
+    creator: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    refunded: u64,
+) -> Result<()> {
+    let deposit = launch.listing_deposit;
+    let forfeited = deposit - refunded;
+    launch.listing_deposit = 0;
+
+    launch.sub_lamports(deposit)?;
+    creator.add_lamports(refunded)?;
+    treasury.add_lamports(forfeited)?;
+
+    emit!(ListingDepositSettled {
+        launch_id: launch.launch_id,
+        creator: launch.creator,
+        refunded,
+        forfeited,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+    Ok(())
+}
+
+/// Zero-pad a string already checked to fit into a fixed-size field
+fn pad_bytes<const N: usize>(value: &str) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    bytes[..value.len()].copy_from_slice(value.as_bytes());
+    bytes
+}
+
+fn unpad_bytes(bytes: &[u8]) -> String {
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+/// Seed a new launch's SOL vaults with rent so small fee transfers never
+/// fail the rent-exemption check
+fn fund_launch_vaults<'info>(
+    creator: &Signer<'info>,
+    sol_vault: &SystemAccount<'info>,
+    reward_vault: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(0);
+    for vault in [sol_vault, reward_vault] {
+        let shortfall = rent.saturating_sub(vault.lamports());
+        transfer_lamports(
+            creator.to_account_info(),
+            vault.to_account_info(),
+            system_program.to_account_info(),
+            shortfall,
+            &[],
+        )?;
+    }
+    Ok(())
+}
+
+/// Lamports a SOL vault can pay out. Vaults are plain system accounts, so
+/// they have to stay rent-exempt; a vault at or under the minimum has
+/// nothing to give.
+fn withdrawable_lamports(vault: &AccountInfo) -> Result<u64> {
+    Ok(vault.lamports().saturating_sub(Rent::get()?.minimum_balance(0)))
+}
+
+fn calculate_trade_fee(quote_amount: u64, trade_fee_bps: u16) -> u64 {
+    ((quote_amount as u128) * trade_fee_bps as u128 / 10000) as u64
+}
+
+/// Split a trade fee between the launch's reward vault and the treasury.
+/// A relock rebate moves part of the protocol's cut over to rewards.
+fn split_trade_fee(launch: &Launch, fee: u64) -> (u64, u64) {
+    let reward_fee = fee as u128 * launch.config.reward_fee_share_bps as u128 / 10000;
+    let protocol_fee = fee as u128 - reward_fee;
+    let rebate = protocol_fee * launch.fee_rebate_bps as u128 / 10000;
+    ((reward_fee + rebate) as u64, (protocol_fee - rebate) as u64)
+}
+
+/// A buy's trade fee and where it goes
+struct BuyFee {
+    discount_bps: u16,
+    fee: u64,
+    reward_fee: u64,
+    protocol_fee: u64,
+    channel_fee: u64,
+}
+
+/// Price a buy's trade fee: the protocol rate less the loyalty discount for
+/// the buyer's proven rank, split between rewards, treasury and channel
+fn buy_fee(
+    launch: &Launch,
+    protocol: &Protocol,
+    channel: Option<&mut Account<Channel>>,
+    proven_rank: Option<DiamondRank>,
+    quote_amount: u64,
+) -> Result<BuyFee> {
+    let discount_bps = proven_rank.map_or(0, |rank| protocol.loyalty_discount_bps[rank as usize]);
+    let trade_fee_bps = protocol.trade_fee_bps;
+    let fee_bps = trade_fee_bps - (trade_fee_bps as u32 * discount_bps as u32 / 10000) as u16;
+
+    let fee = calculate_trade_fee(quote_amount, fee_bps);
+    let (reward_fee, protocol_fee) = split_trade_fee(launch, fee);
+    let channel_fee = accrue_channel_fee(launch, channel, protocol_fee)?;
+    Ok(BuyFee {
+        discount_bps,
+        fee,
+        reward_fee,
+        protocol_fee,
+        channel_fee,
+    })
+}
+
+/// Fill in a contribution opened by something other than a buy
+fn open_contribution(contribution: &mut Contribution, launch: Pubkey, holder: Pubkey, bump: u8) {
+    if contribution.contributor == Pubkey::default() {
+        contribution.contributor = holder;
+        contribution.launch = launch;
+        contribution.bump = bump;
+    }
+}
+
+/// Hand a recovered wallet's contribution over to the wallet its position
+/// moved to
+fn take_over_contribution(
+    contribution: &mut Contribution,
+    old: &Contribution,
+    holder: Pubkey,
+    bump: u8,
+) {
+    *contribution = old.clone();
+    contribution.contributor = holder;
+    contribution.bump = bump;
+}
+
+/// Owe a holder `amount` tokens already issued from the airdrop bucket.
+/// They're minted with the rest of the contribution by `claim_tokens`.
+fn credit_bonus_allocation(
+    contribution: &mut Contribution,
+    launch: Pubkey,
+    holder: Pubkey,
+    bump: u8,
+    amount: u64,
+) {
+    open_contribution(contribution, launch, holder, bump);
+    contribution.tokens_allocated = contribution.tokens_allocated.checked_add(amount).unwrap();
+    contribution.bonus_unclaimed = contribution.bonus_unclaimed.checked_add(amount).unwrap();
+}
+
+/// Add tokens to a holder's position, opening it on first buy
+fn credit_position(
+    position: &mut Account<Position>,
+    launch: &mut Account<Launch>,
+    holder: Pubkey,
+    bump: u8,
+    amount: u64,
+    now: i64,
+) {
+    credit_position_since(position, launch, holder, bump, amount, now, now);
+}
+
+/// `credit_position`, but a position it opens counts as held since
+/// `entered_at` rather than `now`
+fn credit_position_since(
+    position: &mut Account<Position>,
+    launch: &mut Account<Launch>,
+    holder: Pubkey,
+    bump: u8,
+    amount: u64,
+    entered_at: i64,
+    now: i64,
+) {
+    settle_rewards(position, launch, now);
+    if position.balance == 0 {
+        position.holder = holder;
+        position.launch = launch.key();
+        position.layout_version = POSITION_LAYOUT_VERSION;
+        position.first_buy_timestamp = entered_at as u32;
+        position.clear_linked_entry();
+        position.bump = bump;
+        launch.holder_count += 1;
+        launch.peak_holder_count = launch.peak_holder_count.max(launch.holder_count);
+    }
+    if launch.is_insider(&holder) {
+        position.set_flag(POSITION_INSIDER, true);
+    }
+
+    position.balance = position.balance.checked_add(amount).unwrap();
+    position.last_activity_timestamp = now as u32;
+    refresh_rank(position, launch, now);
+    sync_reward_weight(position, launch);
+
+    emit!(PositionUpdated {
+        holder: position.holder,
+        launch: position.launch,
+        balance: position.balance,
+        diamond_rank: position.diamond_rank,
+        multiplier_bps: position.multiplier_bps,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+}
+
+/// Settle, refresh boosts and pay out a holder's accrued rewards; shared by
+/// `claim_rewards` and `claim_and_swap`. Returns the lamports claimed.
+fn process_claim(
+    accounts: &mut ClaimRewards,
+    bumps: &ClaimRewardsBumps,
+    denomination: RewardDenomination,
+    min_tokens_out: u64,
+) -> Result<u64> {
+    flashloan::require_no_buy_in_transaction(&accounts.instructions_sysvar, &accounts.launch.key())?;
+
+    let launch = &mut accounts.launch;
+    let position = &mut accounts.position;
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    require!(!launch.is_paused(PAUSE_CLAIMS), DiamondPadError::ClaimsPaused);
+
+    let destination = accounts.destination.key();
+    if destination != position.holder {
+        let profile = accounts
+            .holder_profile
+            .as_ref()
+            .ok_or(DiamondPadError::InvalidRewardDestination)?;
+        require!(
+            profile.wallets.contains(&position.holder) && profile.wallets.contains(&destination),
+            DiamondPadError::InvalidRewardDestination
+        );
+    }
+
+    require!(
+        now >= position.first_buy_timestamp as i64
+            + launch.config.min_hold_before_claim_days as i64 * 86400,
+        DiamondPadError::NotYetEligible
+    );
+
+    settle_rewards(position, launch, now);
+    refresh_rank(position, launch, now);
+    position.lp_boost_bps =
+        calculate_lp_boost_bps(launch, position, accounts.lp_position.as_deref());
+    position.referral_boost_bps = match (&accounts.referral_code, &accounts.referral_stats) {
+        (Some(code), Some(stats)) => {
+            require_keys_eq!(
+                stats.referral_code,
+                code.key(),
+                DiamondPadError::ReferralStatsMismatch
+            );
+            calculate_referral_boost_bps(stats.qualified_referrals)
+        }
+        _ => 0,
+    };
+    sync_reward_weight(position, launch);
+
+    // Whatever a flagged wallet can't take goes back to the reward pot
+    let bundler_bps = bundler_claim_bps(&accounts.bundler, now)?;
+    if bundler_bps < 10000 {
+        accounts
+            .bundler_stats
+            .as_ref()
+            .ok_or(DiamondPadError::BundlerStatsRequired)?
+            .load_mut()?
+            .record_enforcement(now);
+        let kept = (position.accrued_rewards as u128 * bundler_bps as u128 / 10000) as u64;
+        distribute_rewards(launch, position.accrued_rewards - kept);
+        position.accrued_rewards = kept;
+    }
+
+    let amount = position
+        .accrued_rewards
+        .min(withdrawable_lamports(&accounts.reward_vault)?);
+    require!(amount > 0, DiamondPadError::NothingToClaim);
+    let amount = amount.min(
+        accounts
+            .protocol_stats
+            .reward_cap_remaining(accounts.protocol.daily_reward_cap, now),
+    );
+    require!(amount > 0, DiamondPadError::DailyRewardCapReached);
+
+    let launch_key = launch.key();
+    let seeds = &[b"reward_vault".as_ref(), launch_key.as_ref(), &[bumps.reward_vault]];
+    let tokens_out = match denomination {
+        RewardDenomination::Quote => {
+            transfer_lamports(
+                accounts.reward_vault.to_account_info(),
+                accounts.destination.to_account_info(),
+                accounts.system_program.to_account_info(),
+                amount,
+                &[&seeds[..]],
+            )?;
+            0
+        }
+        RewardDenomination::Token => {
+            // Reinvesting is a buy like any other: same guards, same fee
+            let holder = position.holder;
+            let screening = screen_buy(
+                launch,
+                &accounts.protocol,
+                position,
+                &holder,
+                accounts.priority_launch.as_deref(),
+                accounts.priority_position.as_ref(),
+                accounts.holder_profile.as_ref(),
+                accounts.gateway_token.as_deref(),
+                accounts.wallet_risk.as_deref(),
+                &clock,
+            )?;
+            let treasury = accounts
+                .treasury
+                .as_ref()
+                .ok_or(DiamondPadError::TreasuryRequired)?;
+            let BuyFee {
+                fee,
+                reward_fee,
+                protocol_fee,
+                channel_fee,
+                ..
+            } = buy_fee(
+                launch,
+                &accounts.protocol,
+                accounts.channel.as_deref_mut(),
+                screening.proven_rank,
+                amount,
+            )?;
+            let net_quote = amount.checked_sub(fee).unwrap();
+
+            let tokens_out = launch
+                .config
+                .curve
+                .tokens_out(&launch.curve_state(), net_quote)
+                .ok_or(DiamondPadError::CurveQuoteFailed)?;
+            require!(tokens_out > 0, DiamondPadError::InvalidAmount);
+            require!(
+                launch.tokens_sold.checked_add(tokens_out).unwrap() <= launch.sale_supply,
+                DiamondPadError::SaleSupplyExhausted
+            );
+            require!(
+                launch.total_raised.checked_add(net_quote).unwrap() <= launch.hard_cap,
+                DiamondPadError::HardCapReached
+            );
+            require!(tokens_out >= min_tokens_out, DiamondPadError::SlippageExceeded);
+            require_within_position_cap(launch, position.balance, tokens_out)?;
+
+            // Reinvested rewards are refundable like any other contribution
+            let contribution = accounts
+                .contribution
+                .as_mut()
+                .ok_or(DiamondPadError::ContributionRequired)?;
+
+            // The reward fee never leaves the vault; it goes back to the pot
+            let reward_vault = accounts.reward_vault.to_account_info();
+            let system = accounts.system_program.to_account_info();
+            transfer_lamports(
+                reward_vault.clone(),
+                accounts.sol_vault.to_account_info(),
+                system.clone(),
+                net_quote,
+                &[&seeds[..]],
+            )?;
+            if let Some(channel) = accounts.channel.as_ref() {
+                transfer_lamports(
+                    reward_vault.clone(),
+                    channel.to_account_info(),
+                    system.clone(),
+                    channel_fee,
+                    &[&seeds[..]],
+                )?;
+            }
+            transfer_lamports(
+                reward_vault,
+                treasury.to_account_info(),
+                system,
+                protocol_fee - channel_fee,
+                &[&seeds[..]],
+            )?;
+
+            launch.virtual_sol_reserves =
+                launch.virtual_sol_reserves.checked_add(net_quote).unwrap();
+            launch.virtual_token_reserves =
+                launch.virtual_token_reserves.checked_sub(tokens_out).unwrap();
+            launch.tokens_sold = launch.tokens_sold.checked_add(tokens_out).unwrap();
+            launch.total_raised = launch.total_raised.checked_add(net_quote).unwrap();
+            launch.total_buy_volume = launch.total_buy_volume.checked_add(amount).unwrap();
+            distribute_rewards(launch, reward_fee);
+            launch.record_price(now);
+            trip_circuit_breaker(launch, now);
+            accounts
+                .protocol_stats
+                .record_trade(TradeSide::Buy, amount, fee, reward_fee, now);
+
+            contribution.quote_contributed =
+                contribution.quote_contributed.checked_add(net_quote).unwrap();
+            contribution.tokens_allocated =
+                contribution.tokens_allocated.checked_add(tokens_out).unwrap();
+            contribution.last_contribution_timestamp = now;
+
+            track_wash_trading(position, launch, TradeSide::Buy, now);
+            position.set_flag(POSITION_RISK_DEBOOSTED, screening.risk_deboosted);
+            let bump = position.bump;
+            credit_position(position, launch, holder, bump, tokens_out, now);
+            position.last_buy_slot = clock.slot;
+            tokens_out
+        }
+    };
+
+    position.accrued_rewards -= amount;
+    position.total_rewards_claimed = position.total_rewards_claimed.saturating_add(amount);
+    position.last_claim_timestamp = now as u32;
+    position.reset_twab(now);
+    launch.rewards_distributed = launch.rewards_distributed.saturating_add(amount);
+    accounts.protocol_stats.record_rewards_paid(amount, now);
+    if let Some(profile) = accounts.holder_profile.as_mut() {
+        if profile.wallets.contains(&position.holder) {
+            profile.total_rewards_claimed = profile.total_rewards_claimed.saturating_add(amount);
+            profile.record_position(position, now);
+        }
+    }
+
+    emit!(RewardsClaimed {
+        launch_id: launch.launch_id,
+        holder: position.holder,
+        destination,
+        amount,
+        denomination,
+        tokens_out,
+        multiplier_bps: position.multiplier_bps,
+        lp_boost_bps: position.lp_boost_bps,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+
+    refund_claim_gas(accounts, bumps, amount)?;
+
+    Ok(amount)
+}
+
+/// Reimburse a claiming holder's transaction fee from the launch's gas
+/// pool, within the per-claim refund and the wallet's epoch cap. Does
+/// nothing when sponsorship is off, the pool isn't passed, or the claim is
+/// under MIN_GAS_REFUNDED_CLAIM.
+fn refund_claim_gas(
+    accounts: &mut ClaimRewards,
+    bumps: &ClaimRewardsBumps,
+    claimed: u64,
+) -> Result<()> {
+    let launch = &mut accounts.launch;
+    let (Some(gas_pool), Some(bump)) = (accounts.gas_pool.as_ref(), bumps.gas_pool) else {
+        return Ok(());
+    };
+    if launch.gas_refund_per_claim == 0 || claimed < MIN_GAS_REFUNDED_CLAIM {
+        return Ok(());
+    }
+
+    let position = &mut accounts.position;
+    if position.gas_refund_epoch != launch.reward_epoch {
+        position.gas_refund_epoch = launch.reward_epoch;
+        position.gas_refunded_in_epoch = 0;
+    }
+    // Launches configured before the cap came down are held to it too
+    let refund = launch
+        .gas_refund_per_claim
+        .min(MAX_GAS_REFUND_PER_CLAIM)
+        .min(launch.gas_refund_cap_per_epoch.saturating_sub(position.gas_refunded_in_epoch))
+        .min(withdrawable_lamports(gas_pool)?);
+    if refund == 0 {
+        return Ok(());
+    }
+
+    let launch_key = launch.key();
+    let seeds = &[b"gas_pool".as_ref(), launch_key.as_ref(), &[bump]];
+    transfer_lamports(
+        gas_pool.to_account_info(),
+        accounts.holder.to_account_info(),
+        accounts.system_program.to_account_info(),
+        refund,
+        &[&seeds[..]],
+    )?;
+    position.gas_refunded_in_epoch += refund;
+    launch.gas_refunded = launch.gas_refunded.saturating_add(refund);
+
+    emit!(ClaimGasRefunded {
+        launch_id: launch.launch_id,
+        holder: position.holder,
+        amount: refund,
+        refunded_in_epoch: position.gas_refunded_in_epoch,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+
+    Ok(())
+}
+
+/// Add rank-weighted buy volume to a season score while the season runs
+fn record_season_activity(
+    season: &mut Season,
+    score: &mut SeasonScore,
+    quote_amount: u64,
+    multiplier_bps: u16,
+    now: i64,
+) {
+    if now < season.starts_at || now >= season.ends_at {
+        return;
+    }
+    let points = (quote_amount as u128 * multiplier_bps as u128 / 10000) as u64;
+    score.score = score.score.saturating_add(points);
+    score.last_activity_at = now;
+    season.total_score = season.total_score.saturating_add(points);
+}
+
+/// Newly arrived reward lamports wait in the pot until the current epoch closes
+fn distribute_rewards(launch: &mut Launch, amount: u64) {
+    launch.undistributed_rewards = launch.undistributed_rewards.saturating_add(amount);
+}
+
+/// Close any reward epochs that have ended: emit up to the launch's
+/// per-epoch budget from the pot over the current reward weight and carry
+/// the rest. Epochs with no activity are closed together in one step.
+fn roll_reward_epoch(launch: &mut Launch, now: i64) {
+    let epoch = ((now - launch.created_at).max(0) / REWARD_EPOCH_SECONDS) as u64;
+    if epoch <= launch.reward_epoch {
+        return;
+    }
+
+    let elapsed = epoch - launch.reward_epoch;
+    let budget = match launch.config.reward_epoch_budget {
+        0 => u64::MAX,
+        budget => budget.saturating_mul(elapsed),
+    };
+    let emitted = if launch.total_reward_weight == 0 {
+        0
+    } else {
+        launch.undistributed_rewards.min(budget)
+    };
+
+    if emitted > 0 {
+        launch.reward_index +=
+            emitted as u128 * REWARD_INDEX_PRECISION / launch.total_reward_weight;
+        launch.undistributed_rewards -= emitted;
+    }
+
+    emit!(RewardEpochClosed {
+        launch_id: launch.launch_id,
+        epoch: launch.reward_epoch,
+        epochs_closed: elapsed,
+        emitted,
+        carried_over: launch.undistributed_rewards,
+        total_reward_weight: launch.total_reward_weight,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+
+    // Weight added during the closed epoch counts from the next one
+    launch.total_reward_weight += launch.pending_reward_weight;
+    launch.pending_reward_weight = 0;
+    let first_started =
+        (launch.reward_epoch + 1).max(epoch.saturating_sub(REWARD_EPOCH_HISTORY as u64 - 1));
+    for started in first_started..=epoch {
+        let slot = (started % REWARD_EPOCH_HISTORY as u64) as usize;
+        launch.epoch_start_indices[slot] = launch.reward_index;
+    }
+
+    launch.reward_epoch = epoch;
+    launch.last_epoch_emission = emitted;
+}
+
+/// Reward index when `epoch` began. Beyond the kept history, falls back to
+/// the oldest start we still have (paying slightly less, never more).
+fn epoch_start_index(launch: &Launch, epoch: u64) -> u128 {
+    let oldest = launch.reward_epoch.saturating_sub(REWARD_EPOCH_HISTORY as u64 - 1);
+    launch.epoch_start_indices[(epoch.max(oldest) % REWARD_EPOCH_HISTORY as u64) as usize]
+}
+
+/// Credit a position with everything its weight earned through the last
+/// completed epoch. Rolls the launch's epoch first, then checkpoints the
+/// position on its first touch in a new epoch: weight it gained in an
+/// earlier epoch has been eligible since the epoch after, and is paid from
+/// that epoch's starting index. Earnings are scaled down to the position's
+/// time-weighted average balance since its last claim, with the cut
+/// returned to the reward pot, so a fresh top-up earns little until held.
+///
+/// The position caches the index it last settled at and what it had
+/// accrued by then, so this is constant work however long since the last
+/// claim; the epoch roll is bounded by REWARD_EPOCH_HISTORY.
+fn settle_rewards(position: &mut Position, launch: &mut Launch, now: i64) {
+    roll_reward_epoch(launch, now);
+    position.accumulate_twab(now);
+    // Already settled at this index and epoch: the cache is current
+    if position.reward_index_snapshot == launch.reward_index
+        && position.checkpoint_epoch == launch.reward_epoch
+    {
+        return;
+    }
+    let index_delta = launch.reward_index - position.reward_index_snapshot;
+    let mut earned = position.reward_weight * index_delta / REWARD_INDEX_PRECISION;
+
+    if position.checkpoint_epoch < launch.reward_epoch {
+        if position.pending_weight > 0 {
+            let eligible_from = epoch_start_index(launch, position.checkpoint_epoch + 1);
+            earned += position.pending_weight * (launch.reward_index - eligible_from)
+                / REWARD_INDEX_PRECISION;
+            position.reward_weight += position.pending_weight;
+            position.pending_weight = 0;
+        }
+        position.checkpoint_epoch = launch.reward_epoch;
+    }
+
+    let twab = position.twab().unwrap_or(position.balance);
+    if twab < position.balance {
+        let credited = earned * twab as u128 / position.balance as u128;
+        distribute_rewards(launch, (earned - credited) as u64);
+        earned = credited;
+    }
+
+    if position.accrued_rewards == 0 && earned > 0 {
+        notify(
+            position.holder,
+            position.launch,
+            NotificationKind::RewardsAvailable,
+            earned as u64,
+            now,
+        );
+    }
+    position.accrued_rewards = position.accrued_rewards.saturating_add(earned as u64);
+    position.reward_index_snapshot = launch.reward_index;
+}
+
+/// Re-derive a position's reward weight (balance x rank multiplier x
+/// claim boosts). A drop applies at once; a rise waits as pending weight
+/// until the current epoch closes, so each epoch pays on what was held for
+/// all of it. Call after `settle_rewards` so the position is checkpointed.
+fn sync_reward_weight(position: &mut Position, launch: &mut Launch) {
+    let weight = position.balance as u128
+        * position.multiplier_bps as u128
+        * (10000 + position.lp_boost_bps as u128 + position.referral_boost_bps as u128)
+        / 100_000_000;
+
+    let pending = weight.saturating_sub(position.reward_weight);
+    launch.pending_reward_weight = launch.pending_reward_weight - position.pending_weight + pending;
+    position.pending_weight = pending;
+
+    if weight < position.reward_weight {
+        launch.total_reward_weight -= position.reward_weight - weight;
+        position.reward_weight = weight;
+    }
+}
+
+/// Extra claim multiplier for holders who also provide liquidity: once
+/// their deposited LP (valued in launch tokens at graduation pool ratios)
+/// covers the launch's coverage threshold, they get the launch's LP boost
+/// scaled by their LP rank multiplier
+fn calculate_lp_boost_bps(
+    launch: &Launch,
+    position: &Position,
+    lp_position: Option<&LiquidityPosition>,
+) -> u16 {
+    let Some(lp_position) = lp_position else {
+        return 0;
+    };
+    if launch.config.lp_boost_bps == 0
+        || launch.lp_supply_at_graduation == 0
+        || position.balance == 0
+    {
+        return 0;
+    }
+
+    let lp_token_value = lp_position.lp_amount as u128 * launch.lp_pool_token_reserve as u128
+        / launch.lp_supply_at_graduation as u128;
+    let coverage_bps = lp_token_value * 10000 / position.balance as u128;
+    if coverage_bps < launch.config.lp_boost_coverage_bps as u128 {
+        return 0;
+    }
+
+    (launch.config.lp_boost_bps as u64 * lp_position.multiplier_bps as u64 / 10000)
+        .min(u16::MAX as u64) as u16
+}
+
+/// Share of a claim, in bps, a holder in the bundler registry still
+/// receives: nothing once confirmed, and for a suspected bundler half as
+/// much for every reward epoch since the flag, the first halving landing
+/// with the flag itself
+fn bundler_claim_bps(record: &AccountInfo, now: i64) -> Result<u16> {
+    if record.data_is_empty() {
+        return Ok(10000);
+    }
+    let bundler = Bundler::try_deserialize(&mut &record.data.borrow()[..])?;
+    Ok(match bundler.severity {
+        BundlerSeverity::Confirmed => 0,
+        BundlerSeverity::Suspected => {
+            let halvings = (now - bundler.flagged_at).max(0) / REWARD_EPOCH_SECONDS + 1;
+            10000u16.checked_shr(halvings.min(u32::MAX as i64) as u32).unwrap_or(0)
+        }
+    })
+}
+
+/// Move a position in or out of the launch's verified holder count.
+/// Returns whether anything changed.
+fn set_verified_holder(position: &mut Position, launch: &mut Launch, verified: bool) -> bool {
+    if position.has_flag(POSITION_VERIFIED) == verified {
+        return false;
+    }
+    position.set_flag(POSITION_VERIFIED, verified);
+    if verified {
+        launch.verified_holder_count += 1;
+    } else {
+        launch.verified_holder_count = launch.verified_holder_count.saturating_sub(1);
+    }
+    true
+}
+
+/// Delegate recorded on a holder's VoteDelegation PDA, if they have one
+fn read_vote_delegate(delegation: &AccountInfo) -> Result<Option<Pubkey>> {
+    if delegation.owner != &crate::ID || delegation.data_is_empty() {
+        return Ok(None);
+    }
+    let delegation = VoteDelegation::try_deserialize(&mut &delegation.data.borrow()[..])?;
+    Ok(Some(delegation.delegate))
+}
+
+/// Referrers earn a small boost per qualified referral, capped
+fn calculate_referral_boost_bps(qualified_referrals: u32) -> u16 {
+    (qualified_referrals as u64 * REFERRAL_BOOST_PER_REFERRAL_BPS as u64)
+        .min(MAX_REFERRAL_BOOST_BPS as u64) as u16
+}
+
+/// Recompute rank and multiplier. Insider positions never earn rewards.
+fn refresh_rank(position: &mut Position, launch: &Launch, now: i64) {
+    let threshold_days = &launch.rank_thresholds();
+    let previous_rank = position.diamond_rank;
+    position.diamond_rank = calculate_diamond_rank(position.rank_since(), now, threshold_days);
+    if position.diamond_rank > previous_rank && position.balance > 0 {
+        notify(
+            position.holder,
+            position.launch,
+            NotificationKind::RankUp,
+            position.diamond_rank as u64,
+            now,
+        );
+    }
+    position.record_rank_timeline(threshold_days);
+    position.multiplier_bps = if position.has_flag(POSITION_INSIDER | POSITION_REWARDS_FORFEITED) {
+        0
+    } else if position.has_flag(POSITION_RISK_DEBOOSTED) {
+        get_diamond_multiplier_bps(DiamondRank::Paper)
+    } else {
+        get_diamond_multiplier_bps(position.diamond_rank)
+    };
+}
+
+/// Count direction flips that land within the wash window of the holder's
+/// previous trade. Past the launch's cutoff the position stops earning.
+fn track_wash_trading(
+    position: &mut Account<Position>,
+    launch: &Account<Launch>,
+    side: TradeSide,
+    now: i64,
+) {
+    let is_flip = position.last_trade_timestamp > 0
+        && position.last_trade_side != side
+        && now - position.last_trade_timestamp as i64 <= WASH_WINDOW_SECONDS;
+    position.last_trade_timestamp = now as u32;
+    position.last_trade_side = side;
+
+    if !is_flip {
+        return;
+    }
+
+    position.rapid_flips = position.rapid_flips.saturating_add(1);
+    position.wash_score = (position.rapid_flips as u64 * WASH_SCORE_PER_FLIP_BPS)
+        .min(10000) as u16;
+
+    let cutoff = launch.config.wash_score_reward_cutoff;
+    if cutoff > 0 && position.wash_score >= cutoff {
+        position.set_flag(POSITION_REWARDS_FORFEITED, true);
+    }
+
+    emit!(WashScoreUpdated {
+        launch_id: launch.launch_id,
+        holder: position.holder,
+        rapid_flips: position.rapid_flips,
+        wash_score: position.wash_score,
+        rewards_forfeited: position.has_flag(POSITION_REWARDS_FORFEITED),
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+}
+
+/// LP positions use the same hold-time ranks as token positions
+fn refresh_lp_rank(lp_position: &mut LiquidityPosition, launch: &Launch, now: i64) {
+    lp_position.diamond_rank = calculate_diamond_rank(
+        lp_position.first_deposit_timestamp,
+        now,
+        &launch.rank_thresholds(),
+    );
+    lp_position.multiplier_bps = get_diamond_multiplier_bps(lp_position.diamond_rank);
+}
+
+/// Remove tokens from a holder's position. Sells within the launch's grace
+/// allowance for the rolling window keep the rank; anything beyond is
+/// penalised according to the launch's sell policy. Returns whether the
+/// holder was reset to Paper.
+///
+/// Just after graduation the allowance is raised by the launch's
+/// graduation grace, so early profit-taking on the open market doesn't
+/// reset every holder at once.
+fn debit_position(
+    position: &mut Account<Position>,
+    launch: &mut Account<Launch>,
+    amount: u64,
+    now: i64,
+) -> bool {
+    settle_rewards(position, launch, now);
+    if now - position.grace_window_start as i64 >= SELL_GRACE_WINDOW_DAYS * 86400 {
+        position.grace_window_start = now as u32;
+        position.grace_window_base = position.balance;
+        position.grace_window_sold = 0;
+    }
+
+    let grace_bps = if launch.in_graduation_grace(now) {
+        launch.config.sell_grace_bps + launch.config.graduation_grace_bps
+    } else {
+        launch.config.sell_grace_bps
+    };
+    let grace_allowance = (position.grace_window_base as u128)
+        .checked_mul(grace_bps as u128).unwrap()
+        / 10000;
+    let sold_in_window = position.grace_window_sold.checked_add(amount).unwrap();
+    let mut rank_reset = false;
+
+    if sold_in_window as u128 <= grace_allowance {
+        position.grace_window_sold = sold_in_window;
+    } else {
+        match launch.config.sell_policy {
+            SellPolicy::ResetToPaper => {
+                position.first_buy_timestamp = now as u32;
+                position.clear_linked_entry();
+                rank_reset = true;
+            }
+            SellPolicy::ProportionalAging => {
+                // Selling X% moves the entry time X% of the way to now
+                let held_for = (now - position.first_buy_timestamp as i64).max(0) as u128;
+                let shift = held_for
+                    .checked_mul(amount as u128).unwrap()
+                    / position.balance as u128;
+                position.first_buy_timestamp += shift as u32;
+                // A borrowed linked entry doesn't survive selling over grace
+                position.clear_linked_entry();
+            }
+        }
+    }
+
+    position.balance = position.balance.checked_sub(amount).unwrap();
+    position.last_activity_timestamp = now as u32;
+    refresh_rank(position, launch, now);
+    sync_reward_weight(position, launch);
+
+    if position.balance == 0 {
+        launch.holder_count = launch.holder_count.saturating_sub(1);
+    }
+    if !launch.holds_verified_balance(position.balance) {
+        set_verified_holder(position, launch, false);
+    }
+
+    emit!(PositionUpdated {
+        holder: position.holder,
+        launch: position.launch,
+        balance: position.balance,
+        diamond_rank: position.diamond_rank,
+        multiplier_bps: position.multiplier_bps,
+        schema_version: EVENT_SCHEMA_VERSION,
+    });
+
+    rank_reset
+}
+
+/// Tokens sold through the curve: whatever is left after dev, LP and rewards
+fn calculate_sale_supply(
+    total_supply: u64,
+    dev_allocation_bps: u16,
+    liquidity_pool_bps: u16,
+    holder_rewards_bps: u16,
+    graduation_airdrop_bps: u16,
+) -> u64 {
+    let reserved_bps = (dev_allocation_bps as u64)
+        .saturating_add(liquidity_pool_bps as u64)
+        .saturating_add(holder_rewards_bps as u64)
+        .saturating_add(graduation_airdrop_bps as u64)
+        .min(10000);
+    ((total_supply as u128) * (10000 - reserved_bps) as u128 / 10000) as u64
+}
+
+fn calculate_staking_tier(amount: u64, lock_days: u16) -> StakingTier {
+    if amount >= 100_000_000_000 && lock_days >= 180 { // 100k tokens (assuming 6 decimals)
+        StakingTier::Diamond
+    } else if amount >= 50_000_000_000 && lock_days >= 90 {
+        StakingTier::Gold
+    } else if amount >= 20_000_000_000 && lock_days >= 60 {
+        StakingTier::Silver
+    } else if amount >= 5_000_000_000 && lock_days >= 30 {
+        StakingTier::Bronze
+    } else {
+        StakingTier::Public
+    }
+}
+
+fn get_tier_weight(tier: StakingTier) -> u16 {
+    match tier {
+        StakingTier::Diamond => 1000,  // 10x
+        StakingTier::Gold => 500,      // 5x
+        StakingTier::Silver => 250,    // 2.5x
+        StakingTier::Bronze => 100,    // 1x
+        StakingTier::Public => 25,     // 0.25x
+    }
+}
+
+fn calculate_diamond_rank(first_buy: i64, now: i64, threshold_days: &[u16; 6]) -> DiamondRank {
+    let days_held = (now - first_buy) / 86400;
+    DiamondRank::ALL
+        .into_iter()
+        .rev()
+        .find(|rank| days_held >= threshold_days[*rank as usize] as i64)
+        .unwrap_or(DiamondRank::Paper)
+}
+
+fn get_diamond_multiplier_bps(rank: DiamondRank) -> u16 {
+    match rank {
+        DiamondRank::Paper => 10000,
+        DiamondRank::Bronze => 15000,
+        DiamondRank::Silver => 20000,
+        DiamondRank::Gold => 25000,
+        DiamondRank::Platinum => 30000,
+        DiamondRank::Diamond => 35000,
+    }
+}
+
+fn calculate_vested_amount(
+    total: u64,
+    start: i64,
+    cliff_days: u16,
+    duration_days: u16,
+    tge_bps: u16,
+    now: i64,
+) -> u64 {
+    let tge_amount = total.checked_mul(tge_bps as u64).unwrap() / 10000;
+    let vesting_amount = total.checked_sub(tge_amount).unwrap();
+    
+    let elapsed = now - start;
+    let cliff_seconds = cliff_days as i64 * 86400;
+    let duration_seconds = duration_days as i64 * 86400;
+    
+    if elapsed < cliff_seconds {
+        return tge_amount;
+    }
+    
+    let vesting_elapsed = elapsed - cliff_seconds;
+    if vesting_elapsed >= duration_seconds {
+        return total;
+    }
+    
+    let vested = vesting_amount
+        .checked_mul(vesting_elapsed as u64).unwrap()
+        .checked_div(duration_seconds as u64).unwrap();
+    
+    tge_amount.checked_add(vested).unwrap()
+}
+
+// ============ Account Contexts ============
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = Protocol::SIZE,
+        seeds = [b"protocol"],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = ProtocolStats::SIZE,
+        seeds = [b"protocol_stats"],
+        bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitProtocolStats<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ProtocolStats::SIZE,
+        seeds = [b"protocol_stats"],
+        bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetProtocolRole<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyFeatureFlags<'info> {
+    pub cranker: Signer<'info>,
+    
+    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyProgramImmutability<'info> {
+    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Diamondpad>,
+    
+    pub program_data: Account<'info, ProgramData>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = StakerAccount::SIZE,
+        seeds = [b"staker", owner.key().as_ref()],
+        bump
+    )]
+    pub staker_account: Account<'info, StakerAccount>,
+    
+    #[account(mut)]
+    pub staker_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, seeds = [b"vault"], bump)]
+    pub vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        mut,
+        seeds = [b"staker", owner.key().as_ref()],
+        bump = staker_account.bump,
+        constraint = staker_account.owner == owner.key()
+    )]
+    pub staker_account: Account<'info, StakerAccount>,
+    
+    #[account(mut)]
+    pub staker_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, seeds = [b"vault"], bump)]
+    pub vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct VoteGauge<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        seeds = [b"staker", owner.key().as_ref()],
+        bump = staker_account.bump,
+        constraint = staker_account.owner == owner.key()
+    )]
+    pub staker_account: Account<'info, StakerAccount>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = GaugeEpoch::SIZE,
+        seeds = [b"gauge_epoch", epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub gauge_epoch: Account<'info, GaugeEpoch>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = LaunchGauge::SIZE,
+        seeds = [b"launch_gauge", launch.key().as_ref(), epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub launch_gauge: Account<'info, LaunchGauge>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = GaugeVote::SIZE,
+        seeds = [b"gauge_vote", epoch.to_le_bytes().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub gauge_vote: Account<'info, GaugeVote>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct DistributeGaugeEmissions<'info> {
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(seeds = [b"gauge_epoch", epoch.to_le_bytes().as_ref()], bump = gauge_epoch.bump)]
+    pub gauge_epoch: Account<'info, GaugeEpoch>,
+    
+    #[account(
+        mut,
+        seeds = [b"launch_gauge", launch.key().as_ref(), epoch.to_le_bytes().as_ref()],
+        bump = launch_gauge.bump
+    )]
+    pub launch_gauge: Account<'info, LaunchGauge>,
+    
+    #[account(mut, seeds = [b"emissions_vault"], bump)]
+    pub emissions_vault: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"reward_vault", launch.key().as_ref()], bump)]
+    pub reward_vault: SystemAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String, symbol: String)]
+pub struct CreateLaunch<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = Launch::SIZE,
+        seeds = [b"launch", protocol.total_launches.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut, seeds = [b"sol_vault", launch.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"reward_vault", launch.key().as_ref()], bump)]
+    pub reward_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_stake", creator.key().as_ref()],
+        bump = creator_stake.bump
+    )]
+    pub creator_stake: Option<Account<'info, CreatorStake>>,
+    
+    #[account(mut, seeds = [b"review_queue"], bump = review_queue.bump)]
+    pub review_queue: Option<Account<'info, ReviewQueue>>,
+    
+    /// Partner channel the launch is created through, if any
+    #[account(mut)]
+    pub channel: Option<Account<'info, Channel>>,
+    
+    /// CHECK: Token-2022 mint created by the instruction; omit all three
+    /// mint accounts for a launch without its own mint
+    #[account(mut, seeds = [b"launch_mint", launch.key().as_ref()], bump)]
+    pub launch_mint: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: PDA holding the launch mint's authorities
+    #[account(seeds = [b"launch_authority", launch.key().as_ref()], bump)]
+    pub launch_authority: Option<UncheckedAccount<'info>>,
+    
+    pub token_program: Option<Program<'info, Token2022>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct HarvestTransferFees<'info> {
+    #[account(
+        mut,
+        constraint = launch.mint != Pubkey::default() @ DiamondPadError::LaunchMintRequired,
+        constraint = !launch.config.freeze_until_graduation || launch.transfers_thawed
+            @ DiamondPadError::TransfersStillFrozen
+    )]
+    pub launch: Account<'info, Launch>,
+    
+    /// CHECK: the launch's Token-2022 mint; the token program validates it
+    #[account(mut, address = launch.mint)]
+    pub launch_mint: UncheckedAccount<'info>,
+    
+    /// CHECK: PDA holding the mint's withdraw-withheld authority
+    #[account(seeds = [b"launch_authority", launch.key().as_ref()], bump)]
+    pub launch_authority: UncheckedAccount<'info>,
+    
+    #[account(seeds = [b"reward_vault", launch.key().as_ref()], bump)]
+    pub reward_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        token::mint = launch_mint,
+        token::token_program = token_program,
+        constraint = reward_token_account.owner == reward_vault.key()
+            @ DiamondPadError::InvalidFeeDestination
+    )]
+    pub reward_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct ThawLaunchTransfers<'info> {
+    #[account(
+        mut,
+        constraint = launch.config.freeze_until_graduation @ DiamondPadError::TransfersNotFrozen,
+        constraint = launch.status == LaunchStatus::Graduated
+    )]
+    pub launch: Account<'info, Launch>,
+    
+    /// CHECK: the launch's Token-2022 mint; the token program validates it
+    #[account(mut, address = launch.mint)]
+    pub launch_mint: UncheckedAccount<'info>,
+    
+    /// CHECK: PDA holding the mint's freeze authority
+    #[account(seeds = [b"launch_authority", launch.key().as_ref()], bump)]
+    pub launch_authority: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+#[instruction(seed: String)]
+pub struct CreateLaunchWithSeed<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = Launch::SIZE,
+        seeds = [b"launch", creator.key().as_ref(), seed.as_bytes()],
+        bump
+    )]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = LaunchIndex::SIZE,
+        seeds = [b"launch_index", protocol.total_launches.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub launch_index: Account<'info, LaunchIndex>,
+    
+    #[account(mut, seeds = [b"sol_vault", launch.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"reward_vault", launch.key().as_ref()], bump)]
+    pub reward_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_stake", creator.key().as_ref()],
+        bump = creator_stake.bump
+    )]
+    pub creator_stake: Option<Account<'info, CreatorStake>>,
+    
+    #[account(mut, seeds = [b"review_queue"], bump = review_queue.bump)]
+    pub review_queue: Option<Account<'info, ReviewQueue>>,
+    
+    /// Partner channel the launch is created through, if any
+    #[account(mut)]
+    pub channel: Option<Account<'info, Channel>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenCreatorAccount<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = CreatorAccount::SIZE,
+        seeds = [b"creator_account", creator.key().as_ref()],
+        bump
+    )]
+    pub creator_account: Account<'info, CreatorAccount>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(shard: u8)]
+pub struct OpenLaunchIdShard<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = LaunchIdShard::SIZE,
+        seeds = [b"launch_id_shard", shard.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub launch_id_shard: Account<'info, LaunchIdShard>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateCreatorLaunch<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_account", creator.key().as_ref()],
+        bump = creator_account.bump
+    )]
+    pub creator_account: Account<'info, CreatorAccount>,
+    
+    /// Any open shard; clients spread creates across them
+    #[account(
+        mut,
+        seeds = [b"launch_id_shard", launch_id_shard.shard.to_le_bytes().as_ref()],
+        bump = launch_id_shard.bump
+    )]
+    pub launch_id_shard: Account<'info, LaunchIdShard>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = Launch::SIZE,
+        seeds = [
+            b"creator_launch",
+            creator.key().as_ref(),
+            creator_account.launch_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = LaunchIndex::SIZE,
+        seeds = [b"launch_index", launch_id_shard.next_launch_id().to_le_bytes().as_ref()],
+        bump
+    )]
+    pub launch_index: Account<'info, LaunchIndex>,
+    
+    #[account(mut, seeds = [b"sol_vault", launch.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"reward_vault", launch.key().as_ref()], bump)]
+    pub reward_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_stake", creator.key().as_ref()],
+        bump = creator_stake.bump
+    )]
+    pub creator_stake: Option<Account<'info, CreatorStake>>,
+    
+    #[account(mut, seeds = [b"review_queue"], bump = review_queue.bump)]
+    pub review_queue: Option<Account<'info, ReviewQueue>>,
+    
+    /// Partner channel the launch is created through, if any
+    #[account(mut)]
+    pub channel: Option<Account<'info, Channel>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct PublishTemplate<'info> {
+    #[account(mut)]
+    pub publisher: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init,
+        payer = publisher,
+        space = LaunchTemplate::SIZE,
+        seeds = [b"template", publisher.key().as_ref(), name.as_bytes()],
+        bump
+    )]
+    pub template: Account<'info, LaunchTemplate>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetTemplateVerified<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut)]
+    pub template: Account<'info, LaunchTemplate>,
+}
+
+#[derive(Accounts)]
+pub struct CloneLaunch<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = Launch::SIZE,
+        seeds = [b"launch", protocol.total_launches.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub launch: Account<'info, Launch>,
+    
+    pub source_launch: Box<Account<'info, Launch>>,
+    
+    #[account(mut, seeds = [b"sol_vault", launch.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"reward_vault", launch.key().as_ref()], bump)]
+    pub reward_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_stake", creator.key().as_ref()],
+        bump = creator_stake.bump
+    )]
+    pub creator_stake: Option<Account<'info, CreatorStake>>,
+    
+    #[account(mut, seeds = [b"review_queue"], bump = review_queue.bump)]
+    pub review_queue: Option<Account<'info, ReviewQueue>>,
+    
+    /// Partner channel the launch is created through, if any
+    #[account(mut)]
+    pub channel: Option<Account<'info, Channel>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateLaunchFromTemplate<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = Launch::SIZE,
+        seeds = [b"launch", protocol.total_launches.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut)]
+    pub template: Account<'info, LaunchTemplate>,
+    
+    #[account(mut, seeds = [b"sol_vault", launch.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"reward_vault", launch.key().as_ref()], bump)]
+    pub reward_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_stake", creator.key().as_ref()],
+        bump = creator_stake.bump
+    )]
+    pub creator_stake: Option<Account<'info, CreatorStake>>,
+    
+    #[account(mut, seeds = [b"review_queue"], bump = review_queue.bump)]
+    pub review_queue: Option<Account<'info, ReviewQueue>>,
+    
+    /// Partner channel the launch is created through, if any
+    #[account(mut)]
+    pub channel: Option<Account<'info, Channel>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLpLock<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = launch.creator == creator.key()
+    )]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut, seeds = [b"creator_checklist", launch.key().as_ref()], bump = checklist.bump)]
+    pub checklist: Option<Account<'info, CreatorChecklist>>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshQualityScore<'info> {
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    /// Required when the launch has its own mint
+    #[account(address = launch.mint)]
+    pub launch_mint: Option<InterfaceAccount<'info, token_interface::Mint>>,
+    
+    /// CHECK: the creator's CreatorStake PDA, which may not exist
+    #[account(seeds = [b"creator_stake", launch.creator.as_ref()], bump)]
+    pub creator_stake: UncheckedAccount<'info>,
+    
+    #[account(mut, seeds = [b"creator_checklist", launch.key().as_ref()], bump = checklist.bump)]
+    pub checklist: Option<Account<'info, CreatorChecklist>>,
+}
+
+#[derive(Accounts)]
+pub struct OpenCreatorChecklist<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    /// The launch's own mint, if it has one, to check its metadata
+    #[account(address = launch.mint)]
+    pub launch_mint: Option<InterfaceAccount<'info, token_interface::Mint>>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = CreatorChecklist::SIZE,
+        seeds = [b"creator_checklist", launch.key().as_ref()],
+        bump
+    )]
+    pub checklist: Account<'info, CreatorChecklist>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLaunchTerms<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = launch.creator == creator.key()
+    )]
+    pub launch: Account<'info, Launch>,
+}
+
+#[derive(Accounts)]
+pub struct DeclareInsider<'info> {
+    pub creator: Signer<'info>,
+    
+    /// The team wallet being declared, consenting to the tag
+    pub insider: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = launch.creator == creator.key()
+    )]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", launch.key().as_ref(), insider.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Option<Account<'info, Position>>,
+}
+
+#[derive(Accounts)]
+pub struct ModerateLaunch<'info> {
+    pub moderator: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.moderator == moderator.key() || mod_assignment.is_some()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    /// The moderator's assignment to this launch, if not the protocol moderator
+    #[account(
+        seeds = [b"mod_assignment", launch.key().as_ref(), moderator.key().as_ref()],
+        bump = mod_assignment.bump
+    )]
+    pub mod_assignment: Option<Account<'info, ModAssignment>>,
+}
+
+#[derive(Accounts)]
+#[instruction(moderator: Pubkey)]
+pub struct AssignLaunchModerator<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = ModAssignment::SIZE,
+        seeds = [b"mod_assignment", launch.key().as_ref(), moderator.as_ref()],
+        bump
+    )]
+    pub mod_assignment: Account<'info, ModAssignment>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveLaunchModerator<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"mod_assignment", launch.key().as_ref(), mod_assignment.moderator.as_ref()],
+        bump = mod_assignment.bump
+    )]
+    pub mod_assignment: Account<'info, ModAssignment>,
+}
+
+#[derive(Accounts)]
+pub struct SetCuratedMode<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ReviewQueue::SIZE,
+        seeds = [b"review_queue"],
+        bump
+    )]
+    pub review_queue: Account<'info, ReviewQueue>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveLaunch<'info> {
+    pub reviewer: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.reviewer == reviewer.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut, seeds = [b"review_queue"], bump = review_queue.bump)]
+    pub review_queue: Account<'info, ReviewQueue>,
+}
+
+#[derive(Accounts)]
+pub struct RejectLaunch<'info> {
+    pub reviewer: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.reviewer == reviewer.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut, close = creator, has_one = creator)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut, seeds = [b"review_queue"], bump = review_queue.bump)]
+    pub review_queue: Account<'info, ReviewQueue>,
+    
+    /// CHECK: receives the closed launch's rent; checked against launch.creator
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+    
+    #[account(mut, constraint = treasury.key() == protocol.treasury)]
+    pub treasury: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"sol_vault", launch.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"reward_vault", launch.key().as_ref()], bump)]
+    pub reward_vault: SystemAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StakeCreatorSol<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = CreatorStake::SIZE,
+        seeds = [b"creator_stake", creator.key().as_ref()],
+        bump
+    )]
+    pub creator_stake: Account<'info, CreatorStake>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StakeCreatorDpad<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = CreatorStake::SIZE,
+        seeds = [b"creator_stake", creator.key().as_ref()],
+        bump
+    )]
+    pub creator_stake: Account<'info, CreatorStake>,
+    
+    #[account(address = protocol.launch_token_mint)]
+    pub launch_token_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub creator_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        token::mint = launch_token_mint,
+        token::authority = protocol,
+        seeds = [b"creator_stake_vault"],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeCreator<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_stake", creator.key().as_ref()],
+        bump = creator_stake.bump,
+        constraint = creator_stake.creator == creator.key()
+    )]
+    pub creator_stake: Account<'info, CreatorStake>,
+    
+    #[account(mut)]
+    pub creator_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, seeds = [b"creator_stake_vault"], bump)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SlashCreatorStake<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(seeds = [b"council"], bump = council.bump)]
+    pub council: Account<'info, Council>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_stake", launch.creator.as_ref()],
+        bump = creator_stake.bump
+    )]
+    pub creator_stake: Account<'info, CreatorStake>,
+    
+    #[account(mut, seeds = [b"creator_stake_vault"], bump)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = InsuranceVault::SIZE,
+        seeds = [b"insurance_vault", launch.key().as_ref()],
+        bump
+    )]
+    pub insurance_vault: Account<'info, InsuranceVault>,
+    
+    #[account(address = protocol.launch_token_mint)]
+    pub launch_token_mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        token::mint = launch_token_mint,
+        token::authority = insurance_vault,
+        seeds = [b"insurance_tokens", launch.key().as_ref()],
+        bump
+    )]
+    pub insurance_token_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct UpdateWalletRisk<'info> {
+    #[account(mut)]
+    pub risk_oracle: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.risk_oracle == risk_oracle.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init_if_needed,
+        payer = risk_oracle,
+        space = WalletRisk::SIZE,
+        seeds = [b"wallet_risk", wallet.as_ref()],
+        bump
+    )]
+    pub wallet_risk: Account<'info, WalletRisk>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(kind: AttestationKind)]
+pub struct RecordAttestation<'info> {
+    #[account(mut)]
+    pub verifier: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.verifier == verifier.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    /// CHECK: Creator wallet being attested
+    pub creator: UncheckedAccount<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = verifier,
+        space = Attestation::SIZE,
+        seeds = [b"attestation", creator.key().as_ref(), &[kind as u8]],
+        bump
+    )]
+    pub attestation: Account<'info, Attestation>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeAttestation<'info> {
+    pub verifier: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.verifier == verifier.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut)]
+    pub attestation: Account<'info, Attestation>,
+}
+
+#[derive(Accounts)]
+pub struct RequestAllocation<'info> {
+    #[account(mut)]
+    pub requester: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    #[account(seeds = [b"staker", requester.key().as_ref()], bump = staker_account.bump)]
+    pub staker_account: Account<'info, StakerAccount>,
+    
+    #[account(
+        init,
+        payer = requester,
+        space = Allocation::SIZE,
+        seeds = [b"allocation", launch.key().as_ref(), requester.key().as_ref()],
+        bump
+    )]
+    pub allocation: Account<'info, Allocation>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FulfillAllocation<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut)]
+    pub allocation: Account<'info, Allocation>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = VestingSummary::SIZE,
+        seeds = [b"vesting_summary", allocation.owner.as_ref()],
+        bump
+    )]
+    pub vesting_summary: Account<'info, VestingSummary>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_checklist", allocation.launch.as_ref()],
+        bump = checklist.bump
+    )]
+    pub checklist: Option<Account<'info, CreatorChecklist>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAllocation<'info> {
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = allocation.owner == claimer.key()
+    )]
+    pub allocation: Account<'info, Allocation>,
+    
+    /// Opened here for schedules fulfilled before summaries existed
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        space = VestingSummary::SIZE,
+        seeds = [b"vesting_summary", claimer.key().as_ref()],
+        bump
+    )]
+    pub vesting_summary: Account<'info, VestingSummary>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RebuildVestingSummary<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = VestingSummary::SIZE,
+        seeds = [b"vesting_summary", owner.key().as_ref()],
+        bump
+    )]
+    pub vesting_summary: Account<'info, VestingSummary>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewVesting<'info> {
+    pub allocation: Account<'info, Allocation>,
+}
+
+#[derive(Accounts)]
+pub struct ListOtcSale<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(constraint = launch.creator == seller.key() @ DiamondPadError::Unauthorized)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        constraint = allocation.owner == seller.key(),
+        constraint = allocation.launch == launch.key(),
+        constraint = allocation.otc_tokens == 0 @ DiamondPadError::InvalidOtcSale
+    )]
+    pub allocation: Account<'info, Allocation>,
+    
+    #[account(
+        init,
+        payer = seller,
+        space = OtcSale::SIZE,
+        seeds = [b"otc_sale", allocation.key().as_ref()],
+        bump
+    )]
+    pub otc_sale: Account<'info, OtcSale>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOtcSale<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = seller,
+        has_one = seller,
+        seeds = [b"otc_sale", otc_sale.allocation.as_ref()],
+        bump = otc_sale.bump,
+        constraint = otc_sale.status == OtcSaleStatus::Listed @ DiamondPadError::OtcSaleNotOpen
+    )]
+    pub otc_sale: Account<'info, OtcSale>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOtcSale<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    pub seller: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        has_one = seller,
+        has_one = allocation,
+        seeds = [b"otc_sale", allocation.key().as_ref()],
+        bump = otc_sale.bump
+    )]
+    pub otc_sale: Account<'info, OtcSale>,
+    
+    #[account(mut)]
+    pub allocation: Account<'info, Allocation>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = VestingSummary::SIZE,
+        seeds = [b"vesting_summary", seller.key().as_ref()],
+        bump
+    )]
+    pub vesting_summary: Account<'info, VestingSummary>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimOtcTokens<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = buyer,
+        has_one = allocation,
+        has_one = launch,
+        seeds = [b"otc_sale", allocation.key().as_ref()],
+        bump = otc_sale.bump,
+        constraint = otc_sale.status == OtcSaleStatus::Filled @ DiamondPadError::OtcSaleNotOpen
+    )]
+    pub otc_sale: Account<'info, OtcSale>,
+    
+    pub allocation: Account<'info, Allocation>,
+    
+    /// Same gate as `claim_tokens`: nothing is minted before graduation
+    #[account(
+        mut,
+        constraint = launch.status == LaunchStatus::Graduated @ DiamondPadError::LaunchNotGraduated,
+        constraint = launch.mint != Pubkey::default() @ DiamondPadError::LaunchMintRequired
+    )]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut, address = launch.mint)]
+    pub launch_mint: InterfaceAccount<'info, token_interface::Mint>,
+    
+    /// CHECK: PDA holding the mint authority
+    #[account(seeds = [b"launch_authority", launch.key().as_ref()], bump)]
+    pub launch_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = launch_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = token_program
+    )]
+    pub buyer_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    
+    pub token_program: Program<'info, Token2022>,
+    
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawOtcProceeds<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = seller,
+        has_one = allocation,
+        seeds = [b"otc_sale", allocation.key().as_ref()],
+        bump = otc_sale.bump,
+        constraint = otc_sale.status == OtcSaleStatus::Filled @ DiamondPadError::OtcSaleNotOpen
+    )]
+    pub otc_sale: Account<'info, OtcSale>,
+    
+    pub allocation: Account<'info, Allocation>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+    
+    #[account(seeds = [b"council"], bump = council.bump)]
+    pub council: Account<'info, Council>,
+    
+    #[account(mut)]
+    pub allocation: Account<'info, Allocation>,
+    
+    #[account(
+        init_if_needed,
+        payer = submitter,
+        space = VestingSummary::SIZE,
+        seeds = [b"vesting_summary", allocation.owner.as_ref()],
+        bump
+    )]
+    pub vesting_summary: Account<'info, VestingSummary>,
+    
+    /// The schedule's OTC sale; required once it has been sold
+    #[account(
+        mut,
+        seeds = [b"otc_sale", allocation.key().as_ref()],
+        bump = otc_sale.bump
+    )]
+    pub otc_sale: Option<Account<'info, OtcSale>>,
+    
+    #[account(mut)]
+    pub otc_buyer: Option<SystemAccount<'info>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(partner: Pubkey)]
+pub struct RegisterChannel<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = Channel::SIZE,
+        seeds = [b"channel", partner.as_ref()],
+        bump
+    )]
+    pub channel: Account<'info, Channel>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateChannel<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut, seeds = [b"channel", channel.partner.as_ref()], bump = channel.bump)]
+    pub channel: Account<'info, Channel>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimChannelFees<'info> {
+    #[account(mut)]
+    pub partner: Signer<'info>,
+    
+    #[account(mut, seeds = [b"channel", partner.key().as_ref()], bump = channel.bump)]
+    pub channel: Account<'info, Channel>,
+}
+
+#[derive(Accounts)]
+#[instruction(program: Pubkey)]
+pub struct AddRouter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = ApprovedRouter::SIZE,
+        seeds = [b"router", program.as_ref()],
+        bump
+    )]
+    pub router: Account<'info, ApprovedRouter>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveRouter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"router", router.program.as_ref()],
+        bump = router.bump
+    )]
+    pub router: Account<'info, ApprovedRouter>,
+}
+
+#[derive(Accounts)]
+pub struct RecordPosition<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut, constraint = launch.mint != Pubkey::default() @ DiamondPadError::LaunchMintRequired)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = Position::SIZE,
+        seeds = [b"position", launch.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    /// CHECK: the holder's Token-2022 associated token account for the
+    /// launch mint, read to bound the recorded balance
+    #[account(
+        address = get_associated_token_address_with_program_id(
+            &holder.key(),
+            &launch.mint,
+            &Token2022::id(),
+        )
+    )]
+    pub holder_token_account: UncheckedAccount<'info>,
+    
+    /// Allowlist entry for the router program, when called via CPI
+    #[account(seeds = [b"router", router.program.as_ref()], bump = router.bump)]
+    pub router: Option<Account<'info, ApprovedRouter>>,
+    
+    /// CHECK: Instructions sysvar, read to find a CPI caller
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordSell<'info> {
+    pub holder: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", launch.key().as_ref(), holder.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    /// Allowlist entry for the router program, when called via CPI
+    #[account(seeds = [b"router", router.program.as_ref()], bump = router.bump)]
+    pub router: Option<Account<'info, ApprovedRouter>>,
+    
+    /// CHECK: Instructions sysvar, read to find a CPI caller
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GcPosition<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        close = holder,
+        seeds = [b"position", launch.key().as_ref(), position.holder.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    /// CHECK: the position's holder, who receives the rest of the rent
+    #[account(mut, address = position.holder)]
+    pub holder: UncheckedAccount<'info>,
+    
+    /// CHECK: the holder's contribution, which may not exist; it must have
+    /// nothing left to claim
+    #[account(seeds = [b"contribution", launch.key().as_ref(), position.holder.as_ref()], bump)]
+    pub contribution: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpgradePosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    /// CHECK: a Position too short to deserialize until it is reallocated;
+    /// the discriminator is checked once it has been
+    #[account(mut, owner = crate::ID)]
+    pub position: UncheckedAccount<'info>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpgradeLaunch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    /// CHECK: a Launch in the old layout, which can't deserialize as a
+    /// Launch; its size and discriminator are checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub launch: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshVerifiedHolder<'info> {
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", launch.key().as_ref(), position.holder.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    /// CHECK: Bundler PDA for the holder; may not exist if they were never flagged
+    #[account(seeds = [b"bundler", position.holder.as_ref()], bump)]
+    pub bundler: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReconcilePosition<'info> {
+    #[account(mut, constraint = launch.mint != Pubkey::default() @ DiamondPadError::LaunchMintRequired)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", launch.key().as_ref(), position.holder.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    /// CHECK: the holder's Token-2022 associated token account for the
+    /// launch mint, which may have been closed
+    #[account(
+        address = get_associated_token_address_with_program_id(
+            &position.holder,
+            &launch.mint,
+            &Token2022::id(),
+        )
+    )]
+    pub holder_token_account: UncheckedAccount<'info>,
+    
+    /// CHECK: the holder's contribution, which may not exist; tokens it
+    /// still owes count as held
+    #[account(seeds = [b"contribution", launch.key().as_ref(), position.holder.as_ref()], bump)]
+    pub contribution: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GiftPosition<'info> {
+    #[account(mut)]
+    pub giver: Signer<'info>,
+    
+    /// CHECK: Wallet receiving the gift
+    pub recipient: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", launch.key().as_ref(), giver.key().as_ref()],
+        bump = giver_position.bump
+    )]
+    pub giver_position: Account<'info, Position>,
+    
+    #[account(
+        init_if_needed,
+        payer = giver,
+        space = Position::SIZE,
+        seeds = [b"position", launch.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub recipient_position: Account<'info, Position>,
+    
+    #[account(
+        mut,
+        seeds = [b"contribution", launch.key().as_ref(), giver.key().as_ref()],
+        bump = giver_contribution.bump
+    )]
+    pub giver_contribution: Account<'info, Contribution>,
+    
+    #[account(
+        init_if_needed,
+        payer = giver,
+        space = Contribution::SIZE,
+        seeds = [b"contribution", launch.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub recipient_contribution: Account<'info, Contribution>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRecovery<'info> {
+    pub holder: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", position.launch.as_ref(), holder.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+}
+
+#[derive(Accounts)]
+pub struct RecoverPosition<'info> {
+    #[account(mut)]
+    pub recovery: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = recovery,
+        seeds = [b"position", old_position.launch.as_ref(), old_position.holder.as_ref()],
+        bump = old_position.bump,
+        constraint = old_position.recovery == recovery.key()
+    )]
+    pub old_position: Account<'info, Position>,
+    
+    /// CHECK: Wallet the position moves to
+    pub new_wallet: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = recovery,
+        space = Position::SIZE,
+        seeds = [b"position", old_position.launch.as_ref(), new_wallet.key().as_ref()],
+        bump
+    )]
+    pub new_position: Account<'info, Position>,
+    
+    #[account(
+        mut,
+        close = recovery,
+        seeds = [b"contribution", old_position.launch.as_ref(), old_position.holder.as_ref()],
+        bump = old_contribution.bump
+    )]
+    pub old_contribution: Account<'info, Contribution>,
+    
+    #[account(
+        init,
+        payer = recovery,
+        space = Contribution::SIZE,
+        seeds = [b"contribution", old_position.launch.as_ref(), new_wallet.key().as_ref()],
+        bump
+    )]
+    pub new_contribution: Account<'info, Contribution>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LinkWallet<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub wallet: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = HolderProfile::SIZE,
+        seeds = [b"holder_profile", owner.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, HolderProfile>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = WalletLink::SIZE,
+        seeds = [b"wallet_link", owner.key().as_ref()],
+        bump
+    )]
+    pub owner_link: Account<'info, WalletLink>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = WalletLink::SIZE,
+        seeds = [b"wallet_link", wallet.key().as_ref()],
+        bump
+    )]
+    pub wallet_link: Account<'info, WalletLink>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnlinkWallet<'info> {
+    /// The profile owner or the wallet being unlinked
+    pub signer: Signer<'info>,
+    
+    #[account(mut, seeds = [b"holder_profile", profile.owner.as_ref()], bump = profile.bump)]
+    pub profile: Account<'info, HolderProfile>,
+    
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"wallet_link", wallet_link.wallet.as_ref()],
+        bump = wallet_link.bump,
+        constraint = wallet_link.profile == profile.key() @ DiamondPadError::WalletNotLinked
+    )]
+    pub wallet_link: Account<'info, WalletLink>,
+    
+    /// Paid for the link, so gets its rent back
+    #[account(mut, address = profile.owner)]
+    pub owner: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateHolderProfile<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = HolderProfile::SIZE,
+        seeds = [b"holder_profile", owner.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, HolderProfile>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = WalletLink::SIZE,
+        seeds = [b"wallet_link", owner.key().as_ref()],
+        bump
+    )]
+    pub owner_link: Account<'info, WalletLink>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnlockAchievement<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(mut, seeds = [b"holder_profile", owner.key().as_ref()], bump = profile.bump)]
+    pub profile: Account<'info, HolderProfile>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct RegisterUsername<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(mut, seeds = [b"holder_profile", owner.key().as_ref()], bump = profile.bump)]
+    pub profile: Account<'info, HolderProfile>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = Username::SIZE,
+        seeds = [b"username", name.as_bytes()],
+        bump
+    )]
+    pub username: Account<'info, Username>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseUsername<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(mut, seeds = [b"holder_profile", owner.key().as_ref()], bump = profile.bump)]
+    pub profile: Account<'info, HolderProfile>,
+    
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"username", username.name.as_bytes()],
+        bump = username.bump,
+        constraint = username.profile == profile.key() @ DiamondPadError::Unauthorized
+    )]
+    pub username: Account<'info, Username>,
+}
+
+#[derive(Accounts)]
+pub struct TransferUsername<'info> {
+    pub owner: Signer<'info>,
+    
+    pub new_owner: Signer<'info>,
+    
+    #[account(mut, seeds = [b"holder_profile", owner.key().as_ref()], bump = profile.bump)]
+    pub profile: Account<'info, HolderProfile>,
+    
+    #[account(
+        mut,
+        seeds = [b"holder_profile", new_owner.key().as_ref()],
+        bump = new_profile.bump
+    )]
+    pub new_profile: Account<'info, HolderProfile>,
+    
+    #[account(
+        mut,
+        seeds = [b"username", username.name.as_bytes()],
+        bump = username.bump,
+        constraint = username.profile == profile.key() @ DiamondPadError::Unauthorized
+    )]
+    pub username: Account<'info, Username>,
+}
+
+#[derive(Accounts)]
+pub struct SyncHolderStats<'info> {
+    #[account(mut)]
+    pub profile: Account<'info, HolderProfile>,
+    
+    #[account(mut, constraint = launch.key() == position.launch)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut)]
+    pub position: Account<'info, Position>,
+}
+
+#[derive(Accounts)]
+pub struct SyncLinkedRank<'info> {
+    pub profile: Account<'info, HolderProfile>,
+    
+    #[account(mut, constraint = launch.key() == position.launch)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut)]
+    pub position: Account<'info, Position>,
+    
+    #[account(constraint = source_position.launch == position.launch)]
+    pub source_position: Account<'info, Position>,
+}
+
+#[derive(Accounts)]
+pub struct Buy<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = Position::SIZE,
+        seeds = [b"position", launch.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = Contribution::SIZE,
+        seeds = [b"contribution", launch.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+    
+    #[account(mut, seeds = [b"sol_vault", launch.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"reward_vault", launch.key().as_ref()], bump)]
+    pub reward_vault: SystemAccount<'info>,
+    
+    #[account(mut, constraint = treasury.key() == protocol.treasury)]
+    pub treasury: SystemAccount<'info>,
+    
+    /// CHECK: Civic gateway token, verified in the handler when the launch requires one
+    pub gateway_token: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Buyer's WalletRisk PDA, which may not exist; read in the handler
+    #[account(seeds = [b"wallet_risk", buyer.key().as_ref()], bump)]
+    pub wallet_risk: Option<UncheckedAccount<'info>>,
+    
+    pub referral_code: Option<Account<'info, ReferralCode>>,
+    
+    /// Stats for the code the position is attributed to, if tracked; needs
+    /// `referral_board` alongside
+    #[account(mut, constraint = referral_stats.launch == launch.key())]
+    pub referral_stats: Option<Box<Account<'info, ReferralStats>>>,
+    
+    #[account(mut, seeds = [b"referral_board", launch.key().as_ref()], bump = referral_board.bump)]
+    pub referral_board: Option<Box<Account<'info, ReferralBoard>>>,
+    
+    #[account(
+        mut,
+        seeds = [b"season", protocol.current_season.to_le_bytes().as_ref()],
+        bump = season.bump
+    )]
+    pub season: Option<Account<'info, Season>>,
+    
+    /// Buyer's score for the current season, if they joined it
+    #[account(mut, constraint = season_score.wallet == buyer.key())]
+    pub season_score: Option<Account<'info, SeasonScore>>,
+    
+    /// A graduated launch the buyer held on, proving their rank: Gold or
+    /// better is required during the launch's priority access window, and
+    /// any rank earns its loyalty fee discount
+    pub priority_launch: Option<Box<Account<'info, Launch>>>,
+    
+    pub priority_position: Option<Account<'info, Position>>,
+    
+    /// Buyer's profile, when the rank-proving position is held by a linked
+    /// wallet
+    #[account(
+        seeds = [b"holder_profile", holder_profile.owner.as_ref()],
+        bump = holder_profile.bump
+    )]
+    pub holder_profile: Option<Account<'info, HolderProfile>>,
+    
+    /// The launch's partner channel, required when it has one
+    #[account(mut, address = launch.channel @ DiamondPadError::ChannelRequired)]
+    pub channel: Option<Box<Account<'info, Channel>>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Sell<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", launch.key().as_ref(), seller.key().as_ref()],
+        bump = position.bump,
+        constraint = position.holder == seller.key()
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(
+        mut,
+        seeds = [b"contribution", launch.key().as_ref(), seller.key().as_ref()],
+        bump = contribution.bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+    
+    #[account(mut, seeds = [b"sol_vault", launch.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"reward_vault", launch.key().as_ref()], bump)]
+    pub reward_vault: SystemAccount<'info>,
+    
+    #[account(mut, constraint = treasury.key() == protocol.treasury)]
+    pub treasury: SystemAccount<'info>,
+    
+    /// CHECK: Instructions sysvar, read to refuse sells alongside a buy
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    
+    /// The launch's partner channel, required when it has one
+    #[account(mut, address = launch.channel @ DiamondPadError::ChannelRequired)]
+    pub channel: Option<Account<'info, Channel>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", launch.key().as_ref(), holder.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(
+        seeds = [b"lp_position", launch.key().as_ref(), holder.key().as_ref()],
+        bump = lp_position.bump
+    )]
+    pub lp_position: Option<Account<'info, LiquidityPosition>>,
+    
+    #[account(constraint = referral_code.owner == holder.key())]
+    pub referral_code: Option<Account<'info, ReferralCode>>,
+    
+    /// The holder's referral code's stats on this launch, for its boost
+    #[account(
+        seeds = [b"referral_stats", launch.key().as_ref(), referral_stats.referral_code.as_ref()],
+        bump = referral_stats.bump
+    )]
+    pub referral_stats: Option<Account<'info, ReferralStats>>,
+    
+    #[account(mut, seeds = [b"reward_vault", launch.key().as_ref()], bump)]
+    pub reward_vault: SystemAccount<'info>,
+    
+    /// Payout wallet: the holder, or a wallet linked to them
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"sol_vault", launch.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    
+    /// Holder's contribution record, needed for token-denominated claims
+    #[account(
+        mut,
+        seeds = [b"contribution", launch.key().as_ref(), holder.key().as_ref()],
+        bump = contribution.bump
+    )]
+    pub contribution: Option<Account<'info, Contribution>>,
+    
+    /// Holder's profile, needed when paying out to a linked wallet; its
+    /// lifetime stats are updated when passed
+    #[account(mut)]
+    pub holder_profile: Option<Account<'info, HolderProfile>>,
+    
+    /// CHECK: Bundler PDA for the holder; may not exist if they were never flagged
+    #[account(seeds = [b"bundler", holder.key().as_ref()], bump)]
+    pub bundler: UncheckedAccount<'info>,
+    
+    /// Needed only when the holder is a flagged bundler, so other claims
+    /// don't all write-lock one global account
+    #[account(mut, seeds = [b"bundler_stats"], bump)]
+    pub bundler_stats: Option<AccountLoader<'info, BundlerStats>>,
+    
+    /// CHECK: Instructions sysvar, read to refuse claims alongside a buy
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    
+    /// Launch's gas pool; pass it to have the claim's fee refunded
+    #[account(mut, seeds = [b"gas_pool", launch.key().as_ref()], bump)]
+    pub gas_pool: Option<SystemAccount<'info>>,
+    
+    /// Needed for token-denominated claims, which pay the buy fee
+    #[account(mut, constraint = treasury.key() == protocol.treasury)]
+    pub treasury: Option<SystemAccount<'info>>,
+    
+    /// The launch's partner channel, required for token-denominated claims
+    /// when it has one
+    #[account(mut, address = launch.channel @ DiamondPadError::ChannelRequired)]
+    pub channel: Option<Box<Account<'info, Channel>>>,
+    
+    /// CHECK: Civic gateway token, verified when a token-denominated claim needs one
+    pub gateway_token: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Holder's WalletRisk PDA, which may not exist; read in the handler
+    #[account(seeds = [b"wallet_risk", holder.key().as_ref()], bump)]
+    pub wallet_risk: Option<UncheckedAccount<'info>>,
+    
+    /// A graduated launch the holder held on, proving their rank for a
+    /// token-denominated claim as it would for `buy`
+    pub priority_launch: Option<Box<Account<'info, Launch>>>,
+    
+    pub priority_position: Option<Account<'info, Position>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundGasPool<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut, seeds = [b"gas_pool", launch.key().as_ref()], bump)]
+    pub gas_pool: SystemAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewApr<'info> {
+    pub launch: Account<'info, Launch>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAndSwap<'info> {
+    pub claim: ClaimRewards<'info>,
+    
+    #[account(mut, constraint = output_token_account.owner == claim.holder.key())]
+    pub output_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Jupiter aggregator program
+    #[account(address = jupiter::jupiter_program::ID)]
+    pub jupiter_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QualifyReferral<'info> {
+    #[account(mut)]
+    pub position: Account<'info, Position>,
+    
+    #[account(address = position.launch)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut, constraint = referral_code.key() == position.referral_code)]
+    pub referral_code: Account<'info, ReferralCode>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral_stats", launch.key().as_ref(), referral_code.key().as_ref()],
+        bump = referral_stats.bump
+    )]
+    pub referral_stats: Account<'info, ReferralStats>,
+}
+
+#[derive(Accounts)]
+pub struct GraduateLaunch<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    pub lp_mint: Account<'info, Mint>,
+    
+    #[account(mut, seeds = [b"sol_vault", launch.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"reward_vault", launch.key().as_ref()], bump)]
+    pub reward_vault: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"creator_checklist", launch.key().as_ref()], bump = checklist.bump)]
+    pub checklist: Option<Account<'info, CreatorChecklist>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitGraduationAirdrop<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut, constraint = launch.status == LaunchStatus::Graduated)]
+    pub launch: Account<'info, Launch>,
+}
+
+#[derive(Accounts)]
+#[instruction(quest_id: u32)]
+pub struct CreateQuest<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = Quest::SIZE,
+        seeds = [b"quest", launch.key().as_ref(), quest_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub quest: Account<'info, Quest>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteQuest<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    
+    #[account(address = quest.launch)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        seeds = [b"position", launch.key().as_ref(), holder.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(mut)]
+    pub quest: Account<'info, Quest>,
+    
+    #[account(
+        init,
+        payer = holder,
+        space = QuestCompletion::SIZE,
+        seeds = [b"quest_completion", quest.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub completion: Account<'info, QuestCompletion>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseQuest<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(mut, close = creator, has_one = creator)]
+    pub quest: Account<'info, Quest>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimGraduationAirdrop<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", launch.key().as_ref(), holder.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(
+        init,
+        payer = holder,
+        space = AirdropClaim::SIZE,
+        seeds = [b"airdrop_claim", launch.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub airdrop_claim: Account<'info, AirdropClaim>,
+    
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = Contribution::SIZE,
+        seeds = [b"contribution", launch.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SeedPositions<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = launch.creator == creator.key()
+    )]
+    pub launch: Account<'info, Launch>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ImportPosition<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = Position::SIZE,
+        seeds = [b"position", launch.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(
+        init,
+        payer = holder,
+        space = PositionImport::SIZE,
+        seeds = [b"position_import", launch.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub position_import: Account<'info, PositionImport>,
+    
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = Contribution::SIZE,
+        seeds = [b"contribution", launch.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitLoyaltySnapshot<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(mut, constraint = launch.creator == creator.key())]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        constraint = source_launch.key() != launch.key()
+            && source_launch.creator == creator.key()
+            && source_launch.status == LaunchStatus::Graduated
+            @ DiamondPadError::InvalidLoyaltySource
+    )]
+    pub source_launch: Account<'info, Launch>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimLoyaltyAllocation<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = Position::SIZE,
+        seeds = [b"position", launch.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(
+        init,
+        payer = holder,
+        space = AirdropClaim::SIZE,
+        seeds = [b"loyalty_claim", launch.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub loyalty_claim: Account<'info, AirdropClaim>,
+    
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = Contribution::SIZE,
+        seeds = [b"contribution", launch.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+    
+    #[account(address = launch.loyalty_source @ DiamondPadError::InvalidLoyaltySource)]
+    pub source_launch: Account<'info, Launch>,
+    
+    /// The holder's position in the source launch
+    #[account(
+        seeds = [b"position", source_launch.key().as_ref(), holder.key().as_ref()],
+        bump = source_position.bump
+    )]
+    pub source_position: Account<'info, Position>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositLp<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(constraint = launch.status == LaunchStatus::Graduated)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(address = launch.lp_mint)]
+    pub lp_mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = provider,
+        space = LiquidityPosition::SIZE,
+        seeds = [b"lp_position", launch.key().as_ref(), provider.key().as_ref()],
+        bump
+    )]
+    pub lp_position: Account<'info, LiquidityPosition>,
+    
+    #[account(mut, token::mint = lp_mint, token::authority = provider)]
+    pub provider_lp_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = provider,
+        token::mint = lp_mint,
+        token::authority = protocol,
+        seeds = [b"lp_vault", launch.key().as_ref()],
+        bump
+    )]
+    pub lp_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawLp<'info> {
+    pub provider: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"lp_position", launch.key().as_ref(), provider.key().as_ref()],
+        bump = lp_position.bump
+    )]
+    pub lp_position: Account<'info, LiquidityPosition>,
+    
+    /// CHECK: the provider's holder position, which may not exist; its LP
+    /// boost is resynced if it does
+    #[account(mut, seeds = [b"position", launch.key().as_ref(), provider.key().as_ref()], bump)]
+    pub position: UncheckedAccount<'info>,
+    
+    #[account(mut, token::mint = launch.lp_mint)]
+    pub provider_lp_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, seeds = [b"lp_vault", launch.key().as_ref()], bump)]
+    pub lp_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FailLaunch<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendSale<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        mut,
+        constraint = launch.creator == creator.key()
+    )]
+    pub launch: Account<'info, Launch>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireLaunch<'info> {
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+}
+
+#[derive(Accounts)]
+#[instruction(key: Pubkey)]
+pub struct AddTradeReporter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = TradeReporter::SIZE,
+        seeds = [b"trade_reporter", key.as_ref()],
+        bump
+    )]
+    pub reporter_registration: Account<'info, TradeReporter>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveTradeReporter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"trade_reporter", reporter_registration.key.as_ref()],
+        bump = reporter_registration.bump
+    )]
+    pub reporter_registration: Account<'info, TradeReporter>,
+}
+
+#[derive(Accounts)]
+pub struct ReportTrade<'info> {
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"trade_reporter", reporter.key().as_ref()],
+        bump = reporter_registration.bump
+    )]
+    pub reporter_registration: Account<'info, TradeReporter>,
+    
+    /// CHECK: Wallet that traded on the DEX
+    pub holder: UncheckedAccount<'info>,
+    
+    #[account(mut, constraint = launch.status == LaunchStatus::Graduated)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init_if_needed,
+        payer = reporter,
+        space = Position::SIZE,
+        seeds = [b"position", launch.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"contribution", launch.key().as_ref(), contributor.key().as_ref()],
+        bump = contribution.bump,
+        constraint = contribution.contributor == contributor.key()
+    )]
+    pub contribution: Account<'info, Contribution>,
+    
+    #[account(mut, seeds = [b"sol_vault", launch.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundInsurance<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = InsuranceVault::SIZE,
+        seeds = [b"insurance_vault", launch.key().as_ref()],
+        bump
+    )]
+    pub insurance_vault: Account<'info, InsuranceVault>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenInsuranceClaims<'info> {
+    #[account(mut)]
+    pub adjudicator: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.adjudicator == adjudicator.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(constraint = launch.rug_flagged @ DiamondPadError::LaunchNotRugFlagged)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init_if_needed,
+        payer = adjudicator,
+        space = InsuranceVault::SIZE,
+        seeds = [b"insurance_vault", launch.key().as_ref()],
+        bump
+    )]
+    pub insurance_vault: Account<'info, InsuranceVault>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FileInsuranceClaim<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        seeds = [b"position", launch.key().as_ref(), holder.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(
+        mut,
+        seeds = [b"insurance_vault", launch.key().as_ref()],
+        bump = insurance_vault.bump
+    )]
+    pub insurance_vault: Account<'info, InsuranceVault>,
+    
+    #[account(
+        init,
+        payer = holder,
+        space = InsuranceClaim::SIZE,
+        seeds = [b"insurance_claim", launch.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, InsuranceClaim>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecalculateRank<'info> {
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+}
+
+#[derive(Accounts)]
+pub struct AuditLaunch<'info> {
+    pub launch: Account<'info, Launch>,
+    
+    #[account(seeds = [b"sol_vault", launch.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(seeds = [b"reward_vault", launch.key().as_ref()], bump)]
+    pub reward_vault: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdjudicateInsuranceClaims<'info> {
+    pub adjudicator: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.adjudicator == adjudicator.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut)]
+    pub insurance_vault: Account<'info, InsuranceVault>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimInsurancePayout<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    
+    #[account(mut)]
+    pub insurance_vault: Account<'info, InsuranceVault>,
+    
+    #[account(
+        mut,
+        seeds = [b"insurance_claim", insurance_vault.launch.as_ref(), holder.key().as_ref()],
+        bump = claim.bump
+    )]
+    pub claim: Account<'info, InsuranceClaim>,
+    
+    #[account(address = insurance_vault.launch)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        seeds = [b"position", launch.key().as_ref(), holder.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    /// Required, with a majority of member approvals, for large payouts
+    #[account(seeds = [b"council"], bump = council.bump)]
+    pub council: Option<Account<'info, Council>>,
+    
+    /// Slashed creator DPAD; required once the vault holds any
+    #[account(mut, seeds = [b"insurance_tokens", insurance_vault.launch.as_ref()], bump)]
+    pub insurance_token_vault: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut, constraint = holder_token_account.owner == holder.key())]
+    pub holder_token_account: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AppointCouncilMember<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Council::SIZE,
+        seeds = [b"council"],
+        bump
+    )]
+    pub council: Account<'info, Council>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = CouncilMember::SIZE,
+        seeds = [b"council_member", wallet.as_ref()],
+        bump
+    )]
+    pub member: Account<'info, CouncilMember>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct ReplaceCouncilMember<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(seeds = [b"council"], bump = council.bump)]
+    pub council: Account<'info, Council>,
+    
+    #[account(mut, close = authority)]
+    pub previous_member: Account<'info, CouncilMember>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = CouncilMember::SIZE,
+        seeds = [b"council_member", wallet.as_ref()],
+        bump
+    )]
+    pub member: Account<'info, CouncilMember>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u32)]
+pub struct CreateLaunchProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        seeds = [b"position", launch.key().as_ref(), proposer.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(
+        init,
+        payer = proposer,
+        space = LaunchProposal::SIZE,
+        seeds = [b"launch_proposal", launch.key().as_ref(), proposal_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, LaunchProposal>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastLaunchVote<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut, has_one = launch)]
+    pub proposal: Account<'info, LaunchProposal>,
+    
+    #[account(
+        seeds = [b"position", launch.key().as_ref(), position.holder.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    /// CHECK: the holder's VoteDelegation PDA, which may not exist; read in the handler
+    #[account(
+        seeds = [b"vote_delegation", launch.key().as_ref(), position.holder.as_ref()],
+        bump
+    )]
+    pub delegation: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = voter,
+        space = LaunchVote::SIZE,
+        seeds = [b"launch_vote", proposal.key().as_ref(), position.holder.as_ref()],
+        bump
+    )]
+    pub vote: Account<'info, LaunchVote>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteLaunchProposal<'info> {
+    #[account(mut)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut, has_one = launch)]
+    pub proposal: Account<'info, LaunchProposal>,
+    
+    /// CHECK: the launch's Token-2022 mint, needed to execute a
+    /// `SetInterestRate` action; the token program validates it
+    #[account(mut, address = launch.mint)]
+    pub launch_mint: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: PDA holding the mint's rate authority
+    #[account(seeds = [b"launch_authority", launch.key().as_ref()], bump)]
+    pub launch_authority: Option<UncheckedAccount<'info>>,
+    
+    pub token_program: Option<Program<'info, Token2022>>,
+}
+
+#[derive(Accounts)]
+pub struct DelegateVotes<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = VoteDelegation::SIZE,
+        seeds = [b"vote_delegation", launch.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, VoteDelegation>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVoteDelegation<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = holder,
+        seeds = [b"vote_delegation", delegation.launch.as_ref(), holder.key().as_ref()],
+        bump = delegation.bump
+    )]
+    pub delegation: Account<'info, VoteDelegation>,
+}
+
+#[derive(Accounts)]
+pub struct SettleListingDeposit<'info> {
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(mut, has_one = creator)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut)]
+    pub creator: SystemAccount<'info>,
+    
+    #[account(mut, constraint = treasury.key() == protocol.treasury)]
+    pub treasury: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SweepUnclaimed<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    #[account(mut, seeds = [b"sol_vault", launch.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(mut, constraint = treasury.key() == protocol.treasury)]
+    pub treasury: SystemAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTokens<'info> {
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = launch.status == LaunchStatus::Graduated,
+        constraint = launch.mint != Pubkey::default() @ DiamondPadError::LaunchMintRequired
+    )]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"contribution", launch.key().as_ref(), contributor.key().as_ref()],
+        bump = contribution.bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", launch.key().as_ref(), contributor.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    
+    #[account(mut, address = launch.mint)]
+    pub launch_mint: InterfaceAccount<'info, token_interface::Mint>,
+    
+    /// CHECK: PDA holding the mint authority
+    #[account(seeds = [b"launch_authority", launch.key().as_ref()], bump)]
+    pub launch_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        associated_token::mint = launch_mint,
+        associated_token::authority = contributor,
+        associated_token::token_program = token_program
+    )]
+    pub contributor_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    
+    pub token_program: Program<'info, Token2022>,
+    
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepUnclaimedAllocation<'info> {
+    #[account(
+        mut,
+        constraint = launch.status == LaunchStatus::Graduated,
+        constraint = launch.mint != Pubkey::default() @ DiamondPadError::LaunchMintRequired
+    )]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"contribution", launch.key().as_ref(), contribution.contributor.as_ref()],
+        bump = contribution.bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+}
+
+#[derive(Accounts)]
+#[instruction(key: Pubkey)]
+pub struct AddFlagger<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = Flagger::SIZE,
+        seeds = [b"flagger", key.as_ref()],
+        bump
+    )]
+    pub flagger_registration: Account<'info, Flagger>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFlagger<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"flagger", flagger_registration.key.as_ref()],
+        bump = flagger_registration.bump
+    )]
+    pub flagger_registration: Account<'info, Flagger>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeFlag<'info> {
+    #[account(mut)]
+    pub flagger: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    /// CHECK: Wallet being flagged
+    pub flagged_wallet: UncheckedAccount<'info>,
+    
+    #[account(seeds = [b"flagger", flagger.key().as_ref()], bump = flagger_registration.bump)]
+    pub flagger_registration: Option<Account<'info, Flagger>>,
+    
+    /// Any of the proposer's per-launch moderator assignments
+    #[account(constraint = mod_assignment.moderator == flagger.key() @ DiamondPadError::NotAFlagger)]
+    pub mod_assignment: Option<Account<'info, ModAssignment>>,
+    
+    #[account(
+        init,
+        payer = flagger,
+        space = PendingFlag::SIZE,
+        seeds = [b"pending_flag", flagged_wallet.key().as_ref()],
+        bump
+    )]
+    pub pending_flag: Account<'info, PendingFlag>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CosignFlag<'info> {
+    pub flagger: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(seeds = [b"flagger", flagger.key().as_ref()], bump = flagger_registration.bump)]
+    pub flagger_registration: Option<Account<'info, Flagger>>,
+    
+    #[account(
+        mut,
+        seeds = [b"pending_flag", pending_flag.wallet.as_ref()],
+        bump = pending_flag.bump
+    )]
+    pub pending_flag: Account<'info, PendingFlag>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey, evidence_hash: [u8; 32], evidence_uri: String, reported_at: i64)]
+pub struct SubmitDetectionReport<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = PendingFlag::SIZE,
+        seeds = [b"pending_flag", wallet.as_ref()],
+        bump
+    )]
+    pub pending_flag: Account<'info, PendingFlag>,
+    
+    /// Fails to initialize if this exact report was already relayed
+    #[account(
+        init,
+        payer = relayer,
+        space = DetectionReportReceipt::SIZE,
+        seeds = [
+            b"detection_report",
+            detection::report_hash(&wallet, &evidence_hash, &evidence_uri, reported_at).as_ref()
+        ],
+        bump
+    )]
+    pub report_receipt: Account<'info, DetectionReportReceipt>,
+    
+    /// CHECK: Instructions sysvar, read for the preceding Ed25519 check
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct CheckBundler<'info> {
+    /// CHECK: Bundler PDA for `wallet`; may not exist if the wallet was never flagged
+    #[account(seeds = [b"bundler", wallet.as_ref()], bump)]
+    pub bundler: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddEvidence<'info> {
+    pub flagger: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(seeds = [b"flagger", flagger.key().as_ref()], bump = flagger_registration.bump)]
+    pub flagger_registration: Option<Account<'info, Flagger>>,
+    
+    #[account(mut, seeds = [b"bundler", bundler.wallet.as_ref()], bump = bundler.bump)]
+    pub bundler: Account<'info, Bundler>,
+}
+
+#[derive(Accounts)]
+pub struct FlagBundler<'info> {
+    #[account(mut)]
+    pub flagger: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(seeds = [b"flagger", flagger.key().as_ref()], bump = flagger_registration.bump)]
+    pub flagger_registration: Option<Account<'info, Flagger>>,
+    
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"pending_flag", pending_flag.wallet.as_ref()],
+        bump = pending_flag.bump
+    )]
+    pub pending_flag: Account<'info, PendingFlag>,
+    
+    /// CHECK: Receives the pending flag's rent; checked against pending_flag.proposer
+    #[account(mut, constraint = proposer.key() == pending_flag.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = flagger,
+        space = Bundler::SIZE,
+        seeds = [b"bundler", pending_flag.wallet.as_ref()],
+        bump
+    )]
+    pub bundler: Account<'info, Bundler>,
+    
+    #[account(mut, seeds = [b"bundler_stats"], bump)]
+    pub bundler_stats: AccountLoader<'info, BundlerStats>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelFlag<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"pending_flag", pending_flag.wallet.as_ref()],
+        bump = pending_flag.bump
+    )]
+    pub pending_flag: Account<'info, PendingFlag>,
+    
+    /// CHECK: Receives the pending flag's rent; checked against pending_flag.proposer
+    #[account(mut, constraint = proposer.key() == pending_flag.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitBundlerStats<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = BundlerStats::SIZE,
+        seeds = [b"bundler_stats"],
+        bump
+    )]
+    pub bundler_stats: AccountLoader<'info, BundlerStats>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(code: String)]
+pub struct RegisterReferralCode<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ReferralCode::SIZE,
+        seeds = [b"referral", code.as_bytes()],
+        bump
+    )]
+    pub referral_code: Account<'info, ReferralCode>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenReferralBoard<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = ReferralBoard::SIZE,
+        seeds = [b"referral_board", launch.key().as_ref()],
+        bump
+    )]
+    pub referral_board: Account<'info, ReferralBoard>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenReferralStats<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub launch: Account<'info, Launch>,
+    
+    pub referral_code: Account<'info, ReferralCode>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = ReferralStats::SIZE,
+        seeds = [b"referral_stats", launch.key().as_ref(), referral_code.key().as_ref()],
+        bump
+    )]
+    pub referral_stats: Account<'info, ReferralStats>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SyncReferralBoard<'info> {
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        seeds = [b"referral_stats", launch.key().as_ref(), referral_stats.referral_code.as_ref()],
+        bump = referral_stats.bump
+    )]
+    pub referral_stats: Account<'info, ReferralStats>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral_board", launch.key().as_ref()],
+        bump = referral_board.bump
+    )]
+    pub referral_board: Account<'info, ReferralBoard>,
+}
+
+#[derive(Accounts)]
+pub struct FundReferralBonus<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(constraint = launch.creator == creator.key())]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral_board", launch.key().as_ref()],
+        bump = referral_board.bump
+    )]
+    pub referral_board: Account<'info, ReferralBoard>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralBonus<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(constraint = launch.status == LaunchStatus::Graduated)]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(constraint = referral_code.owner == owner.key())]
+    pub referral_code: Account<'info, ReferralCode>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral_stats", launch.key().as_ref(), referral_code.key().as_ref()],
+        bump = referral_stats.bump
+    )]
+    pub referral_stats: Account<'info, ReferralStats>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral_board", launch.key().as_ref()],
+        bump = referral_board.bump
+    )]
+    pub referral_board: Account<'info, ReferralBoard>,
+    
+    #[account(mut, address = referral_code.payout_wallet)]
+    pub payout_wallet: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimReferralBonus<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(constraint = launch.creator == creator.key())]
+    pub launch: Account<'info, Launch>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral_board", launch.key().as_ref()],
+        bump = referral_board.bump
+    )]
+    pub referral_board: Account<'info, ReferralBoard>,
+}
+
+#[derive(Accounts)]
+pub struct StartSeason<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        constraint = protocol.authority == authority.key()
+    )]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = Season::SIZE,
+        seeds = [b"season", (protocol.current_season + 1).to_le_bytes().as_ref()],
+        bump
+    )]
+    pub season: Account<'info, Season>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinSeason<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    
+    #[account(seeds = [b"protocol"], bump = protocol.bump)]
+    pub protocol: Account<'info, Protocol>,
+    
+    #[account(
+        mut,
+        seeds = [b"season", protocol.current_season.to_le_bytes().as_ref()],
+        bump = season.bump
+    )]
+    pub season: Account<'info, Season>,
+    
+    #[account(
+        init,
+        payer = wallet,
+        space = SeasonScore::SIZE,
+        seeds = [b"season_score", season.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub season_score: Account<'info, SeasonScore>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+// ============ State Accounts ============
+
+#[account]
+#[derive(InitSpace)]
+pub struct Protocol {
+    pub authority: Pubkey,
+    pub moderator: Pubkey,
+    pub verifier: Pubkey,
+    pub reviewer: Pubkey,
+    /// Off-chain detection service whose signed reports count as flag approvals
+    pub detector: Pubkey,
+    pub risk_oracle: Pubkey,
+    /// Decides insurance claims against rug-flagged launches
+    pub adjudicator: Pubkey,
+    pub treasury: Pubkey,
+    pub launch_token_mint: Pubkey,
+    /// Next id for launches numbered by the protocol counter. Nonce
+    /// launches take ids from `LaunchIdShard`s instead.
+    pub total_launches: u64,
+    pub total_stakers: u64,
+    pub total_staked: u64,
+    /// No longer updated; `BundlerStats::total_flags` is the live count
+    pub total_bundlers_caught: u64,
+    pub early_unstake_penalty_bps: u16,
+    pub trade_fee_bps: u16,
+    /// New launches need reviewer approval before they can trade
+    pub curated_mode: bool,
+    /// Distinct flagger approvals needed to enforce a bundler flag
+    pub flag_threshold: u8,
+    /// Most days a creator can add to their sale deadline
+    pub max_sale_extension_days: u16,
+    /// Upgrade authority was found revoked at the last verification
+    pub program_immutable: bool,
+    pub program_verified_at: i64,
+    /// Latest season started (0 = none yet) and when it ends
+    pub current_season: u32,
+    pub season_ends_at: i64,
+    /// Lamports the emissions vault pays out across each gauge epoch
+    pub gauge_emissions_per_epoch: u64,
+    /// Buys, sells and recorded trades allowed per position per minute (0 = no limit)
+    pub max_position_actions_per_minute: u16,
+    /// Buy fee discount by the rank the buyer proves on another graduated
+    /// launch, Paper to Diamond
+    pub loyalty_discount_bps: [u16; 6],
+    /// Lamports each creator escrows per launch against spam listings
+    pub listing_deposit: u64,
+    /// Share of the deposit refunded when a launch with buyers fails
+    pub listing_deposit_failure_refund_bps: u16,
+    /// Legacy event shapes are emitted alongside current ones until then
+    pub legacy_events_until: i64,
+    /// FEATURE_* bits for subsystems switched off (0 = all on)
+    pub disabled_features: u32,
+    /// Bitset replacing `disabled_features` at `feature_flags_effective_at`
+    pub pending_disabled_features: u32,
+    /// When the pending bitset applies (0 = none scheduled)
+    pub feature_flags_effective_at: i64,
+    pub bump: u8,
+    /// Most reward lamports paid across all launches per UTC day (0 = uncapped)
+    pub daily_reward_cap: u64,
+    /// Positions below this many tokens can be garbage collected (0 = off)
+    pub gc_dust_threshold: u64,
+    /// Days without activity before a dust position can be collected
+    pub gc_inactivity_days: u16,
+    /// Share of a collected position's rent paid to the cranker
+    pub gc_cranker_share_bps: u16,
+}
+
+impl Protocol {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+
+    pub fn require_feature(&self, flag: u32) -> Result<()> {
+        require!(self.disabled_features & flag == 0, DiamondPadError::FeatureDisabled);
+        Ok(())
+    }
+
+    /// Protocol-wide proposal actions are only taken on the launch of the
+    /// protocol's own token, whose holders govern the protocol
+    pub fn governed_by(&self, launch: &Launch) -> bool {
+        launch.mint != Pubkey::default() && launch.mint == self.launch_token_mint
+    }
+
+    /// Hand out the next protocol-numbered launch id
+    pub fn take_launch_id(&mut self) -> u64 {
+        let launch_id = self.total_launches;
+        self.total_launches += 1;
+        launch_id
+    }
+}
+
+/// Headline protocol numbers, updated from the trade paths
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolStats {
+    pub total_buy_volume: u64,
+    pub total_sell_volume: u64,
+    pub total_fees_collected: u64,
+    /// Trade fees routed into launch reward vaults
+    pub total_rewards_funded: u64,
+    pub total_rewards_paid: u64,
+    /// Hourly buckets backing the rolling 24h figures
+    pub hourly_buy_volume: [u64; 24],
+    pub hourly_sell_volume: [u64; 24],
+    pub hourly_fees: [u64; 24],
+    pub last_trade_hour: i64,
+    pub bump: u8,
+    /// UTC day (unix time / 86400) `rewards_paid_today` counts
+    pub reward_day: i64,
+    pub rewards_paid_today: u64,
+}
+
+impl ProtocolStats {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+
+    pub fn record_trade(&mut self, side: TradeSide, volume: u64, fee: u64, reward_fee: u64, now: i64) {
+        self.roll_to(now);
+        let bucket = (now / 3600).rem_euclid(24) as usize;
+
+        match side {
+            TradeSide::Buy => {
+                self.total_buy_volume = self.total_buy_volume.saturating_add(volume);
+                self.hourly_buy_volume[bucket] = self.hourly_buy_volume[bucket].saturating_add(volume);
+            }
+            TradeSide::Sell => {
+                self.total_sell_volume = self.total_sell_volume.saturating_add(volume);
+                self.hourly_sell_volume[bucket] = self.hourly_sell_volume[bucket].saturating_add(volume);
+            }
+        }
+        self.total_fees_collected = self.total_fees_collected.saturating_add(fee);
+        self.hourly_fees[bucket] = self.hourly_fees[bucket].saturating_add(fee);
+        self.total_rewards_funded = self.total_rewards_funded.saturating_add(reward_fee);
+    }
+
+    pub fn record_rewards_paid(&mut self, amount: u64, now: i64) {
+        self.total_rewards_paid = self.total_rewards_paid.saturating_add(amount);
+        if now / 86400 != self.reward_day {
+            self.reward_day = now / 86400;
+            self.rewards_paid_today = 0;
+        }
+        self.rewards_paid_today = self.rewards_paid_today.saturating_add(amount);
+    }
+
+    /// What can still be paid out today under `cap` (0 = uncapped)
+    pub fn reward_cap_remaining(&self, cap: u64, now: i64) -> u64 {
+        match cap {
+            0 => u64::MAX,
+            cap if now / 86400 != self.reward_day => cap,
+            cap => cap.saturating_sub(self.rewards_paid_today),
+        }
+    }
+
+    /// Clear buckets for hours that passed without a trade
+    fn roll_to(&mut self, now: i64) {
+        let hour = now / 3600;
+        let elapsed = (hour - self.last_trade_hour).clamp(0, 24);
+        for offset in 1..=elapsed {
+            let bucket = (self.last_trade_hour + offset).rem_euclid(24) as usize;
+            self.hourly_buy_volume[bucket] = 0;
+            self.hourly_sell_volume[bucket] = 0;
+            self.hourly_fees[bucket] = 0;
+        }
+        self.last_trade_hour = self.last_trade_hour.max(hour);
+    }
+
+    pub fn buy_volume_24h(&self, now: i64) -> u64 {
+        self.window_sum(&self.hourly_buy_volume, now)
+    }
+
+    pub fn sell_volume_24h(&self, now: i64) -> u64 {
+        self.window_sum(&self.hourly_sell_volume, now)
+    }
+
+    pub fn fees_24h(&self, now: i64) -> u64 {
+        self.window_sum(&self.hourly_fees, now)
+    }
+
+    /// Sum the buckets still inside the last 24 hours as of `now`
+    fn window_sum(&self, buckets: &[u64; 24], now: i64) -> u64 {
+        let stale = (now / 3600 - self.last_trade_hour).clamp(0, 24);
+        (0..24 - stale)
+            .map(|age| buckets[(self.last_trade_hour - age).rem_euclid(24) as usize])
+            .fold(0u64, |sum, v| sum.saturating_add(v))
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakerAccount {
+    pub owner: Pubkey,
+    pub staked_amount: u64,
+    pub staked_at: i64,
+    pub lock_end_timestamp: i64,
+    pub tier: StakingTier,
+    pub strong_holder_score: u16,
+    pub total_allocations_received: u32,
+    pub last_update_timestamp: i64,
+    pub bump: u8,
+}
+
+impl StakerAccount {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Vote totals and emissions budget for one gauge epoch, fixed when the
+/// epoch's first vote lands
+#[account]
+#[derive(InitSpace)]
+pub struct GaugeEpoch {
+    pub epoch: u64,
+    pub total_votes: u64,
+    pub emissions: u64,
+    pub emissions_paid: u64,
+    pub bump: u8,
+}
+
+impl GaugeEpoch {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Stake voted toward one launch in one gauge epoch
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchGauge {
+    pub launch: Pubkey,
+    pub epoch: u64,
+    pub votes: u64,
+    pub distributed: bool,
+    pub emissions_received: u64,
+    pub bump: u8,
+}
+
+impl LaunchGauge {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// A staker's single gauge vote for an epoch
+#[account]
+#[derive(InitSpace)]
+pub struct GaugeVote {
+    pub voter: Pubkey,
+    pub epoch: u64,
+    pub launch: Pubkey,
+    pub weight: u64,
+    pub bump: u8,
+}
+
+impl GaugeVote {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Launch {
+    pub creator: Pubkey,
+    /// Creator plus declared team wallets, barred from buying the sale
+    #[max_len(MAX_INSIDERS)]
+    pub insiders: Vec<Pubkey>,
+    /// UTF-8, zero-padded; read through `name()` and `symbol()`
+    pub name: [u8; MAX_NAME_LEN],
+    pub symbol: [u8; MAX_SYMBOL_LEN],
+    pub total_supply: u64,
+    pub config: LaunchConfig,
+    pub template: Pubkey,
+    /// Position in the creator's own launch sequence, for launches created
+    /// at `["creator_launch", creator, nonce]` (0 otherwise)
+    pub creator_nonce: u64,
+    pub lp_unlock_timestamp: i64,
+    pub lp_burned: bool,
+    pub fee_rebate_bps: u16,
+    /// Holder rewards boost granted so far for extending or burning the LP lock
+    pub lock_bonus_bps: u16,
+    pub created_at: i64,
+    /// Deadline to reach the soft cap (0 = open-ended)
+    pub end_time: i64,
+    /// The creator's one deadline extension has been used
+    pub sale_extended: bool,
+    /// FREEZE_* bits for parameter groups that can never change again
+    pub frozen_params: u16,
+    /// Token-2022 mint created with the launch (default = none)
+    pub mint: Pubkey,
+    /// Withheld transfer fees swept into the reward vault's token account
+    pub transfer_fees_harvested: u64,
+    /// The mint has the interest-bearing extension
+    pub interest_bearing: bool,
+    /// A graduation freeze on the mint has been lifted for new accounts
+    pub transfers_thawed: bool,
+    /// 0-100 safety score as of the last `refresh_quality_score`
+    pub quality_score: u8,
+    pub quality_score_updated_at: i64,
+    pub launch_id: u64,
+    pub status: LaunchStatus,
+    pub total_raised: u64,
+    pub holder_count: u64,
+    /// Holders passing the launch's sybil filters, as of their last refresh
+    pub verified_holder_count: u64,
+    /// Days held to reach each DiamondRank, indexed by rank. All zero on
+    /// launches created before schedules existed; read it through
+    /// `rank_thresholds`.
+    pub rank_threshold_days: [u16; 6],
+    // Analytics
+    pub unique_buyers: u64,
+    pub total_buy_volume: u64,
+    pub total_sell_volume: u64,
+    pub peak_holder_count: u64,
+    pub rewards_distributed: u64,
+    // Creator-stake tier
+    pub tier: LaunchTier,
+    pub hard_cap: u64,
+    pub featured: bool,
+    pub rug_flagged: bool,
+    /// When the launch was ruled a rug (0 = not ruled)
+    pub rugged_at: i64,
+    /// Moderators marked the launch as spam; its listing deposit is forfeit
+    pub spam_flagged: bool,
+    /// Creator's listing deposit still escrowed on this account
+    pub listing_deposit: u64,
+    // Curated review
+    pub review_status: ReviewStatus,
+    pub reviewed_at: i64,
+    // Bonding curve
+    pub sale_supply: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub tokens_sold: u64,
+    pub price_history: PriceHistory,
+    // Trade fees routed to the holder reward vault
+    pub reward_vault_funded: u64,
+    /// Reward lamports per unit of reward weight, scaled by REWARD_INDEX_PRECISION
+    pub reward_index: u128,
+    pub total_reward_weight: u128,
+    /// Reward lamports waiting for the current epoch to close
+    pub undistributed_rewards: u64,
+    /// Lamports the gas pool refunds a holder per claim (0 = no sponsorship)
+    pub gas_refund_per_claim: u64,
+    /// Most one wallet is refunded per reward epoch
+    pub gas_refund_cap_per_epoch: u64,
+    pub gas_refunded: u64,
+    /// Epochs (REWARD_EPOCH_SECONDS each) since creation, as of the last roll
+    pub reward_epoch: u64,
+    pub last_epoch_emission: u64,
+    /// Weight gained this epoch, added to the total when it closes
+    pub pending_reward_weight: u128,
+    /// Reward index at the start of each recent epoch (ring by epoch number)
+    pub epoch_start_indices: [u128; REWARD_EPOCH_HISTORY],
+    // Refunds
+    pub refund_deadline: i64,
+    pub total_refunded: u64,
+    /// Unclaimed tokens written off after the claim window
+    pub allocations_swept: u64,
+    // Moderation
+    /// PAUSE_* bits for activity moderators have paused
+    pub paused: u8,
+    pub halt_reason: u8,
+    pub halted_at: i64,
+    pub claims_paused_at: i64,
+    pub circuit_breaker_until: i64,
+    // Graduation
+    pub graduated_at: i64,
+    /// Sale tokens unclaimed by this time can be swept to the reward vault
+    pub claim_deadline: i64,
+    pub lp_mint: Pubkey,
+    /// Launch tokens seeded into the DEX pool, used to value LP deposits
+    pub lp_pool_token_reserve: u64,
+    pub lp_supply_at_graduation: u64,
+    /// Supply set aside at graduation for the top-holder airdrop
+    pub airdrop_reserve: u64,
+    /// Raised SOL moved into the holder reward pot at graduation
+    pub graduation_reward_topup: u64,
+    /// Merkle root of `(holder, amount)` airdrop leaves (zero = not committed)
+    pub airdrop_root: [u8; 32],
+    pub airdrop_claimed: u64,
+    /// Merkle root of `(holder, balance, hold_start)` leaves imported from
+    /// the token this launch relaunches (zero = none)
+    pub import_root: [u8; 32],
+    /// Earlier graduated launch by the same creator whose holders the
+    /// loyalty snapshot covers (default = none)
+    pub loyalty_source: Pubkey,
+    /// Merkle root of `(holder, amount)` loyalty bonus leaves (zero = none)
+    pub loyalty_root: [u8; 32],
+    /// Total supply split into the buckets tokens can be issued from
+    pub supply: SupplyLedger,
+    /// Partner channel the launch was created through (default = none)
+    pub channel: Pubkey,
+    // Allocation pools
+    pub guaranteed_pool_bps: u16,
+    pub lottery_pool_bps: u16,
+    pub public_pool_bps: u16,
+    pub fcfs_pool_bps: u16,
+    pub flipper_pool_bps: u16,
+    pub liquidity_pool_bps: u16,
+    pub trader_rewards_pool_bps: u16,
+    pub bump: u8,
+}
+
+impl Launch {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+impl Launch {
+    pub fn require_unfrozen(&self, flag: u16) -> Result<()> {
+        require!(self.frozen_params & flag == 0, DiamondPadError::ParameterFrozen);
+        Ok(())
+    }
+
+    /// The supply ledger, reserved from the config if this launch predates
+    /// it and has never issued from it
+    pub fn supply_ledger(&self) -> SupplyLedger {
+        if self.supply.is_unset() {
+            SupplyLedger::reserve(self.total_supply, &self.config, self.liquidity_pool_bps)
+        } else {
+            self.supply
+        }
+    }
+
+    /// Hand back tokens issued from a bucket that will never be minted
+    pub fn release_supply(&mut self, bucket: SupplyBucket, amount: u64) {
+        self.supply = self.supply_ledger();
+        self.supply.release(bucket, amount);
+    }
+
+    /// Issue launch tokens from one supply bucket, enforcing the ledger's
+    /// invariants first
+    pub fn issue_supply(&mut self, bucket: SupplyBucket, amount: u64) -> Result<()> {
+        self.supply = self.supply_ledger();
+        self.supply.issue(bucket, amount, self.total_supply)
+    }
+
+    /// Checklist steps the launch shows as done: its mint (when passed)
+    /// carries a name and URI, the LP lock is in force, or there's no dev
+    /// allocation to vest
+    pub fn checklist_steps(&self, mint: Option<&AccountInfo>, now: i64) -> Result<u8> {
+        let mut steps = 0;
+        if let Some(mint) = mint {
+            if launch_mint::has_metadata(mint)? {
+                steps |= CHECKLIST_METADATA_SET;
+            }
+        }
+        if self.lp_lock_in_force(now) {
+            steps |= CHECKLIST_LP_LOCKED;
+        }
+        if self.config.dev_allocation_bps == 0 {
+            steps |= CHECKLIST_VESTING_FUNDED;
+        }
+        Ok(steps)
+    }
+
+    /// Whether the graduated LP is burned, or locked past `now`
+    pub fn lp_lock_in_force(&self, now: i64) -> bool {
+        self.lp_burned || (self.lp_mint != Pubkey::default() && self.lp_unlock_timestamp > now)
+    }
+
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.paused & flag != 0
+    }
+
+    /// Whether `now` falls in the post-graduation sell grace window
+    pub fn in_graduation_grace(&self, now: i64) -> bool {
+        self.status == LaunchStatus::Graduated
+            && now < self.graduated_at + self.config.graduation_grace_hours as i64 * 3600
+    }
+
+    /// The launch's rank schedule, or the default one if it never had one
+    /// (an all-zero schedule would rank every holder Diamond)
+    pub fn rank_thresholds(&self) -> [u16; 6] {
+        if self.rank_threshold_days == [0; 6] {
+            DiamondRank::DEFAULT_THRESHOLD_DAYS
+        } else {
+            self.rank_threshold_days
+        }
+    }
+
+    pub fn is_insider(&self, wallet: &Pubkey) -> bool {
+        *wallet == self.creator || self.insiders.contains(wallet)
+    }
+
+    /// Whether a balance clears the verified-holder minimum
+    pub fn holds_verified_balance(&self, balance: u64) -> bool {
+        balance > 0 && balance >= self.config.verified_min_balance
+    }
+
+    pub fn name(&self) -> String {
+        unpad_bytes(&self.name)
+    }
+
+    pub fn symbol(&self) -> String {
+        unpad_bytes(&self.symbol)
+    }
+
+    pub fn curve_state(&self) -> CurveState {
+        CurveState {
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves,
+            tokens_sold: self.tokens_sold,
+        }
+    }
+
+    /// Append the post-trade spot price to the TWAP history
+    pub fn record_price(&mut self, now: i64) {
+        if let Some(price) = self.config.curve.spot_price(&self.curve_state()) {
+            self.price_history.record(now, price);
+        }
+    }
+}
+
+/// A Launch from before launches carried a config, read by `upgrade_launch`
+#[derive(AnchorDeserialize)]
+pub struct LegacyLaunch {
+    pub creator: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub total_supply: u64,
+    pub dev_allocation_bps: u16,
+    pub dev_vesting_days: u16,
+    pub lp_lock_days: u16,
+    pub holder_rewards_bps: u16,
+    pub created_at: i64,
+    pub launch_id: u64,
+    pub status: LaunchStatus,
+    pub total_raised: u64,
+    pub holder_count: u64,
+    pub guaranteed_pool_bps: u16,
+    pub lottery_pool_bps: u16,
+    pub public_pool_bps: u16,
+    pub fcfs_pool_bps: u16,
+    pub flipper_pool_bps: u16,
+    pub liquidity_pool_bps: u16,
+    pub trader_rewards_pool_bps: u16,
+    pub bump: u8,
+}
+
+impl LegacyLaunch {
+    /// Copy the old fields onto a zeroed current-layout launch. Everything
+    /// else stays zero, which reads as predating that feature.
+    pub fn carry_over(self, launch: &mut Launch) {
+        launch.creator = self.creator;
+        launch.insiders = vec![self.creator];
+        launch.name = pad_bytes(&self.name);
+        launch.symbol = pad_bytes(&self.symbol);
+        launch.total_supply = self.total_supply;
+        launch.config.dev_allocation_bps = self.dev_allocation_bps;
+        launch.config.dev_vesting_days = self.dev_vesting_days;
+        launch.config.lp_lock_days = self.lp_lock_days;
+        launch.config.holder_rewards_bps = self.holder_rewards_bps;
+        launch.created_at = self.created_at;
+        launch.lp_unlock_timestamp = self.created_at + self.lp_lock_days as i64 * 86400;
+        launch.launch_id = self.launch_id;
+        launch.status = self.status;
+        launch.total_raised = self.total_raised;
+        launch.holder_count = self.holder_count;
+        launch.peak_holder_count = self.holder_count;
+        launch.tier = LaunchTier::Standard;
+        launch.hard_cap = get_launch_tier_hard_cap(LaunchTier::Standard);
+        launch.guaranteed_pool_bps = self.guaranteed_pool_bps;
+        launch.lottery_pool_bps = self.lottery_pool_bps;
+        launch.public_pool_bps = self.public_pool_bps;
+        launch.fcfs_pool_bps = self.fcfs_pool_bps;
+        launch.flipper_pool_bps = self.flipper_pool_bps;
+        launch.liquidity_pool_bps = self.liquidity_pool_bps;
+        launch.trader_rewards_pool_bps = self.trader_rewards_pool_bps;
+        launch.bump = self.bump;
+    }
+}
+
+/// Maps a launch id to the address of a launch created at a seeded or
+/// creator-nonce address. `seed` is empty for nonce launches.
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchIndex {
+    pub launch_id: u64,
+    pub launch: Pubkey,
+    #[max_len(MAX_LAUNCH_SEED_LEN)]
+    pub seed: String,
+    pub bump: u8,
+}
+
+impl LaunchIndex {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchTemplate {
+    pub publisher: Pubkey,
+    #[max_len(MAX_NAME_LEN)]
+    pub name: String,
+    pub config: LaunchConfig,
+    pub verified: bool,
+    pub times_used: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl LaunchTemplate {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Index of launch applications awaiting a curator decision
+#[account]
+#[derive(InitSpace)]
+pub struct ReviewQueue {
+    #[max_len(MAX_REVIEW_QUEUE)]
+    pub entries: Vec<ReviewEntry>,
+    pub bump: u8,
+}
+
+impl ReviewQueue {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+
+    pub fn remove(&mut self, launch: &Pubkey) {
+        self.entries.retain(|entry| entry.launch != *launch);
+    }
+
+    /// Drop applications whose review window has closed, so undecided
+    /// launches can't hold the queue full. Curators can still decide them.
+    pub fn prune_expired(&mut self, now: i64) {
+        self.entries.retain(|entry| entry.deadline >= now);
+    }
+}
+
+/// One of LAUNCH_ID_SHARDS independent launch id counters, at
+/// `["launch_id_shard", shard]`. Ids carry SHARDED_LAUNCH_ID_BIT, so they
+/// never collide with protocol-numbered ids, and the shard in their low
+/// byte, so shards never collide with each other.
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchIdShard {
+    pub shard: u8,
+    /// Launches numbered by this shard so far
+    pub launches: u64,
+    pub bump: u8,
+}
+
+impl LaunchIdShard {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+
+    pub fn next_launch_id(&self) -> u64 {
+        SHARDED_LAUNCH_ID_BIT | (self.launches << 8) | self.shard as u64
+    }
+
+    pub fn take_launch_id(&mut self) -> u64 {
+        let launch_id = self.next_launch_id();
+        self.launches += 1;
+        launch_id
+    }
+}
+
+/// Numbers a creator's launches, at `["creator_account", creator]`
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorAccount {
+    pub creator: Pubkey,
+    /// Nonce the creator's next launch is created at
+    pub launch_count: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl CreatorAccount {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// SOL and DPAD a creator has put at risk behind their launches
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorStake {
+    pub creator: Pubkey,
+    pub sol_staked: u64,
+    pub dpad_staked: u64,
+    pub tier: LaunchTier,
+    pub locked_until: i64,
+    /// Rug rulings against this creator's launches; never decremented
+    pub times_slashed: u32,
+    /// When the first rug ruling landed (0 = none)
+    pub first_rug_verdict_at: i64,
+    pub bump: u8,
+}
+
+impl CreatorStake {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// A launch's safety steps, at `["creator_checklist", launch]`, so a UI can
+/// render the creator's progress straight from chain state. The
+/// instructions that complete a step tick it off when passed the account.
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorChecklist {
+    pub launch: Pubkey,
+    pub creator: Pubkey,
+    /// CHECKLIST_* steps completed; steps never reopen
+    pub completed: u8,
+    /// When each step was completed (0 = open), indexed by bit position
+    pub completed_at: [i64; CHECKLIST_STEPS],
+    pub bump: u8,
+}
+
+impl CreatorChecklist {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+
+    /// Tick off `steps`, stamping and announcing any not done before
+    pub fn complete(&mut self, steps: u8, now: i64) {
+        let newly_completed = steps & !self.completed;
+        if newly_completed == 0 {
+            return;
+        }
+        for (bit, completed_at) in self.completed_at.iter_mut().enumerate() {
+            if newly_completed & (1 << bit) != 0 {
+                *completed_at = now;
+            }
+        }
+        self.completed |= newly_completed;
+
+        emit!(CreatorChecklistUpdated {
+            launch: self.launch,
+            completed: self.completed,
+            newly_completed,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+    }
+}
+
+/// Oracle-maintained risk assessment for a wallet
+#[account]
+#[derive(InitSpace)]
+pub struct WalletRisk {
+    pub wallet: Pubkey,
+    /// 0 (clean) to 100 (known bad)
+    pub score: u8,
+    /// Oracle-defined category bitflags (sniper, drainer, mixer, ...)
+    pub categories: u32,
+    pub updated_at: i64,
+    pub bump: u8,
+    /// Slot of the wallet's first signature (0 = not attested)
+    pub first_signature_slot: u64,
+    /// Wallet that first funded this one, as attested by the oracle
+    pub funded_by: Pubkey,
+}
+
+impl WalletRisk {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Verifier-backed proof that a creator controls a social handle or domain.
+/// Only a hash of the handle is stored.
+#[account]
+#[derive(InitSpace)]
+pub struct Attestation {
+    pub creator: Pubkey,
+    pub verifier: Pubkey,
+    pub kind: AttestationKind,
+    pub handle_hash: [u8; 32],
+    pub verified_at: i64,
+    pub revoked: bool,
+    pub bump: u8,
+}
+
+impl Attestation {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Allocation {
+    pub owner: Pubkey,
+    pub launch: Pubkey,
+    pub pool: AllocationPool,
+    pub requested_amount_usd: u64,
+    pub allocated_tokens: u64,
+    pub weight: u16,
+    pub status: AllocationStatus,
+    pub requested_at: i64,
+    pub vesting_start: i64,
+    pub vesting_cliff_days: u16,
+    pub vesting_duration_days: u16,
+    pub tge_unlock_bps: u16,
+    pub tokens_claimed: u64,
+    /// Set when the council revokes the schedule; nothing vests after it
+    pub revoked_at: i64,
+    pub tokens_forfeited: u64,
+    pub bump: u8,
+    /// Tokens sold to an OTC buyer, who receives them as they vest
+    pub otc_tokens: u64,
+    /// Tokens already vested when the OTC sale filled
+    pub otc_vested_at_sale: u64,
+}
+
+impl Allocation {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+
+    /// Tokens vested by `now`, owner's and OTC buyer's together. Vesting
+    /// stops at revocation.
+    pub fn vested(&self, now: i64) -> u64 {
+        let vested_until = match self.revoked_at {
+            0 => now,
+            revoked_at => revoked_at.min(now),
+        };
+        calculate_vested_amount(
+            self.allocated_tokens,
+            self.vesting_start,
+            self.vesting_cliff_days,
+            self.vesting_duration_days,
+            self.tge_unlock_bps,
+            vested_until,
+        )
+    }
+
+    /// Tokens still due to the owner: the schedule less the OTC buyer's
+    /// part, cut to what had vested if revoked, less what's been claimed
+    pub fn locked(&self) -> u64 {
+        if self.status != AllocationStatus::Won {
+            return 0;
+        }
+        let total = if self.revoked_at == 0 {
+            self.allocated_tokens - self.otc_tokens
+        } else {
+            let vested = self.vested(self.revoked_at);
+            vested - self.otc_vested(vested)
+        };
+        total.saturating_sub(self.tokens_claimed)
+    }
+
+    /// The OTC buyer's part of `vested`: their tokens vest in step with
+    /// whatever was still unvested when the sale filled
+    pub fn otc_vested(&self, vested: u64) -> u64 {
+        if self.otc_tokens == 0 {
+            return 0;
+        }
+        let unvested_at_sale = self.allocated_tokens - self.otc_vested_at_sale;
+        (self.otc_tokens as u128 * (vested - self.otc_vested_at_sale) as u128
+            / unvested_at_sale as u128) as u64
+    }
+}
+
+/// An OTC offer on part of an allocation, at `["otc_sale", allocation]`.
+/// An allocation can be sold OTC once; the account stays after filling as
+/// the buyer's claim record.
+#[account]
+#[derive(InitSpace)]
+pub struct OtcSale {
+    pub allocation: Pubkey,
+    pub launch: Pubkey,
+    pub seller: Pubkey,
+    /// Who may take the offer (default = anyone); the actual buyer once filled
+    pub buyer: Pubkey,
+    pub tokens: u64,
+    /// Lamports the buyer pays, held on this account until earned
+    pub price: u64,
+    pub status: OtcSaleStatus,
+    pub tokens_claimed: u64,
+    pub listed_at: i64,
+    pub filled_at: i64,
+    pub bump: u8,
+    pub proceeds_withdrawn: u64,
+    /// Price handed back to the buyer when the schedule was revoked
+    pub refunded: u64,
+}
+
+impl OtcSale {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+
+    /// The seller's share of the price once `otc_vested` of the buyer's
+    /// tokens have vested
+    pub fn earned(&self, otc_vested: u64) -> u64 {
+        (self.price as u128 * otc_vested as u128 / self.tokens as u128) as u64
+    }
+}
+
+/// Totals across one wallet's vesting schedules, at `["vesting_summary", owner]`
+#[account]
+#[derive(InitSpace)]
+pub struct VestingSummary {
+    pub owner: Pubkey,
+    pub schedule_count: u32,
+    /// Allocated but not yet released
+    pub total_locked: u64,
+    pub total_released: u64,
+    /// Earliest cliff end still ahead as of the last update (0 = none)
+    pub next_unlock_at: i64,
+    /// When the last schedule seen finishes vesting
+    pub fully_vested_at: i64,
+    pub bump: u8,
+}
+
+impl VestingSummary {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+
+    /// Fill in a summary `init_if_needed` just opened
+    pub fn open(&mut self, owner: Pubkey, bump: u8) {
+        if self.owner == Pubkey::default() {
+            self.owner = owner;
+            self.bump = bump;
+        }
+    }
+
+    /// Take a schedule out of the totals before it changes. Saturates, so
+    /// a schedule from before the summary existed can't underflow it.
+    pub fn untrack(&mut self, allocation: &Allocation) {
+        if allocation.status == AllocationStatus::Won {
+            self.schedule_count = self.schedule_count.saturating_sub(1);
+            self.total_locked = self.total_locked.saturating_sub(allocation.locked());
+        }
+    }
+
+    /// Count a won schedule into the totals and fold its unlock times in,
+    /// dropping a cliff that has passed. Other schedules' later cliffs
+    /// aren't known here; `rebuild_vesting_summary` restores them.
+    pub fn track_schedule(&mut self, allocation: &Allocation, now: i64) {
+        if allocation.status != AllocationStatus::Won {
+            return;
+        }
+        self.schedule_count += 1;
+        self.total_locked = self.total_locked.checked_add(allocation.locked()).unwrap();
+
+        let cliff_end = allocation.vesting_start + allocation.vesting_cliff_days as i64 * 86400;
+        let vested_at = cliff_end + allocation.vesting_duration_days as i64 * 86400;
+        if self.next_unlock_at <= now {
+            self.next_unlock_at = 0;
+        }
+        if cliff_end > now && (self.next_unlock_at == 0 || cliff_end < self.next_unlock_at) {
+            self.next_unlock_at = cliff_end;
+        }
+        self.fully_vested_at = self.fully_vested_at.max(vested_at);
+    }
+
+    pub fn emit_update(&self) {
+        emit!(VestingSummaryUpdated {
+            owner: self.owner,
+            schedule_count: self.schedule_count,
+            total_locked: self.total_locked,
+            total_released: self.total_released,
+            next_unlock_at: self.next_unlock_at,
+            fully_vested_at: self.fully_vested_at,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    /// POSITION_LAYOUT_VERSION the account was written with
+    pub layout_version: u8,
+    /// POSITION_* flag bits
+    pub flags: u8,
+    pub holder: Pubkey,
+    pub launch: Pubkey,
+    pub balance: u64,
+    // Timestamps are unix seconds as u32, good until 2106
+    pub first_buy_timestamp: u32,
+    pub last_activity_timestamp: u32,
+    pub last_claim_timestamp: u32,
+    pub diamond_rank: DiamondRank,
+    pub multiplier_bps: u16,
+    pub total_rewards_claimed: u64,
+    // Sell grace tracking (rolling window)
+    pub grace_window_start: u32,
+    pub grace_window_base: u64,
+    pub grace_window_sold: u64,
+    pub last_buy_slot: u64,
+    // Wash trading evidence
+    pub last_trade_timestamp: u32,
+    pub last_trade_side: TradeSide,
+    pub rapid_flips: u32,
+    pub wash_score: u16,
+    // Reward accounting
+    pub reward_weight: u128,
+    pub reward_index_snapshot: u128,
+    pub accrued_rewards: u64,
+    /// Weight gained this epoch that only earns once the epoch closes
+    pub pending_weight: u128,
+    /// Epoch of the last checkpoint
+    pub checkpoint_epoch: u64,
+    /// No longer written; weight drops already apply at once through
+    /// `sync_reward_weight`. Kept so the layout doesn't shift.
+    pub checkpoint_balance: u64,
+    /// Hold-plus-LP boost, refreshed on claim
+    pub lp_boost_bps: u16,
+    // Referrals
+    pub referral_code: Pubkey,
+    /// Boost from this holder's own qualified referrals, refreshed on claim
+    pub referral_boost_bps: u16,
+    // Recovery
+    pub recovery: Pubkey,
+    pub pending_recovery: Pubkey,
+    pub recovery_change_at: u32,
+    /// Earliest entry borrowed from a linked wallet's position (0 if none)
+    pub linked_since: u32,
+    /// Balance-seconds since the last claim, for the time-weighted balance
+    pub twab_accumulator: u128,
+    pub twab_period_start: u32,
+    pub twab_updated_at: u32,
+    /// Actions taken in the current one-minute rate-limit window
+    pub rate_window_start: u32,
+    pub rate_window_actions: u16,
+    pub bump: u8,
+    // Layout 3
+    /// When each DiamondRank was first reached (0 = not yet), indexed by rank
+    pub rank_reached_at: [i64; 6],
+    // Layout 4
+    /// Reward epoch of the last claim gas refund, and lamports refunded in it
+    pub gas_refund_epoch: u64,
+    pub gas_refunded_in_epoch: u64,
+    // Layout 5
+    /// Linked position `linked_since` was borrowed from, and its balance then
+    pub linked_source: Pubkey,
+    pub linked_source_balance: u64,
+}
+
+impl Position {
+    /// No reserve: there is one of these per holder per launch, so later
+    /// layouts bump `layout_version` and realloc instead
+    pub const SIZE: usize = 8 + Self::INIT_SPACE;
+
+    pub fn has_flag(&self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
+
+    pub fn set_flag(&mut self, flag: u8, on: bool) {
+        if on {
+            self.flags |= flag;
+        } else {
+            self.flags &= !flag;
+        }
+    }
+
+    /// Drop an entry borrowed from a linked wallet
+    pub fn clear_linked_entry(&mut self) {
+        self.linked_since = 0;
+        self.linked_source = Pubkey::default();
+        self.linked_source_balance = 0;
+    }
+
+    /// Timestamp rank is measured from: own entry, or a linked wallet's if earlier
+    pub fn rank_since(&self) -> i64 {
+        if self.linked_since != 0 {
+            self.first_buy_timestamp.min(self.linked_since) as i64
+        } else {
+            self.first_buy_timestamp as i64
+        }
+    }
+
+    /// Date each rank up to the current one the first time it's seen. Rank
+    /// comes from time held, so a rank passed between refreshes is dated to
+    /// when its threshold was crossed rather than to the refresh.
+    pub fn record_rank_timeline(&mut self, threshold_days: &[u16; 6]) {
+        let since = self.rank_since();
+        let current = self.diamond_rank;
+        for rank in DiamondRank::ALL.into_iter().take_while(|rank| *rank <= current) {
+            let reached_at = &mut self.rank_reached_at[rank as usize];
+            if *reached_at == 0 {
+                *reached_at = since + threshold_days[rank as usize] as i64 * 86400;
+            }
+        }
+    }
+
+    /// Fold the balance held since the last update into the claim period's
+    /// balance-seconds. Balances only change after a settle, so the balance
+    /// here is the one held for the whole stretch.
+    pub fn accumulate_twab(&mut self, now: i64) {
+        if self.twab_period_start == 0 {
+            self.twab_period_start = now as u32;
+        } else {
+            let held = (now - self.twab_updated_at as i64).max(0) as u128;
+            self.twab_accumulator += self.balance as u128 * held;
+        }
+        self.twab_updated_at = now as u32;
+    }
+
+    /// Time-weighted average balance over the claim period so far, or
+    /// `None` if no time has passed since it started
+    pub fn twab(&self) -> Option<u64> {
+        let period = self.twab_updated_at as i64 - self.twab_period_start as i64;
+        (period > 0).then(|| (self.twab_accumulator / period as u128) as u64)
+    }
+
+    /// Start a new claim period at `now`
+    pub fn reset_twab(&mut self, now: i64) {
+        self.twab_accumulator = 0;
+        self.twab_period_start = now as u32;
+        self.twab_updated_at = now as u32;
+    }
+
+    /// Count one state-changing action against the protocol's per-minute
+    /// limit (0 = unlimited)
+    pub fn consume_rate_limit(&mut self, max_per_minute: u16, now: i64) -> Result<()> {
+        if max_per_minute == 0 {
+            return Ok(());
+        }
+        if now - self.rate_window_start as i64 >= 60 {
+            self.rate_window_start = now as u32;
+            self.rate_window_actions = 0;
+        }
+        require!(
+            self.rate_window_actions < max_per_minute,
+            DiamondPadError::RateLimited
+        );
+        self.rate_window_actions += 1;
+        Ok(())
+    }
+}
+
+/// A Position as first deployed (layout 1), read by `upgrade_position`
+#[derive(AnchorDeserialize)]
+pub struct LegacyPosition {
+    pub holder: Pubkey,
+    pub launch: Pubkey,
+    pub balance: u64,
+    pub first_buy_timestamp: i64,
+    pub last_activity_timestamp: i64,
+    pub last_claim_timestamp: i64,
+    pub diamond_rank: DiamondRank,
+    pub multiplier_bps: u16,
+    pub total_rewards_claimed: u64,
+    pub bump: u8,
+}
+
+impl LegacyPosition {
+    /// Copy the old fields onto a zeroed current-layout position
+    pub fn carry_over(self, position: &mut Position) {
+        position.holder = self.holder;
+        position.launch = self.launch;
+        position.balance = self.balance;
+        position.first_buy_timestamp = self.first_buy_timestamp as u32;
+        position.last_activity_timestamp = self.last_activity_timestamp as u32;
+        position.last_claim_timestamp = self.last_claim_timestamp as u32;
+        position.diamond_rank = self.diamond_rank;
+        position.multiplier_bps = self.multiplier_bps;
+        position.total_rewards_claimed = self.total_rewards_claimed;
+        position.bump = self.bump;
+    }
+}
+
+/// Wallets one holder has proven they control, aggregated for rank, plus
+/// lifetime stats across every launch those wallets held
+#[account]
+#[derive(InitSpace)]
+pub struct HolderProfile {
+    pub owner: Pubkey,
+    #[max_len(MAX_LINKED_WALLETS)]
+    pub wallets: Vec<Pubkey>,
+    // Lifetime stats
+    pub launches_participated: u32,
+    pub total_rewards_claimed: u64,
+    pub best_rank: DiamondRank,
+    /// Longest a single position has held its rank, in seconds
+    pub longest_hold_seconds: i64,
+    /// Registered handle (empty = none)
+    #[max_len(MAX_USERNAME_LEN)]
+    pub username: String,
+    /// Unlocked achievements, one `Achievement::bit` each
+    pub achievements: u32,
+    pub bump: u8,
+}
+
+impl HolderProfile {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+
+    /// Count a held position's launch once and keep its best rank and hold
+    pub fn record_position(&mut self, position: &mut Position, now: i64) {
+        if position.balance > 0 && !position.has_flag(POSITION_COUNTED_IN_PROFILE) {
+            position.set_flag(POSITION_COUNTED_IN_PROFILE, true);
+            self.launches_participated += 1;
+        }
+        self.best_rank = self.best_rank.max(position.diamond_rank);
+        if position.balance > 0 {
+            self.longest_hold_seconds = self.longest_hold_seconds.max(now - position.rank_since());
+        }
+
+        emit!(HolderStatsUpdated {
+            owner: self.owner,
+            launches_participated: self.launches_participated,
+            total_rewards_claimed: self.total_rewards_claimed,
+            best_rank: self.best_rank,
+            longest_hold_seconds: self.longest_hold_seconds,
+            schema_version: EVENT_SCHEMA_VERSION,
+        });
+    }
+}
+
+/// A unique handle at `["username", name]`, pointing at its profile
+#[account]
+#[derive(InitSpace)]
+pub struct Username {
+    #[max_len(MAX_USERNAME_LEN)]
+    pub name: String,
+    pub profile: Pubkey,
+    pub registered_at: i64,
+    /// No release or transfer before this, so handles can't be flipped
+    pub locked_until: i64,
+    pub bump: u8,
+}
+
+impl Username {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Reverse lookup so a wallet can only sit in one profile
+#[account]
+#[derive(InitSpace)]
+pub struct WalletLink {
+    pub wallet: Pubkey,
+    pub profile: Pubkey,
+    pub bump: u8,
+}
+
+impl WalletLink {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Deposited LP tokens for a graduated launch, ranked like a token position
+#[account]
+#[derive(InitSpace)]
+pub struct LiquidityPosition {
+    pub provider: Pubkey,
+    pub launch: Pubkey,
+    pub lp_amount: u64,
+    pub first_deposit_timestamp: i64,
+    pub last_activity_timestamp: i64,
+    pub diamond_rank: DiamondRank,
+    pub multiplier_bps: u16,
+    pub bump: u8,
+}
+
+impl LiquidityPosition {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Receipt of what a wallet paid into a launch and what it is owed.
+/// Authoritative source for refunds and sale allocations, unlike Position
+/// which tracks holding behaviour.
+#[account]
+#[derive(InitSpace)]
+pub struct Contribution {
+    pub contributor: Pubkey,
+    pub launch: Pubkey,
+    pub quote_contributed: u64,
+    pub tokens_allocated: u64,
+    pub last_contribution_timestamp: i64,
+    pub refunded: bool,
+    pub bump: u8,
+    /// Left unclaimed past the claim window and written off
+    pub swept: bool,
+    pub tokens_claimed: u64,
+    /// Slot of the contributor's first buy into this launch
+    pub first_buy_slot: u64,
+    /// Funding source the oracle had attested for the wallet at first buy
+    pub funded_by: Pubkey,
+    /// Part of the unclaimed allocation already issued from the airdrop
+    /// bucket (airdrops and other bonus credits), so claiming it doesn't
+    /// draw on the sale bucket
+    pub bonus_unclaimed: u64,
+}
+
+impl Contribution {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+
+    /// Unclaimed tokens bought from the curve, leaving out bonus
+    /// allocations; the only ones `sell` takes back
+    pub fn curve_tokens(&self) -> u64 {
+        self.tokens_allocated
+            .saturating_sub(self.tokens_claimed)
+            .saturating_sub(self.bonus_unclaimed)
+    }
+}
+
+/// Lamports set aside for a launch's holders in case it rugs, at
+/// `["insurance_vault", launch]`. The account holds the lamports itself.
+#[account]
+#[derive(InitSpace)]
+pub struct InsuranceVault {
+    pub launch: Pubkey,
+    pub total_funded: u64,
+    /// Claims can be filed until this time (0 = claims not open)
+    pub claims_deadline: i64,
+    pub claims_filed: u32,
+    pub claims_pending: u32,
+    pub claims_approved: u32,
+    /// Snapshotted position balances across filed and approved claims
+    pub total_filed: u64,
+    pub total_approved: u64,
+    /// Payouts have started; no more funding or decisions after this
+    pub settled: bool,
+    /// Lamports split between approved claims, fixed at the first payout
+    pub payout_pool: u64,
+    pub total_paid: u64,
+    /// Slashed creator DPAD held in `["insurance_tokens", launch]`
+    pub dpad_funded: u64,
+    pub dpad_payout_pool: u64,
+    pub bump: u8,
+}
+
+impl InsuranceVault {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// A holder's claim on a rug-flagged launch's insurance vault, with the
+/// position as it stood when filed
+#[account]
+#[derive(InitSpace)]
+pub struct InsuranceClaim {
+    pub launch: Pubkey,
+    pub holder: Pubkey,
+    pub balance: u64,
+    pub diamond_rank: DiamondRank,
+    pub first_buy_timestamp: i64,
+    pub filed_at: i64,
+    pub status: InsuranceClaimStatus,
+    pub payout: u64,
+    pub dpad_payout: u64,
+    pub bump: u8,
+}
+
+impl InsuranceClaim {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Seat count for the arbitration council, at `["council"]`
+#[account]
+#[derive(InitSpace)]
+pub struct Council {
+    pub member_count: u8,
+    pub bump: u8,
+}
+
+impl Council {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// One council seat, at `["council_member", wallet]`. Signatures only count
+/// until the term ends.
+#[account]
+#[derive(InitSpace)]
+pub struct CouncilMember {
+    pub wallet: Pubkey,
+    pub term_started_at: i64,
+    pub term_ends_at: i64,
+    pub bump: u8,
+}
+
+impl CouncilMember {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// A holder proposal on a launch, at `["launch_proposal", launch, proposal_id]`
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchProposal {
+    pub launch: Pubkey,
+    pub proposer: Pubkey,
+    pub proposal_id: u32,
+    /// Hash of the off-chain proposal text
+    pub description_hash: [u8; 32],
+    pub action: ProposalAction,
+    pub executed: bool,
+    pub created_at: i64,
+    pub voting_ends_at: i64,
+    /// Rank-weighted votes, in reward weight units
+    pub votes_for: u128,
+    pub votes_against: u128,
+    pub bump: u8,
+    /// The launch's total reward weight when the proposal opened
+    pub total_weight: u128,
+    /// Votes needed either way for the proposal to pass
+    pub quorum_weight: u128,
+}
+
+impl LaunchProposal {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// One position's vote on a proposal, at `["launch_vote", proposal, holder]`
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchVote {
+    pub proposal: Pubkey,
+    pub holder: Pubkey,
+    /// The holder, or the delegate who voted for them
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u128,
+    pub bump: u8,
+}
+
+impl LaunchVote {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// A holder's launch voting power handed to another wallet, at
+/// `["vote_delegation", launch, holder]`. Closed to revoke.
+#[account]
+#[derive(InitSpace)]
+pub struct VoteDelegation {
+    pub launch: Pubkey,
+    pub holder: Pubkey,
+    pub delegate: Pubkey,
+    pub delegated_at: i64,
+    pub bump: u8,
+}
+
+impl VoteDelegation {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// A funded task on a launch, at `["quest", launch, quest_id]`. The reward
+/// budget is held as extra lamports on the account itself.
+#[account]
+#[derive(InitSpace)]
+pub struct Quest {
+    pub launch: Pubkey,
+    pub creator: Pubkey,
+    pub quest_id: u32,
+    pub condition: QuestCondition,
+    /// Lamports paid per completion
+    pub reward: u64,
+    pub budget_remaining: u64,
+    pub completions: u32,
+    /// No completions from this time on (0 = open-ended)
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl Quest {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Marks a holder as having completed a quest, at `["quest_completion", quest, holder]`
+#[account]
+#[derive(InitSpace)]
+pub struct QuestCompletion {
+    pub quest: Pubkey,
+    pub holder: Pubkey,
+    pub completed_at: i64,
+    pub bump: u8,
+}
+
+impl QuestCompletion {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Receipt for a claimed airdrop: the graduation airdrop at
+/// `["airdrop_claim", launch, holder]`, or a loyalty bonus at
+/// `["loyalty_claim", launch, holder]`
+#[account]
+#[derive(InitSpace)]
+pub struct AirdropClaim {
+    pub holder: Pubkey,
+    pub launch: Pubkey,
+    pub amount: u64,
+    pub claimed_at: i64,
+    pub bump: u8,
+}
+
+impl AirdropClaim {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Receipt for a holding imported from a relaunched token, at
+/// `["position_import", launch, holder]`
+#[account]
+#[derive(InitSpace)]
+pub struct PositionImport {
+    pub holder: Pubkey,
+    pub launch: Pubkey,
+    pub balance: u64,
+    /// When the holder first held the old token, clamped to the window
+    /// `import_position` allows
+    pub hold_start: i64,
+    pub imported_at: i64,
+    pub bump: u8,
+}
+
+impl PositionImport {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Bundler registry entry at `["bundler", wallet]`.
+///
+/// The layout is part of the public interface and only ever grows at the
+/// end, so other programs may read it directly; `check_bundler` is the
+/// simpler route via CPI.
+#[account]
+#[derive(InitSpace)]
+pub struct Bundler {
+    pub wallet: Pubkey,
+    pub flagged_at: i64,
+    /// Every report filed against the wallet, oldest first
+    #[max_len(MAX_EVIDENCE_ENTRIES)]
+    pub evidence: Vec<EvidenceEntry>,
+    pub incident_count: u32,
+    pub bump: u8,
+    pub severity: BundlerSeverity,
+}
+
+impl Bundler {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Bundler enforcement rollup at `["bundler_stats"]`: lifetime totals plus
+/// a ring of weekly buckets, kept zero-copy so indexers and other programs
+/// can read it without deserializing
+#[account(zero_copy)]
+pub struct BundlerStats {
+    pub weeks: [BundlerWeek; BUNDLER_STATS_WEEKS],
+    pub total_flags: u64,
+    /// Claims cut or blocked because the claimant is a flagged bundler
+    pub total_enforcements: u64,
+    pub bump: u8,
+    pub _padding: [u8; 7],
+}
+
+/// One week's bundler activity; `week` is unix time / BUNDLER_STATS_WEEK_SECONDS
+#[zero_copy]
+pub struct BundlerWeek {
+    pub week: u64,
+    pub flags: u32,
+    pub enforcements: u32,
+}
+
+impl BundlerStats {
+    pub const SIZE: usize = 8 + std::mem::size_of::<Self>();
+
+    pub fn record_flag(&mut self, now: i64) {
+        self.total_flags += 1;
+        self.week_at(now).flags += 1;
+    }
+
+    pub fn record_enforcement(&mut self, now: i64) {
+        self.total_enforcements += 1;
+        self.week_at(now).enforcements += 1;
+    }
+
+    /// The bucket for `now`'s week, cleared if it still holds an older week
+    fn week_at(&mut self, now: i64) -> &mut BundlerWeek {
+        let week = (now / BUNDLER_STATS_WEEK_SECONDS) as u64;
+        let bucket = &mut self.weeks[(week % BUNDLER_STATS_WEEKS as u64) as usize];
+        if bucket.week != week {
+            *bucket = BundlerWeek { week, flags: 0, enforcements: 0 };
+        }
+        bucket
+    }
+}
+
+/// A key delegated bundler-flagging powers (and nothing else)
+#[account]
+#[derive(InitSpace)]
+pub struct Flagger {
+    pub key: Pubkey,
+    pub added_at: i64,
+    pub bump: u8,
+}
+
+impl Flagger {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// A key allowed to moderate one launch, at
+/// `["mod_assignment", launch, moderator]`
+#[account]
+#[derive(InitSpace)]
+pub struct ModAssignment {
+    pub launch: Pubkey,
+    pub moderator: Pubkey,
+    pub assigned_at: i64,
+    pub bump: u8,
+}
+
+impl ModAssignment {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// An ecosystem partner launches can be attributed to, at
+/// `["channel", partner]`. Its share of those launches' protocol fees is
+/// held on this account until the partner claims it.
+#[account]
+#[derive(InitSpace)]
+pub struct Channel {
+    pub partner: Pubkey,
+    /// Share of attributed launches' protocol trade fees
+    pub fee_share_bps: u16,
+    pub launches_attributed: u64,
+    pub fees_accrued: u64,
+    pub fees_claimed: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Channel {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// An aggregator/router program allowed to CPI into position recording
+#[account]
+#[derive(InitSpace)]
+pub struct ApprovedRouter {
+    pub program: Pubkey,
+    pub added_at: i64,
+    pub bump: u8,
+}
+
+impl ApprovedRouter {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// A key allowed to report DEX trades for graduated launches
+#[account]
+#[derive(InitSpace)]
+pub struct TradeReporter {
+    pub key: Pubkey,
+    pub added_at: i64,
+    pub trades_reported: u64,
+    pub bump: u8,
+}
+
+impl TradeReporter {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// A bundler flag collecting flagger approvals until it expires
+#[account]
+#[derive(InitSpace)]
+pub struct PendingFlag {
+    pub wallet: Pubkey,
+    pub proposer: Pubkey,
+    pub evidence: EvidenceEntry,
+    #[max_len(MAX_FLAG_APPROVALS)]
+    pub approvals: Vec<Pubkey>,
+    pub proposed_at: i64,
+    pub bump: u8,
+}
+
+impl PendingFlag {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+
+    /// When the flag stops taking approvals and can no longer be enforced
+    pub fn expires_at(&self) -> i64 {
+        self.proposed_at.saturating_add(PENDING_FLAG_TTL_SECONDS)
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at()
+    }
+}
+
+/// Marks a detector report as relayed so it can't be replayed
+#[account]
+#[derive(InitSpace)]
+pub struct DetectionReportReceipt {
+    pub wallet: Pubkey,
+    pub detector: Pubkey,
+    pub report_hash: [u8; 32],
+    pub submitted_at: i64,
+    pub bump: u8,
+}
+
+impl DetectionReportReceipt {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// On-chain resolution of a referral link code to its payout wallet
+#[account]
+#[derive(InitSpace)]
+pub struct ReferralCode {
+    pub owner: Pubkey,
+    pub payout_wallet: Pubkey,
+    #[max_len(MAX_REFERRAL_CODE_LEN)]
+    pub code: String,
+    pub created_at: i64,
+    /// Referred buyers who went on to reach Bronze or above, across every
+    /// launch. The reward boost uses the per-launch count in `ReferralStats`.
+    pub qualified_referrals: u32,
+    pub bump: u8,
+}
+
+impl ReferralCode {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// Volume bought through one referral code on one launch, at
+/// `["referral_stats", launch, referral_code]`
+#[account]
+#[derive(InitSpace)]
+pub struct ReferralStats {
+    pub launch: Pubkey,
+    pub referral_code: Pubkey,
+    /// Lamports spent on curve buys by positions attributed to the code
+    pub referred_volume: u64,
+    pub referred_buys: u32,
+    pub bonus_claimed: bool,
+    pub bump: u8,
+    /// Referred buyers on this launch who reached Bronze or above; sets the
+    /// referrer's reward boost on this launch
+    pub qualified_referrals: u32,
+}
+
+impl ReferralStats {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// A launch's top referrers by referred volume and the creator-funded
+/// bonus pool they split at graduation, at `["referral_board", launch]`.
+/// The pool's lamports are held on this account.
+#[account]
+#[derive(InitSpace)]
+pub struct ReferralBoard {
+    pub launch: Pubkey,
+    #[max_len(MAX_REFERRAL_LEADERS)]
+    pub leaders: Vec<ReferralLeader>,
+    pub bonus_pool: u64,
+    pub bonus_claimed: u64,
+    pub bump: u8,
+}
+
+impl ReferralBoard {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+
+    /// Update a code's volume on the board, taking the lowest leader's
+    /// place if it has overtaken them
+    pub fn record(&mut self, referral_code: Pubkey, volume: u64) {
+        if let Some(leader) = self.leaders.iter_mut().find(|l| l.referral_code == referral_code) {
+            leader.volume = volume;
+        } else if self.leaders.len() < MAX_REFERRAL_LEADERS {
+            self.leaders.push(ReferralLeader { referral_code, volume });
+        } else if let Some(lowest) = self.leaders.iter_mut().min_by_key(|l| l.volume) {
+            if volume > lowest.volume {
+                *lowest = ReferralLeader { referral_code, volume };
+            }
+        }
+    }
+
+    /// Referred volume across everyone on the board
+    pub fn leader_volume(&self) -> u64 {
+        self.leaders.iter().map(|leader| leader.volume).sum()
+    }
+}
+
+/// One protocol-wide leaderboard season, at `["season", season_id]`
+#[account]
+#[derive(InitSpace)]
+pub struct Season {
+    pub season_id: u32,
+    pub starts_at: i64,
+    /// Scores stop moving at this time, leaving them final for prizes
+    pub ends_at: i64,
+    pub participants: u32,
+    pub total_score: u64,
+    pub bump: u8,
+}
+
+impl Season {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+/// A wallet's score in one season, at `["season_score", season, wallet]`
+#[account]
+#[derive(InitSpace)]
+pub struct SeasonScore {
+    pub season: Pubkey,
+    pub wallet: Pubkey,
+    /// Buy volume weighted by the position's rank multiplier at the time
+    pub score: u64,
+    pub last_activity_at: i64,
+    pub bump: u8,
+}
+
+impl SeasonScore {
+    pub const SIZE: usize = 8 + Self::INIT_SPACE + ACCOUNT_RESERVE;
+}
+
+// Accounts are created in a single system-program CPI, which caps their
+// size. Fail the build, not the first init, if a layout outgrows it.
+const _: () = {
+    use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+    assert!(Protocol::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(ProtocolStats::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(InsuranceVault::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(InsuranceClaim::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(Council::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(CouncilMember::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(LaunchProposal::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(LaunchVote::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(VoteDelegation::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(GaugeEpoch::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(LaunchGauge::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(GaugeVote::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(StakerAccount::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(Launch::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(LaunchIndex::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(CreatorAccount::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(LaunchIdShard::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(LaunchTemplate::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(ReviewQueue::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(CreatorStake::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(CreatorChecklist::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(WalletRisk::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(Attestation::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(Allocation::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(VestingSummary::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(OtcSale::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(Position::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(HolderProfile::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(WalletLink::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(Username::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(LiquidityPosition::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(Contribution::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(AirdropClaim::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(PositionImport::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(Quest::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(QuestCompletion::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(Bundler::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(Flagger::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(BundlerStats::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(ModAssignment::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(ApprovedRouter::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(Channel::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(TradeReporter::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(PendingFlag::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(ReferralCode::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(Season::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(SeasonScore::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(ReferralStats::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(ReferralBoard::SIZE <= MAX_PERMITTED_DATA_INCREASE);
+};
+
+// ============ Config Types ============
+
+/// Creator-tunable launch parameters, shared by launches and templates
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct LaunchConfig {
+    pub dev_allocation_bps: u16,
+    pub dev_vesting_days: u16,
+    pub lp_lock_days: u16,
+    pub holder_rewards_bps: u16,
+    pub min_contribution: u64,
+    pub max_position_bps: u16,
+    pub sell_grace_bps: u16,
+    pub sell_policy: SellPolicy,
+    pub curve: CurveType,
+    pub virtual_sol_reserves: u64,
+    /// Virtual token reserves as a share of the sale supply
+    pub virtual_token_reserves_bps: u16,
+    /// Share of every trade fee routed to the launch's reward vault
+    pub reward_fee_share_bps: u16,
+    /// Civic gatekeeper network buyers must hold a pass for (None = open)
+    pub gatekeeper_network: Option<Pubkey>,
+    /// Price move vs. the TWAP that pauses curve trading (0 = no breaker)
+    pub circuit_breaker_bps: u16,
+    pub circuit_breaker_window_minutes: u16,
+    pub circuit_breaker_cooldown_minutes: u16,
+    /// Wash score at which a position stops earning rewards (0 = never)
+    pub wash_score_reward_cutoff: u16,
+    /// What happens to buyers whose oracle risk score exceeds `max_risk_score`
+    pub risk_policy: RiskPolicy,
+    pub max_risk_score: u8,
+    /// LP value (as a share of the token position) needed for the LP boost
+    pub lp_boost_coverage_bps: u16,
+    /// Claim boost for holders who also LP, before LP rank scaling (0 = off)
+    pub lp_boost_bps: u16,
+    /// Raise the sale must reach by its deadline, or it can be expired
+    pub soft_cap: u64,
+    /// Days from creation until the soft cap deadline (0 = no deadline)
+    pub sale_duration_days: u16,
+    /// Most reward lamports emitted per epoch; the rest carries (0 = no cap)
+    pub reward_epoch_budget: u64,
+    /// Token-2022 transfer fee on the launch mint, harvested to rewards (0 = none)
+    pub transfer_fee_bps: u16,
+    /// Most a single transfer can be charged, in token base units
+    pub max_transfer_fee: u64,
+    /// Token-2022 display interest rate on the launch mint (0 = none)
+    pub interest_rate_bps: i16,
+    /// Supply reserved for the top holders at graduation (0 = no airdrop)
+    pub graduation_airdrop_bps: u16,
+    /// How many top holders the airdrop can be split between
+    pub graduation_airdrop_recipients: u16,
+    /// Days from a position's first buy before it can claim rewards (0 = none)
+    pub min_hold_before_claim_days: u16,
+    /// Hours after creation when only proven Gold+ holders can buy (0 = none)
+    pub priority_access_hours: u16,
+    /// Launch mint token accounts open frozen until the launch graduates
+    pub freeze_until_graduation: bool,
+    /// Slots since a buyer's attested first signature before it can buy (0 = none)
+    pub min_wallet_age_slots: u64,
+    /// Smallest balance counted as a verified holder
+    pub verified_min_balance: u64,
+    /// Days from first buy before a holder can be counted as verified
+    pub verified_min_hold_days: u16,
+    /// Share of total rank-weighted supply that must vote on a proposal (0 = none)
+    pub governance_quorum_bps: u16,
+    /// Share of the raise paid into the holder reward pot at graduation (0 = none)
+    pub graduation_reward_bps: u16,
+    /// Hours after graduation when sells get the extra graduation grace (0 = none)
+    pub graduation_grace_hours: u16,
+    /// Share of a position sellable on top of `sell_grace_bps` during that window
+    pub graduation_grace_bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct ReviewEntry {
+    pub launch: Pubkey,
+    pub deadline: i64,
+}
+
+/// One report against a flagged wallet: who filed it, when, and where the
+/// underlying data lives (content hash plus optional URI)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct EvidenceEntry {
+    pub timestamp: i64,
+    pub flagger: Pubkey,
+    pub hash: [u8; 32],
+    #[max_len(MAX_EVIDENCE_URI_LEN)]
+    pub uri: String,
+}
+
+/// `check_bundler` return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct BundlerStatus {
+    pub flagged: bool,
+    pub flagged_at: i64,
+    pub incident_count: u32,
+    pub severity: BundlerSeverity,
+}
+
+/// `preview_vesting` return data, all from the allocation owner's side
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VestingPreview {
+    /// Tokens the owner will end up with (less any OTC sale, or capped at
+    /// revocation)
+    pub total: u64,
+    pub released: u64,
+    /// Vested and not yet claimed
+    pub releasable_now: u64,
+    /// Cliff end if still ahead (0 = none)
+    pub next_unlock_at: i64,
+}
+
+/// `preview_apr` return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AprPreview {
+    /// Estimated lamports earned over a year
+    pub yearly_rewards: u64,
+    /// `yearly_rewards` over the position's value (0 = no price yet)
+    pub apr_bps: u64,
+}
+
+/// A referral code's place on a launch's `ReferralBoard`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct ReferralLeader {
+    pub referral_code: Pubkey,
+    pub volume: u64,
+}
+
+/// One holder to open a position for in `seed_positions`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SeedPosition {
+    pub holder: Pubkey,
+    pub balance: u64,
+    /// When the holder first held; ranks count from here
+    pub entry_timestamp: i64,
+}
+
+// ============ Enums ============
+
+/// How firmly a bundler flag is enforced. Confirmed comes first so records
+/// written before severity existed read as confirmed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum BundlerSeverity {
+    Confirmed,
+    /// Claims are cut in half every reward epoch rather than blocked
+    Suspected,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum StakingTier {
+    Public,
+    Bronze,
+    Silver,
+    Gold,
+    Diamond,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum LaunchStatus {
+    Pending,
+    Active,
+    Graduated,
+    Failed,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, InitSpace)]
+pub enum DiamondRank {
+    Paper,
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+    Diamond,
+}
+
+impl DiamondRank {
+    pub const ALL: [DiamondRank; 6] = [
+        DiamondRank::Paper,
+        DiamondRank::Bronze,
+        DiamondRank::Silver,
+        DiamondRank::Gold,
+        DiamondRank::Platinum,
+        DiamondRank::Diamond,
+    ];
+
+    /// Days held to reach each rank on a new launch; holders can vote in
+    /// a different schedule
+    pub const DEFAULT_THRESHOLD_DAYS: [u16; 6] = [0, 7, 30, 60, 90, 180];
+}
+
+/// How sells beyond the grace allowance affect a holder's rank. Sells
+/// within the allowance leave it alone under either policy.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum SellPolicy {
+    /// Restart the hold clock: the entry time moves to the sell and any
+    /// linked wallet's earlier entry is dropped, so the rank and multiplier
+    /// recompute from zero days held (Paper). An LP withdrawal restarts the
+    /// deposit clock the same way. Balance, accrued rewards and the grace
+    /// window carry on as before.
+    ResetToPaper,
+    /// Selling X% of a position moves the entry time X% of the way to now
+    /// and drops any linked wallet's entry
+    ProportionalAging,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+/// Keys the authority can delegate day-to-day powers to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolRole {
+    Moderator,
+    Verifier,
+    Reviewer,
+    Detector,
+    RiskOracle,
+    Adjudicator,
+}
+
+/// Privileged instructions covered by the admin audit trail
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AdminAction {
+    SetProtocolRole(ProtocolRole),
+    SetFlagThreshold,
+    SetMaxSaleExtension,
+    SetCuratedMode,
+    SetTemplateVerified,
+    HaltTrading,
+    ResumeTrading,
+    ApproveLaunch,
+    RejectLaunch,
+    SlashCreatorStake,
+    UpdateWalletRisk,
+    RecordAttestation,
+    RevokeAttestation,
+    FulfillAllocation,
+    FailLaunch,
+    GraduateLaunch,
+    SweepUnclaimed,
+    FlagBundler,
+    AddFlagger,
+    RemoveFlagger,
+    AddRouter,
+    RemoveRouter,
+    AddTradeReporter,
+    RemoveTradeReporter,
+    SetInterestRate,
+    CommitGraduationAirdrop,
+    StartSeason,
+    SetGaugeEmissions,
+    AppointCouncilMember,
+    ReplaceCouncilMember,
+    SetPositionRateLimit,
+    SetLoyaltyDiscounts,
+    SetListingDeposit,
+    FlagLaunchSpam,
+    SetLegacyEventWindow,
+    ScheduleFeatureFlags,
+    ApplyFeatureFlags,
+    AssignLaunchModerator,
+    RemoveLaunchModerator,
+    SetDailyRewardCap,
+    SetPositionGc,
+    AttestWalletAge,
+    PauseClaims,
+    ResumeClaims,
+    RegisterChannel,
+    SetChannelFeeShare,
+    RevokeVesting,
+    OpenInsuranceClaims,
+    PayLargeInsuranceClaim,
+    CancelFlag,
+}
+
+/// What a `HolderNotification` is about
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    RankUp,
+    VestingUnlock,
+    Graduation,
+    RewardsAvailable,
+}
+
+/// A before/after value in the admin audit trail
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AuditValue {
+    None,
+    Bool(bool),
+    Amount(u64),
+    Key(Pubkey),
+    Timestamp(i64),
+}
+
+/// What a holder's position must show to complete a quest
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum QuestCondition {
+    /// Hold at least `min_balance` without a rank reset for `days`
+    HoldFor { min_balance: u64, days: u16 },
+    /// Reach `rank` before the launch graduates
+    ReachRankBeforeGraduation { rank: DiamondRank },
+}
+
+/// What a passed launch proposal does when executed. Only actions the
+/// program already lets a creator take in holders' favor are allowed,
+/// plus the rank schedule and an interest-bearing mint's rate, which only
+/// holders can change, and the protocol's feature flags on the protocol
+/// token's launch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ProposalAction {
+    /// Signalling only
+    None,
+    ExtendLpLock { new_unlock_timestamp: i64 },
+    RaiseHolderRewards { holder_rewards_bps: u16 },
+    /// Days held to reach each rank; existing positions pick it up through
+    /// `recalculate_rank` or their next transaction
+    SetRankThresholds { threshold_days: [u16; 6] },
+    /// Schedule the protocol's switched-off FEATURE_* bits; only on the
+    /// protocol token's own launch
+    ScheduleFeatureFlags { disabled_features: u32 },
+    /// Move an interest-bearing launch mint's rate, within
+    /// MAX_INTEREST_RATE_BPS
+    SetInterestRate { rate_bps: i16 },
+}
+
+/// Profile achievements, each unlocked once against on-chain evidence
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Achievement {
+    /// Reached Diamond rank on a position still held
+    FirstDiamond,
+    /// Kept holding through a 50% fall from the peak since entry
+    DrawdownSurvivor,
+    /// Held positions in five launches that graduated
+    GraduationVeteran,
+}
+
+impl Achievement {
+    pub fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// What a holder receives when claiming rewards
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RewardDenomination {
+    /// Reward lamports as-is
+    Quote,
+    /// Launch tokens bought through the curve with the reward lamports
+    Token,
+}
+
+/// How a launch treats buyers the risk oracle scores above its threshold
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum RiskPolicy {
+    Ignore,
+    /// Refuse the buy
+    Reject,
+    /// Allow the buy but cap the position's multiplier at Paper
+    Deboost,
+}
+
+/// Where an insurance claim stands
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum InsuranceClaimStatus {
+    Pending,
+    Approved,
+    Denied,
+    Paid,
+}
+
+/// Where a launch stands in the curated review track
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ReviewStatus {
+    NotRequired,
+    Pending,
+    Approved,
+}
+
+/// Launch tier unlocked by creator stake
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum LaunchTier {
+    Standard,
+    Silver,
+    Gold,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AttestationKind {
+    Twitter,
+    Domain,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AllocationPool {
+    Guaranteed,
+    WeightedLottery,
+    PublicLottery,
+    FCFS,
+    Flipper,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AllocationStatus {
+    Pending,
+    Won,
+    Lost,
+    Claimed,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum OtcSaleStatus {
+    Listed,
+    Filled,
+}
+
+// ============ Events ============
+//
+// Every event carries `schema_version`. Fields are only ever appended, so a
+// parser built for an older version still reads the prefix it knows; events
+// that were renamed or reshaped also go out in their legacy form under
+// "Legacy Events" until `Protocol::legacy_events_until`.
+
+#[event]
+pub struct Staked {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub lock_days: u16,
+    pub tier: StakingTier,
+    pub total_staked: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct Unstaked {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub return_amount: u64,
+    pub penalty_amount: u64,
+    pub early: bool,
+    pub remaining_stake: u64,
+    pub new_tier: StakingTier,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct GaugeVoted {
+    pub voter: Pubkey,
+    pub launch: Pubkey,
+    pub epoch: u64,
+    pub weight: u64,
+    pub launch_votes: u64,
+    pub total_votes: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct GaugeEmissionsDistributed {
+    pub launch: Pubkey,
+    pub epoch: u64,
+    pub votes: u64,
+    pub total_votes: u64,
+    pub amount: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct ProtocolRoleUpdated {
+    pub role: ProtocolRole,
+    pub previous: Pubkey,
+    pub new: Pubkey,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct CuratedModeUpdated {
+    pub enabled: bool,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchCreated {
+    pub launch_id: u64,
+    pub creator: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub total_supply: u64,
+    pub dev_allocation_bps: u16,
+    pub dev_vesting_days: u16,
+    pub template: Pubkey,
+    pub schema_version: u8,
+    /// The launch account, which only protocol-numbered ids derive from
+    pub launch: Pubkey,
+}
+
+#[event]
+pub struct LaunchCloned {
+    pub launch_id: u64,
+    pub source_launch_id: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct TemplatePublished {
+    pub template: Pubkey,
+    pub publisher: Pubkey,
+    pub name: String,
+    pub verified: bool,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct TemplateVerificationUpdated {
+    pub template: Pubkey,
+    pub verified: bool,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct QualityScoreRefreshed {
+    pub launch: Pubkey,
+    pub score: u8,
+    pub components: QualityScore,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct CreatorChecklistUpdated {
+    pub launch: Pubkey,
+    /// CHECKLIST_* steps done so far
+    pub completed: u8,
+    pub newly_completed: u8,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LpLockExtended {
+    pub launch_id: u64,
+    pub creator: Pubkey,
+    pub previous_unlock_timestamp: i64,
+    pub new_unlock_timestamp: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchTermsUpdated {
+    pub launch_id: u64,
+    pub reward_fee_share_bps: u16,
+    pub dev_vesting_days: u16,
+    pub sell_policy: SellPolicy,
+    pub sell_grace_bps: u16,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchConfigFrozen {
+    pub launch_id: u64,
+    pub frozen_params: u16,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LpLockBurned {
+    pub launch_id: u64,
+    pub creator: Pubkey,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct HolderRewardsRaised {
+    pub launch_id: u64,
+    pub previous_bps: u16,
+    pub holder_rewards_bps: u16,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct RelockIncentiveGranted {
+    pub launch_id: u64,
+    pub bonus_bps: u16,
+    pub holder_rewards_bps: u16,
+    pub fee_rebate_bps: u16,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct InsiderDeclared {
+    pub launch_id: u64,
+    pub wallet: Pubkey,
+    pub insider_count: u8,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchTradingHalted {
+    pub launch_id: u64,
+    pub moderator: Pubkey,
+    pub reason_code: u8,
+    pub timestamp: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchTradingResumed {
+    pub launch_id: u64,
+    pub moderator: Pubkey,
+    pub reason_code: u8,
+    pub halted_for_seconds: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchClaimsPaused {
+    pub launch_id: u64,
+    pub moderator: Pubkey,
+    pub reason_code: u8,
+    pub timestamp: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchClaimsResumed {
+    pub launch_id: u64,
+    pub moderator: Pubkey,
+    pub paused_for_seconds: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchSpamFlagged {
+    pub launch_id: u64,
+    pub moderator: Pubkey,
+    pub listing_deposit: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct ListingDepositSettled {
+    pub launch_id: u64,
+    pub creator: Pubkey,
+    pub refunded: u64,
+    pub forfeited: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchSubmittedForReview {
+    pub launch_id: u64,
+    pub launch: Pubkey,
+    pub deadline: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchApproved {
+    pub launch_id: u64,
+    pub reviewer: Pubkey,
+    pub timestamp: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchRejected {
+    pub launch_id: u64,
+    pub reviewer: Pubkey,
+    pub reason_code: u8,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct WashScoreUpdated {
+    pub launch_id: u64,
+    pub holder: Pubkey,
+    pub rapid_flips: u32,
+    pub wash_score: u16,
+    pub rewards_forfeited: bool,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct CircuitBreakerTripped {
+    pub launch_id: u64,
+    pub reference_price: u64,
+    pub price: u64,
+    pub move_bps: u64,
+    pub resumes_at: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct CreatorStakeUpdated {
+    pub creator: Pubkey,
+    pub sol_staked: u64,
+    pub dpad_staked: u64,
+    pub tier: LaunchTier,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct Slashed {
+    pub creator: Pubkey,
+    pub launch_id: u64,
+    pub insurance_vault: Pubkey,
+    pub sol_slashed: u64,
+    pub dpad_slashed: u64,
+    /// Listing deposit moved into the insurance vault
+    pub deposit_forfeited: u64,
+    pub times_slashed: u32,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchTierAssigned {
+    pub launch_id: u64,
+    pub tier: LaunchTier,
+    pub hard_cap: u64,
+    pub featured: bool,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct WalletRiskUpdated {
+    pub wallet: Pubkey,
+    pub score: u8,
+    pub categories: u32,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct WalletAgeAttested {
+    pub wallet: Pubkey,
+    pub first_signature_slot: u64,
+    pub funded_by: Pubkey,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct AttestationRecorded {
+    pub creator: Pubkey,
+    pub verifier: Pubkey,
+    pub kind: AttestationKind,
+    pub handle_hash: [u8; 32],
+    pub verified_at: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct AttestationRevoked {
+    pub creator: Pubkey,
+    pub verifier: Pubkey,
+    pub kind: AttestationKind,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct AllocationRequested {
+    pub owner: Pubkey,
+    pub launch_id: u64,
+    pub pool: AllocationPool,
+    pub amount_usd: u64,
+    pub weight: u16,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct AllocationFulfilled {
+    pub owner: Pubkey,
+    pub launch: Pubkey,
+    pub allocated_tokens: u64,
+    pub status: AllocationStatus,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct AllocationClaimed {
+    pub owner: Pubkey,
+    pub launch: Pubkey,
+    pub claimed: u64,
+    pub total_claimed: u64,
+    pub remaining: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub owner: Pubkey,
+    pub launch: Pubkey,
+    pub vested: u64,
+    pub forfeited: u64,
+    pub schema_version: u8,
+    pub otc_refunded: u64,
+}
+
+#[event]
+pub struct VestingSummaryUpdated {
+    pub owner: Pubkey,
+    pub schedule_count: u32,
+    pub total_locked: u64,
+    pub total_released: u64,
+    pub next_unlock_at: i64,
+    pub fully_vested_at: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct PositionUpdated {
+    pub holder: Pubkey,
+    pub launch: Pubkey,
+    pub balance: u64,
+    pub diamond_rank: DiamondRank,
+    pub multiplier_bps: u16,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct HolderStatsUpdated {
+    pub owner: Pubkey,
+    pub launches_participated: u32,
+    pub total_rewards_claimed: u64,
+    pub best_rank: DiamondRank,
+    pub longest_hold_seconds: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct AchievementUnlocked {
+    pub owner: Pubkey,
+    pub achievement: Achievement,
+    pub achievements: u32,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct UsernameUpdated {
+    pub name: String,
+    /// Profile now holding the name (default = released)
+    pub profile: Pubkey,
+    pub previous_profile: Pubkey,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct SeasonStarted {
+    pub season_id: u32,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct SeasonJoined {
+    pub season_id: u32,
+    pub wallet: Pubkey,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct WalletLinked {
+    pub profile: Pubkey,
+    pub owner: Pubkey,
+    pub wallet: Pubkey,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct WalletUnlinked {
+    pub profile: Pubkey,
+    pub wallet: Pubkey,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct RecoveryUpdated {
+    pub holder: Pubkey,
+    pub launch: Pubkey,
+    pub recovery: Pubkey,
+    pub effective_at: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct PositionRecovered {
+    pub launch: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub balance: u64,
+    pub accrued_rewards: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct PositionGifted {
+    pub launch_id: u64,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct TokensBought {
+    pub launch_id: u64,
+    pub buyer: Pubkey,
+    pub quote_amount: u64,
+    pub tokens_out: u64,
+    pub fee: u64,
+    pub reward_fee: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub schema_version: u8,
+    pub fee_discount_bps: u16,
+}
+
+#[event]
+pub struct TokensSold {
+    pub launch_id: u64,
+    pub seller: Pubkey,
+    pub token_amount: u64,
+    pub quote_out: u64,
+    pub fee: u64,
+    pub reward_fee: u64,
+    pub rank_reset: bool,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct RewardsSwapped {
+    pub launch_id: u64,
+    pub holder: Pubkey,
+    /// Reward lamports claimed and fed into the swap
+    pub claimed: u64,
+    pub output_mint: Pubkey,
+    pub received: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct RewardEpochClosed {
+    pub launch_id: u64,
+    /// First epoch closed by this roll
+    pub epoch: u64,
+    pub epochs_closed: u64,
+    pub emitted: u64,
+    pub carried_over: u64,
+    pub total_reward_weight: u128,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct GasSponsorshipUpdated {
+    pub launch_id: u64,
+    pub refund_per_claim: u64,
+    pub cap_per_epoch: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct GasPoolFunded {
+    pub launch_id: u64,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct ClaimGasRefunded {
+    pub launch_id: u64,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub refunded_in_epoch: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub launch_id: u64,
+    pub holder: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub denomination: RewardDenomination,
+    /// Launch tokens bought with `amount` for token-denominated claims
+    pub tokens_out: u64,
+    pub multiplier_bps: u16,
+    pub lp_boost_bps: u16,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchGraduated {
+    pub launch_id: u64,
+    pub lp_mint: Pubkey,
+    pub total_raised: u64,
+    pub tokens_sold: u64,
+    pub timestamp: i64,
+    pub schema_version: u8,
+    /// Raised SOL moved into the holder reward pot
+    pub reward_topup: u64,
+}
+
+#[event]
+pub struct QuestCreated {
+    pub quest: Pubkey,
+    pub launch: Pubkey,
+    pub creator: Pubkey,
+    pub condition: QuestCondition,
+    pub reward: u64,
+    pub budget: u64,
+    pub expires_at: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct QuestCompleted {
+    pub quest: Pubkey,
+    pub holder: Pubkey,
+    pub reward: u64,
+    pub budget_remaining: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct QuestClosed {
+    pub quest: Pubkey,
+    pub completions: u32,
+    pub refunded: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct GraduationAirdropCommitted {
+    pub launch_id: u64,
+    pub root: [u8; 32],
+    pub recipients: u16,
+    pub reserve: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct GraduationAirdropClaimed {
+    pub launch_id: u64,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LiquidityPositionUpdated {
+    pub provider: Pubkey,
+    pub launch: Pubkey,
+    pub lp_amount: u64,
+    pub diamond_rank: DiamondRank,
+    pub multiplier_bps: u16,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct TransferFeesHarvested {
+    pub launch_id: u64,
+    pub amount: u64,
+    pub total_harvested: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct SaleExtended {
+    pub launch_id: u64,
+    pub previous_end_time: i64,
+    pub new_end_time: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchFailed {
+    pub launch_id: u64,
+    pub total_raised: u64,
+    pub refund_deadline: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub launch_id: u64,
+    pub contributor: Pubkey,
+    pub amount: u64,
+    pub total_refunded: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct UnclaimedRefundsSwept {
+    pub launch_id: u64,
+    pub treasury: Pubkey,
+    pub amount: u64,
+    pub total_refunded: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct CouncilMemberAppointed {
+    pub wallet: Pubkey,
+    pub term_ends_at: i64,
+    pub member_count: u8,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct CouncilMemberReplaced {
+    pub previous: Pubkey,
+    pub wallet: Pubkey,
+    pub term_ends_at: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchProposalCreated {
+    pub proposal: Pubkey,
+    pub launch: Pubkey,
+    pub proposer: Pubkey,
+    pub proposal_id: u32,
+    pub description_hash: [u8; 32],
+    pub voting_ends_at: i64,
+    pub schema_version: u8,
+    pub action: ProposalAction,
+    pub quorum_weight: u128,
+}
+
+#[event]
+pub struct RankThresholdsUpdated {
+    pub launch_id: u64,
+    pub previous_threshold_days: [u16; 6],
+    pub threshold_days: [u16; 6],
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchProposalExecuted {
+    pub proposal: Pubkey,
+    pub launch: Pubkey,
+    pub action: ProposalAction,
+    pub votes_for: u128,
+    pub votes_against: u128,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchVoteCast {
+    pub proposal: Pubkey,
+    pub holder: Pubkey,
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u128,
+    pub votes_for: u128,
+    pub votes_against: u128,
+    pub schema_version: u8,
 }
 
-fn get_tier_weight(tier: StakingTier) -> u16 {
-    match tier {
-        StakingTier::Diamond => 1000,  // 10x
-        StakingTier::Gold => 500,      // 5x
-        StakingTier::Silver => 250,    // 2.5x
-        StakingTier::Bronze => 100,    // 1x
-        StakingTier::Public => 25,     // 0.25x
-    }
+#[event]
+pub struct VotesDelegated {
+    pub launch: Pubkey,
+    pub holder: Pubkey,
+    pub delegate: Pubkey,
+    pub schema_version: u8,
 }
 
-fn calculate_diamond_rank(first_buy: i64, now: i64) -> DiamondRank {
-    let days_held = (now - first_buy) / 86400;
-    
-    if days_held >= 180 { DiamondRank::Diamond }
-    else if days_held >= 90 { DiamondRank::Platinum }
-    else if days_held >= 60 { DiamondRank::Gold }
-    else if days_held >= 30 { DiamondRank::Silver }
-    else if days_held >= 7 { DiamondRank::Bronze }
-    else { DiamondRank::Paper }
+#[event]
+pub struct VoteDelegationRevoked {
+    pub launch: Pubkey,
+    pub holder: Pubkey,
+    pub delegate: Pubkey,
+    pub schema_version: u8,
 }
 
-fn get_diamond_multiplier_bps(rank: DiamondRank) -> u16 {
-    match rank {
-        DiamondRank::Paper => 10000,
-        DiamondRank::Bronze => 15000,
-        DiamondRank::Silver => 20000,
-        DiamondRank::Gold => 25000,
-        DiamondRank::Platinum => 30000,
-        DiamondRank::Diamond => 35000,
-    }
+#[event]
+pub struct InsuranceFunded {
+    pub launch: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub total_funded: u64,
+    pub schema_version: u8,
 }
 
-fn calculate_vested_amount(
-    total: u64,
-    start: i64,
-    cliff_days: u16,
-    duration_days: u16,
-    tge_bps: u16,
-    now: i64,
-) -> u64 {
-    let tge_amount = total.checked_mul(tge_bps as u64).unwrap() / 10000;
-    let vesting_amount = total.checked_sub(tge_amount).unwrap();
-    
-    let elapsed = now - start;
-    let cliff_seconds = cliff_days as i64 * 86400;
-    let duration_seconds = duration_days as i64 * 86400;
-    
-    if elapsed < cliff_seconds {
-        return tge_amount;
-    }
-    
-    let vesting_elapsed = elapsed - cliff_seconds;
-    if vesting_elapsed >= duration_seconds {
-        return total;
-    }
-    
-    let vested = vesting_amount
-        .checked_mul(vesting_elapsed as u64).unwrap()
-        .checked_div(duration_seconds as u64).unwrap();
-    
-    tge_amount.checked_add(vested).unwrap()
+#[event]
+pub struct InsuranceClaimsOpened {
+    pub launch: Pubkey,
+    pub claims_deadline: i64,
+    pub schema_version: u8,
 }
 
-// ============ Account Contexts ============
+#[event]
+pub struct InsuranceClaimFiled {
+    pub launch: Pubkey,
+    pub holder: Pubkey,
+    pub balance: u64,
+    pub diamond_rank: DiamondRank,
+    pub schema_version: u8,
+}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    #[account(
-        init,
-        payer = authority,
-        space = Protocol::SIZE,
-        seeds = [b"protocol"],
-        bump
-    )]
-    pub protocol: Account<'info, Protocol>,
-    
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct InsuranceClaimAdjudicated {
+    pub launch: Pubkey,
+    pub holder: Pubkey,
+    pub approved: bool,
+    pub balance: u64,
+    pub schema_version: u8,
 }
 
-#[derive(Accounts)]
-pub struct Stake<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    
-    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
-    pub protocol: Account<'info, Protocol>,
-    
-    #[account(
-        init_if_needed,
-        payer = owner,
-        space = StakerAccount::SIZE,
-        seeds = [b"staker", owner.key().as_ref()],
-        bump
-    )]
-    pub staker_account: Account<'info, StakerAccount>,
-    
-    #[account(mut)]
-    pub staker_token_account: Account<'info, TokenAccount>,
-    
-    #[account(mut, seeds = [b"vault"], bump)]
-    pub vault: Account<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct InsurancePaid {
+    pub launch: Pubkey,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub dpad_amount: u64,
+    pub total_paid: u64,
+    pub schema_version: u8,
 }
 
-#[derive(Accounts)]
-pub struct Unstake<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    
-    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
-    pub protocol: Account<'info, Protocol>,
-    
-    #[account(
-        mut,
-        seeds = [b"staker", owner.key().as_ref()],
-        bump = staker_account.bump,
-        constraint = staker_account.owner == owner.key()
-    )]
-    pub staker_account: Account<'info, StakerAccount>,
-    
-    #[account(mut)]
-    pub staker_token_account: Account<'info, TokenAccount>,
-    
-    #[account(mut, seeds = [b"vault"], bump)]
-    pub vault: Account<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token>,
+#[event]
+pub struct AdminActionAudited {
+    pub actor: Pubkey,
+    pub action: AdminAction,
+    pub target: Pubkey,
+    pub old_value: AuditValue,
+    pub new_value: AuditValue,
+    pub timestamp: i64,
+    pub schema_version: u8,
 }
 
-#[derive(Accounts)]
-#[instruction(name: String, symbol: String)]
-pub struct CreateLaunch<'info> {
-    #[account(mut)]
-    pub creator: Signer<'info>,
-    
-    #[account(mut, seeds = [b"protocol"], bump = protocol.bump)]
-    pub protocol: Account<'info, Protocol>,
-    
-    #[account(
-        init,
-        payer = creator,
-        space = Launch::SIZE,
-        seeds = [b"launch", protocol.total_launches.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub launch: Account<'info, Launch>,
-    
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct ProgramImmutabilityVerified {
+    pub immutable: bool,
+    pub upgrade_authority: Option<Pubkey>,
+    pub verified_at: i64,
+    pub schema_version: u8,
 }
 
-#[derive(Accounts)]
-pub struct RequestAllocation<'info> {
-    #[account(mut)]
-    pub requester: Signer<'info>,
-    
-    pub launch: Account<'info, Launch>,
-    
-    #[account(seeds = [b"staker", requester.key().as_ref()], bump = staker_account.bump)]
-    pub staker_account: Account<'info, StakerAccount>,
-    
-    #[account(
-        init,
-        payer = requester,
-        space = Allocation::SIZE,
-        seeds = [b"allocation", launch.key().as_ref(), requester.key().as_ref()],
-        bump
-    )]
-    pub allocation: Account<'info, Allocation>,
-    
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct FlagThresholdUpdated {
+    pub previous: u8,
+    pub new: u8,
+    pub schema_version: u8,
 }
 
-#[derive(Accounts)]
-pub struct FulfillAllocation<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    #[account(seeds = [b"protocol"], bump = protocol.bump)]
-    pub protocol: Account<'info, Protocol>,
-    
-    #[account(mut)]
-    pub allocation: Account<'info, Allocation>,
+#[event]
+pub struct ChannelUpdated {
+    pub channel: Pubkey,
+    pub partner: Pubkey,
+    pub fee_share_bps: u16,
+    pub schema_version: u8,
 }
 
-#[derive(Accounts)]
-pub struct ClaimAllocation<'info> {
-    pub claimer: Signer<'info>,
-    
-    #[account(
-        mut,
-        constraint = allocation.owner == claimer.key()
-    )]
-    pub allocation: Account<'info, Allocation>,
+#[event]
+pub struct LaunchAttributedToChannel {
+    pub launch_id: u64,
+    pub channel: Pubkey,
+    pub partner: Pubkey,
+    pub launches_attributed: u64,
+    pub schema_version: u8,
 }
 
-#[derive(Accounts)]
-pub struct RecordPosition<'info> {
-    #[account(mut)]
-    pub holder: Signer<'info>,
-    
-    #[account(mut)]
-    pub launch: Account<'info, Launch>,
-    
-    #[account(
-        init_if_needed,
-        payer = holder,
-        space = Position::SIZE,
-        seeds = [b"position", launch.key().as_ref(), holder.key().as_ref()],
-        bump
-    )]
-    pub position: Account<'info, Position>,
-    
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct ChannelFeesClaimed {
+    pub channel: Pubkey,
+    pub partner: Pubkey,
+    pub amount: u64,
+    pub total_claimed: u64,
+    pub schema_version: u8,
 }
 
-#[derive(Accounts)]
-pub struct FlagBundler<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    #[account(
-        mut,
-        seeds = [b"protocol"],
-        bump = protocol.bump,
-        constraint = protocol.authority == authority.key()
-    )]
-    pub protocol: Account<'info, Protocol>,
-    
-    /// CHECK: Wallet being flagged
-    pub flagged_wallet: UncheckedAccount<'info>,
-    
-    #[account(
-        init,
-        payer = authority,
-        space = Bundler::SIZE,
-        seeds = [b"bundler", flagged_wallet.key().as_ref()],
-        bump
-    )]
-    pub bundler: Account<'info, Bundler>,
-    
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct RouterUpdated {
+    pub program: Pubkey,
+    pub active: bool,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct TradeReporterUpdated {
+    pub key: Pubkey,
+    pub active: bool,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct DexTradeReported {
+    pub reporter: Pubkey,
+    pub launch: Pubkey,
+    pub holder: Pubkey,
+    pub side: TradeSide,
+    pub token_amount: u64,
+    pub quote_amount: u64,
+    pub schema_version: u8,
 }
 
-// ============ State Accounts ============
+#[event]
+pub struct FlaggerUpdated {
+    pub key: Pubkey,
+    pub active: bool,
+    pub schema_version: u8,
+}
 
-#[account]
-pub struct Protocol {
-    pub authority: Pubkey,
-    pub launch_token_mint: Pubkey,
-    pub total_launches: u64,
-    pub total_stakers: u64,
-    pub total_staked: u64,
-    pub total_bundlers_caught: u64,
-    pub early_unstake_penalty_bps: u16,
-    pub bump: u8,
+#[event]
+pub struct FlagProposed {
+    pub wallet: Pubkey,
+    pub proposer: Pubkey,
+    pub threshold: u8,
+    pub schema_version: u8,
 }
 
-impl Protocol {
-    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 2 + 1 + 64;
+#[event]
+pub struct FlagCosigned {
+    pub wallet: Pubkey,
+    pub flagger: Pubkey,
+    pub approvals: u8,
+    pub threshold: u8,
+    pub schema_version: u8,
 }
 
-#[account]
-pub struct StakerAccount {
-    pub owner: Pubkey,
-    pub staked_amount: u64,
-    pub staked_at: i64,
-    pub lock_end_timestamp: i64,
-    pub tier: StakingTier,
-    pub strong_holder_score: u16,
-    pub total_allocations_received: u32,
-    pub last_update_timestamp: i64,
-    pub bump: u8,
+#[event]
+pub struct FlagCancelled {
+    pub wallet: Pubkey,
+    pub cancelled_by: Pubkey,
+    pub expired: bool,
+    pub approvals: u8,
+    pub schema_version: u8,
 }
 
-impl StakerAccount {
-    pub const SIZE: usize = 8 + 32 + 8 + 8 + 8 + 1 + 2 + 4 + 8 + 1 + 64;
+#[event]
+pub struct DetectionReportAccepted {
+    pub wallet: Pubkey,
+    pub detector: Pubkey,
+    pub evidence_hash: [u8; 32],
+    pub reported_at: i64,
+    pub schema_version: u8,
 }
 
-#[account]
-pub struct Launch {
-    pub creator: Pubkey,
-    pub name: String,
-    pub symbol: String,
-    pub total_supply: u64,
-    pub dev_allocation_bps: u16,
-    pub dev_vesting_days: u16,
-    pub lp_lock_days: u16,
-    pub holder_rewards_bps: u16,
-    pub created_at: i64,
-    pub launch_id: u64,
-    pub status: LaunchStatus,
-    pub total_raised: u64,
-    pub holder_count: u64,
-    // Allocation pools
-    pub guaranteed_pool_bps: u16,
-    pub lottery_pool_bps: u16,
-    pub public_pool_bps: u16,
-    pub fcfs_pool_bps: u16,
-    pub flipper_pool_bps: u16,
-    pub liquidity_pool_bps: u16,
-    pub trader_rewards_pool_bps: u16,
-    pub bump: u8,
+#[event]
+pub struct BundlerFlagged {
+    pub wallet: Pubkey,
+    pub evidence: EvidenceEntry,
+    pub schema_version: u8,
+    pub severity: BundlerSeverity,
 }
 
-impl Launch {
-    pub const SIZE: usize = 8 + 32 + 36 + 14 + 8 + 2 + 2 + 2 + 2 + 8 + 8 + 1 + 8 + 8 + 2 + 2 + 2 + 2 + 2 + 2 + 2 + 1 + 64;
+#[event]
+pub struct EvidenceAdded {
+    pub wallet: Pubkey,
+    pub evidence: EvidenceEntry,
+    pub incident_count: u32,
+    pub schema_version: u8,
 }
 
-#[account]
-pub struct Allocation {
-    pub owner: Pubkey,
+#[event]
+pub struct ReferralQualified {
+    pub code: String,
+    pub referred: Pubkey,
     pub launch: Pubkey,
-    pub pool: AllocationPool,
-    pub requested_amount_usd: u64,
-    pub allocated_tokens: u64,
-    pub weight: u16,
-    pub status: AllocationStatus,
-    pub requested_at: i64,
-    pub vesting_start: i64,
-    pub vesting_cliff_days: u16,
-    pub vesting_duration_days: u16,
-    pub tge_unlock_bps: u16,
-    pub tokens_claimed: u64,
-    pub bump: u8,
+    pub qualified_referrals: u32,
+    pub schema_version: u8,
+    pub launch_qualified_referrals: u32,
 }
 
-impl Allocation {
-    pub const SIZE: usize = 8 + 32 + 32 + 1 + 8 + 8 + 2 + 1 + 8 + 8 + 2 + 2 + 2 + 8 + 1 + 64;
+#[event]
+pub struct ReferralCodeRegistered {
+    pub code: String,
+    pub owner: Pubkey,
+    pub payout_wallet: Pubkey,
+    pub schema_version: u8,
 }
 
-#[account]
-pub struct Position {
+#[event]
+pub struct SellRecorded {
+    pub launch_id: u64,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub rank_reset: bool,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct PositionReconciled {
+    pub launch_id: u64,
     pub holder: Pubkey,
+    /// Balance the position claimed before reconciling
+    pub recorded: u64,
+    pub actual: u64,
+    pub rank_reset: bool,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct FeatureFlagsScheduled {
+    pub current: u32,
+    pub pending: u32,
+    pub effective_at: i64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LaunchModeratorUpdated {
+    pub launch_id: u64,
+    pub moderator: Pubkey,
+    pub active: bool,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct OtcSaleListed {
     pub launch: Pubkey,
-    pub balance: u64,
-    pub first_buy_timestamp: i64,
-    pub last_activity_timestamp: i64,
-    pub last_claim_timestamp: i64,
-    pub diamond_rank: DiamondRank,
-    pub multiplier_bps: u16,
-    pub total_rewards_claimed: u64,
-    pub bump: u8,
+    pub allocation: Pubkey,
+    pub seller: Pubkey,
+    /// Default when the offer is open to anyone
+    pub buyer: Pubkey,
+    pub tokens: u64,
+    pub price: u64,
+    pub schema_version: u8,
 }
 
-impl Position {
-    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 2 + 8 + 1 + 64;
+#[event]
+pub struct OtcSaleCancelled {
+    pub launch: Pubkey,
+    pub allocation: Pubkey,
+    pub seller: Pubkey,
+    pub schema_version: u8,
 }
 
-#[account]
-pub struct Bundler {
-    pub wallet: Pubkey,
-    pub flagged_at: i64,
-    pub evidence: String,
-    pub incident_count: u32,
-    pub bump: u8,
+#[event]
+pub struct OtcSaleFilled {
+    pub launch: Pubkey,
+    pub allocation: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub tokens: u64,
+    pub price: u64,
+    pub schema_version: u8,
 }
 
-impl Bundler {
-    pub const SIZE: usize = 8 + 32 + 8 + 256 + 4 + 1 + 64;
+#[event]
+pub struct OtcTokensClaimed {
+    pub launch: Pubkey,
+    pub allocation: Pubkey,
+    pub buyer: Pubkey,
+    pub claimed: u64,
+    pub total_claimed: u64,
+    pub schema_version: u8,
 }
 
-// ============ Enums ============
+#[event]
+pub struct OtcProceedsWithdrawn {
+    pub launch: Pubkey,
+    pub allocation: Pubkey,
+    pub seller: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+    pub schema_version: u8,
+}
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum StakingTier {
-    Public,
-    Bronze,
-    Silver,
-    Gold,
-    Diamond,
+#[event]
+pub struct LaunchTransfersThawed {
+    pub launch_id: u64,
+    pub accounts_thawed: u32,
+    pub schema_version: u8,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum LaunchStatus {
-    Pending,
-    Active,
-    Graduated,
-    Failed,
+#[event]
+pub struct PositionCollected {
+    pub launch_id: u64,
+    pub holder: Pubkey,
+    /// Dust balance dropped with the position
+    pub balance: u64,
+    pub cranker: Pubkey,
+    pub cranker_share: u64,
+    pub schema_version: u8,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum DiamondRank {
-    Paper,
-    Bronze,
-    Silver,
-    Gold,
-    Platinum,
-    Diamond,
+#[event]
+pub struct PositionRankRecalculated {
+    pub launch_id: u64,
+    pub holder: Pubkey,
+    pub previous_rank: DiamondRank,
+    pub rank: DiamondRank,
+    pub multiplier_bps: u16,
+    pub schema_version: u8,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum AllocationPool {
-    Guaranteed,
-    WeightedLottery,
-    PublicLottery,
-    FCFS,
-    Flipper,
+#[event]
+pub struct LaunchUpgraded {
+    pub launch: Pubkey,
+    pub launch_id: u64,
+    pub schema_version: u8,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum AllocationStatus {
-    Pending,
-    Won,
-    Lost,
-    Claimed,
+#[event]
+pub struct PositionUpgraded {
+    pub launch: Pubkey,
+    pub holder: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub schema_version: u8,
 }
 
-// ============ Events ============
+#[event]
+pub struct VerifiedHolderUpdated {
+    pub launch_id: u64,
+    pub holder: Pubkey,
+    pub verified: bool,
+    pub verified_holder_count: u64,
+    pub schema_version: u8,
+}
 
 #[event]
-pub struct Staked {
-    pub owner: Pubkey,
+pub struct SaleTokensClaimed {
+    pub launch_id: u64,
+    pub contributor: Pubkey,
     pub amount: u64,
-    pub lock_days: u16,
-    pub tier: StakingTier,
-    pub total_staked: u64,
+    pub claim_deadline: i64,
+    pub schema_version: u8,
 }
 
 #[event]
-pub struct Unstaked {
-    pub owner: Pubkey,
+pub struct UnclaimedAllocationSwept {
+    pub launch_id: u64,
+    pub contributor: Pubkey,
     pub amount: u64,
-    pub return_amount: u64,
-    pub penalty_amount: u64,
-    pub early: bool,
-    pub remaining_stake: u64,
-    pub new_tier: StakingTier,
+    pub total_swept: u64,
+    pub schema_version: u8,
 }
 
 #[event]
-pub struct LaunchCreated {
+pub struct PositionsSeeded {
     pub launch_id: u64,
-    pub creator: Pubkey,
-    pub name: String,
-    pub symbol: String,
-    pub total_supply: u64,
-    pub dev_allocation_bps: u16,
-    pub dev_vesting_days: u16,
+    pub count: u16,
+    pub total_balance: u64,
+    pub schema_version: u8,
 }
 
 #[event]
-pub struct AllocationRequested {
-    pub owner: Pubkey,
+pub struct PositionImportCommitted {
     pub launch_id: u64,
-    pub pool: AllocationPool,
-    pub amount_usd: u64,
-    pub weight: u16,
+    pub root: [u8; 32],
+    pub schema_version: u8,
 }
 
 #[event]
-pub struct AllocationFulfilled {
-    pub owner: Pubkey,
-    pub launch: Pubkey,
-    pub allocated_tokens: u64,
-    pub status: AllocationStatus,
+pub struct PositionImported {
+    pub launch_id: u64,
+    pub holder: Pubkey,
+    pub balance: u64,
+    pub hold_start: i64,
+    pub diamond_rank: DiamondRank,
+    pub schema_version: u8,
 }
 
 #[event]
-pub struct AllocationClaimed {
-    pub owner: Pubkey,
-    pub launch: Pubkey,
-    pub claimed: u64,
-    pub total_claimed: u64,
-    pub remaining: u64,
+pub struct ReferralBonusFunded {
+    pub launch_id: u64,
+    pub amount: u64,
+    pub bonus_pool: u64,
+    pub schema_version: u8,
 }
 
 #[event]
-pub struct PositionUpdated {
+pub struct ReferralBonusClaimed {
+    pub launch_id: u64,
+    pub referral_code: Pubkey,
+    pub referred_volume: u64,
+    pub amount: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct ReferralBonusReclaimed {
+    pub launch_id: u64,
+    pub amount: u64,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LoyaltySnapshotCommitted {
+    pub launch_id: u64,
+    pub source_launch_id: u64,
+    pub root: [u8; 32],
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct LoyaltyAllocationClaimed {
+    pub launch_id: u64,
+    pub source_launch: Pubkey,
     pub holder: Pubkey,
+    pub amount: u64,
+    pub schema_version: u8,
+}
+
+/// One standard record for every holder moment a wallet might push, so
+/// inboxes can subscribe to a single event instead of indexing each one
+#[event]
+pub struct HolderNotification {
+    /// Wallet to notify (default = every holder of `launch`)
+    pub recipient: Pubkey,
     pub launch: Pubkey,
-    pub balance: u64,
-    pub diamond_rank: DiamondRank,
-    pub multiplier_bps: u16,
+    pub kind: NotificationKind,
+    /// New rank, tokens unlocking, raise at graduation or lamports now
+    /// claimable, by `kind`
+    pub value: u64,
+    /// When the moment lands; ahead of the emitting block for scheduled ones
+    pub at: i64,
+    pub schema_version: u8,
 }
 
 #[event]
-pub struct BundlerFlagged {
-    pub wallet: Pubkey,
-    pub evidence: String,
+pub struct AuditReport {
+    pub launch_id: u64,
+    pub status: LaunchStatus,
+    /// AUDIT_* bits for the invariants that failed (0 = healthy)
+    pub violations: u8,
+    pub sol_vault_balance: u64,
+    pub sol_vault_expected: u64,
+    pub reward_vault_balance: u64,
+    /// Rewards routed to the vault and not yet paid out
+    pub reward_liabilities: u64,
+    pub holder_count: u64,
+    pub sampled_positions: u16,
+    pub sampled_live: u64,
+    pub timestamp: i64,
+    pub schema_version: u8,
+}
+
+// ============ Legacy Events ============
+//
+// Pre-versioning shapes, emitted until `Protocol::legacy_events_until`
+
+/// Superseded by `Slashed`
+#[event]
+pub struct CreatorStakeSlashed {
+    pub creator: Pubkey,
+    pub launch_id: u64,
+    pub sol_slashed: u64,
+    pub dpad_slashed: u64,
 }
 
 // ============ Errors ============
@@ -921,6 +15191,12 @@ pub enum DiamondPadError {
     #[msg("LP must be locked for at least 365 days")]
     LpLockTooShort,
     
+    #[msg("New LP unlock time must be later than the current one")]
+    LpLockNotExtended,
+    
+    #[msg("LP lock has already been burned")]
+    LpAlreadyBurned,
+    
     #[msg("Token name too long (max 32 chars)")]
     NameTooLong,
     
@@ -947,4 +15223,813 @@ pub enum DiamondPadError {
     
     #[msg("Nothing to claim yet")]
     NothingToClaim,
+    
+    #[msg("Trading is halted on this launch")]
+    TradingHalted,
+    
+    #[msg("Trading is not halted on this launch")]
+    TradingNotHalted,
+    
+    #[msg("Launch is not open for trading")]
+    LaunchNotOpen,
+    
+    #[msg("Output below minimum (slippage exceeded)")]
+    SlippageExceeded,
+    
+    #[msg("Launch has not failed")]
+    LaunchNotFailed,
+    
+    #[msg("Refund window has closed")]
+    RefundWindowClosed,
+    
+    #[msg("Refund window is still open")]
+    RefundWindowOpen,
+    
+    #[msg("Nothing to refund")]
+    NothingToRefund,
+    
+    #[msg("Contribution below the launch minimum")]
+    ContributionTooSmall,
+    
+    #[msg("Max position cannot exceed 100% (10000 bps)")]
+    InvalidMaxPosition,
+    
+    #[msg("Buy would exceed the launch's max position per wallet")]
+    MaxPositionExceeded,
+    
+    #[msg("Sell grace cannot exceed 25% (2500 bps)")]
+    SellGraceTooHigh,
+    
+    #[msg("Insufficient position balance")]
+    InsufficientBalance,
+    
+    #[msg("Insufficient liquidity in the curve")]
+    InsufficientLiquidity,
+    
+    #[msg("Total supply must be greater than zero")]
+    InvalidTotalSupply,
+    
+    #[msg("Holder rewards exceed the protocol cap")]
+    HolderRewardsTooHigh,
+    
+    #[msg("Dev, LP and holder reward shares exceed 100% of supply")]
+    AllocationExceedsSupply,
+    
+    #[msg("Too little supply left for the sale after reserved shares")]
+    SaleSupplyTooSmall,
+    
+    #[msg("Invalid bonding curve parameters")]
+    InvalidCurveParams,
+    
+    #[msg("Virtual reserves outside protocol bounds")]
+    InvalidVirtualReserves,
+    
+    #[msg("Fee share cannot exceed 100% (10000 bps)")]
+    InvalidFeeShare,
+    
+    #[msg("Creator and insider wallets cannot buy their own launch")]
+    InsiderCannotBuy,
+    
+    #[msg("Wallet is already an insider of this launch")]
+    AlreadyInsider,
+    
+    #[msg("Launch has reached its insider limit")]
+    TooManyInsiders,
+    
+    #[msg("This launch requires a gateway pass")]
+    GatewayTokenRequired,
+    
+    #[msg("Gateway pass is invalid for this wallet or network")]
+    InvalidGatewayToken,
+    
+    #[msg("Gateway pass is frozen, revoked or expired")]
+    GatewayTokenInactive,
+    
+    #[msg("Attestation has already been revoked")]
+    AttestationRevoked,
+    
+    #[msg("Buy would exceed the launch's hard cap")]
+    HardCapReached,
+    
+    #[msg("Creator stake is still locked behind a recent launch")]
+    CreatorStakeLocked,
+    
+    #[msg("Launch has already been flagged as a rug")]
+    AlreadyRugFlagged,
+    
+    #[msg("Launch is awaiting curator approval")]
+    LaunchNotApproved,
+    
+    #[msg("Launch is not awaiting review")]
+    LaunchNotUnderReview,
+    
+    #[msg("Curated launches must be submitted with the review queue")]
+    ReviewQueueRequired,
+    
+    #[msg("Review queue is full")]
+    ReviewQueueFull,
+    
+    #[msg("Circuit breaker has paused trading on this launch")]
+    CircuitBreakerActive,
+    
+    #[msg("Invalid circuit breaker settings")]
+    InvalidCircuitBreaker,
+    
+    #[msg("Cannot sell in the same slot as a buy")]
+    SameSlotRoundTrip,
+    
+    #[msg("Wash score cutoff cannot exceed 10000")]
+    InvalidWashCutoff,
+    
+    #[msg("Referral code must be 1-16 letters, digits, '-' or '_'")]
+    InvalidReferralCode,
+    
+    #[msg("Referral code is already registered")]
+    ReferralCodeTaken,
+    
+    #[msg("Launch seed must be 1-32 bytes")]
+    InvalidLaunchSeed,
+    
+    #[msg("Signer is not allowed to flag bundlers")]
+    NotAFlagger,
+    
+    #[msg("Flagger has already approved this flag")]
+    AlreadyApprovedFlag,
+    
+    #[msg("Pending flag has reached its approval limit")]
+    TooManyFlagApprovals,
+    
+    #[msg("Bundler flag has not reached the approval threshold")]
+    FlagThresholdNotMet,
+    
+    #[msg("Flag threshold must be between 1 and 8")]
+    InvalidFlagThreshold,
+    
+    #[msg("Evidence URI too long (max 128 chars)")]
+    EvidenceUriTooLong,
+    
+    #[msg("Bundler record has no room for more evidence")]
+    EvidenceLogFull,
+    
+    #[msg("Detection report is stale or from the future")]
+    StaleDetectionReport,
+    
+    #[msg("Detection report must follow an Ed25519 signature check")]
+    MissingReportSignature,
+    
+    #[msg("Detection report signature does not match the report or detector")]
+    InvalidReportSignature,
+    
+    #[msg("Risk score must be between 0 and 100")]
+    InvalidRiskScore,
+    
+    #[msg("Launch screens buyers; pass the buyer's wallet risk account")]
+    WalletRiskRequired,
+    
+    #[msg("Wallet risk score is above this launch's threshold")]
+    WalletRiskTooHigh,
+    
+    #[msg("Launch has not sold enough of its sale supply to graduate")]
+    GraduationNotReached,
+    
+    #[msg("LP boost too high (max 50%)")]
+    LpBoostTooHigh,
+    
+    #[msg("Referral has already been credited")]
+    ReferralAlreadyQualified,
+    
+    #[msg("Referred holder has not reached Bronze")]
+    ReferralNotQualified,
+    
+    #[msg("Cannot gift a position to yourself")]
+    InvalidGiftRecipient,
+    
+    #[msg("Insider positions cannot be gifted")]
+    InsiderCannotGift,
+    
+    #[msg("No recovery key change is scheduled")]
+    NoPendingRecovery,
+    
+    #[msg("Recovery key change is still timelocked")]
+    RecoveryTimelocked,
+    
+    #[msg("Wallet is already linked to a holder profile")]
+    WalletAlreadyLinked,
+    
+    #[msg("Holder profile has no room for more wallets")]
+    TooManyLinkedWallets,
+    
+    #[msg("Wallet is not linked to this holder profile")]
+    WalletNotLinked,
+    
+    #[msg("Launch has not missed its deadline or gone inactive")]
+    LaunchNotExpired,
+    
+    #[msg("Sale deadline passed without reaching the soft cap")]
+    SaleEnded,
+    
+    #[msg("Soft cap needs a sale duration of 1-90 days")]
+    InvalidSaleWindow,
+    
+    #[msg("Sale deadline has already been extended")]
+    SaleAlreadyExtended,
+    
+    #[msg("Sale extension exceeds the protocol maximum")]
+    InvalidSaleExtension,
+    
+    #[msg("Launch parameter has been permanently frozen")]
+    ParameterFrozen,
+    
+    #[msg("Freeze mask must set at least one known FREEZE_* bit")]
+    InvalidFreezeMask,
+    
+    #[msg("Calling program is not an allowlisted router")]
+    RouterNotAllowed,
+    
+    #[msg("Reward destination must be the holder or a linked wallet")]
+    InvalidRewardDestination,
+    
+    #[msg("Contribution record required to claim rewards in the launch token")]
+    ContributionRequired,
+    
+    #[msg("Curve could not price this trade (too large or overflow)")]
+    CurveQuoteFailed,
+    
+    #[msg("Not enough sale supply left on the curve")]
+    SaleSupplyExhausted,
+    
+    #[msg("Transfer fee exceeds the protocol cap or has no maximum")]
+    InvalidTransferFee,
+    
+    #[msg("Launch mint, launch authority and Token-2022 program must be passed together")]
+    LaunchMintRequired,
+    
+    #[msg("Interest rate must be between 0 and the protocol cap")]
+    InvalidInterestRate,
+    
+    #[msg("Launch mint does not have the interest-bearing extension")]
+    NotInterestBearing,
+    
+    #[msg("Metadata URI too long")]
+    MetadataUriTooLong,
+    
+    #[msg("Graduation airdrop must reserve at most 5% for 1-1000 holders")]
+    InvalidGraduationAirdrop,
+    
+    #[msg("Launch has no graduation airdrop to commit or claim")]
+    NoGraduationAirdrop,
+    
+    #[msg("Graduation airdrop root has already been committed")]
+    AirdropAlreadyCommitted,
+    
+    #[msg("Airdrop commitment needs a root and a recipient count within the config")]
+    InvalidAirdropCommitment,
+    
+    #[msg("Airdrop proof does not match the committed root")]
+    InvalidAirdropProof,
+    
+    #[msg("Username must be 1-20 lowercase letters, digits or underscores")]
+    InvalidUsername,
+    
+    #[msg("Profile already has a username")]
+    UsernameAlreadySet,
+    
+    #[msg("Username can't be released or transferred yet")]
+    UsernameLocked,
+    
+    #[msg("Achievement already unlocked")]
+    AchievementAlreadyUnlocked,
+    
+    #[msg("Evidence does not satisfy the achievement")]
+    AchievementNotEarned,
+    
+    #[msg("Quest needs a reward, a budget covering it and a future expiry")]
+    InvalidQuest,
+    
+    #[msg("Quest has expired")]
+    QuestExpired,
+    
+    #[msg("Quest budget cannot cover another reward")]
+    QuestBudgetExhausted,
+    
+    #[msg("Position does not meet the quest condition")]
+    QuestConditionNotMet,
+    
+    #[msg("Quest can only be closed once expired or exhausted")]
+    QuestStillActive,
+    
+    #[msg("Current season has not ended yet")]
+    SeasonStillActive,
+    
+    #[msg("Season must start now or later and end after it starts")]
+    InvalidSeasonWindow,
+    
+    #[msg("Season has ended")]
+    SeasonEnded,
+    
+    #[msg("Stake must stay locked until the gauge epoch ends")]
+    StakeUnlocksBeforeEpochEnd,
+    
+    #[msg("Votes can only go to the current gauge epoch")]
+    NotCurrentGaugeEpoch,
+    
+    #[msg("Gauge epoch has not ended yet")]
+    GaugeEpochNotEnded,
+    
+    #[msg("Emissions already distributed for this launch and epoch")]
+    GaugeAlreadyDistributed,
+    
+    #[msg("Launch has not been flagged as a rug")]
+    LaunchNotRugFlagged,
+    
+    #[msg("Insurance claims are already open for this launch")]
+    InsuranceClaimsAlreadyOpen,
+    
+    #[msg("Insurance claims are not open")]
+    InsuranceClaimsClosed,
+    
+    #[msg("Insurance claim window has not closed yet")]
+    InsuranceClaimsStillOpen,
+    
+    #[msg("Insurance claims are still awaiting a decision")]
+    InsuranceClaimsPending,
+    
+    #[msg("Insurance payouts have already started")]
+    InsuranceSettled,
+    
+    #[msg("Insurance claim belongs to a different launch")]
+    InsuranceClaimMismatch,
+    
+    #[msg("Insurance claim has already been decided")]
+    InsuranceClaimNotPending,
+    
+    #[msg("Insurance claim is not approved for payout")]
+    InsuranceClaimNotApproved,
+    
+    #[msg("Insurance vault holds DPAD; pass its token accounts")]
+    InsuranceTokenAccountsRequired,
+    
+    #[msg("Council term must be between 1 and 365 days")]
+    InvalidCouncilTerm,
+    
+    #[msg("Council has no free seats")]
+    CouncilFull,
+    
+    #[msg("Council approvals must be member account and signing wallet pairs")]
+    InvalidCouncilApproval,
+    
+    #[msg("Needs signatures from a majority of the council")]
+    CouncilMajorityRequired,
+    
+    #[msg("Vesting schedule has already been revoked")]
+    VestingAlreadyRevoked,
+    
+    #[msg("Position has not been held long enough to claim rewards")]
+    NotYetEligible,
+    
+    #[msg("Minimum hold before claiming is too long")]
+    InvalidMinHoldBeforeClaim,
+    
+    #[msg("Transaction also buys on this launch")]
+    BuyInSameTransaction,
+    
+    #[msg("Too many actions on this position; try again in a minute")]
+    RateLimited,
+    
+    #[msg("Priority window: needs a Gold+ position on a graduated launch")]
+    PriorityAccessRequired,
+    
+    #[msg("Priority access window is too long")]
+    InvalidPriorityAccess,
+    
+    #[msg("Loyalty discount exceeds the maximum")]
+    InvalidLoyaltyDiscount,
+    
+    #[msg("Launch is already flagged as spam")]
+    AlreadySpamFlagged,
+    
+    #[msg("Launch has no listing deposit to settle")]
+    NoListingDeposit,
+    
+    #[msg("Launch has neither graduated nor failed")]
+    LaunchNotSettled,
+    
+    #[msg("Listing deposit is still held after graduation")]
+    ListingDepositHeld,
+    
+    #[msg("Feature is not enabled")]
+    FeatureDisabled,
+    
+    #[msg("No feature flag change is scheduled")]
+    NoPendingFeatureFlags,
+    
+    #[msg("Feature flag change is still timelocked")]
+    FeatureFlagsTimelocked,
+    
+    #[msg("Position does not exceed the holder's token balance")]
+    PositionInSync,
+    
+    #[msg("OTC sale must be for a nonzero price and unvested tokens only")]
+    InvalidOtcSale,
+    
+    #[msg("OTC sale is not open")]
+    OtcSaleNotOpen,
+    
+    #[msg("OTC sale is reserved for another buyer")]
+    NotOtcBuyer,
+    
+    #[msg("Launch does not freeze transfers until graduation")]
+    TransfersNotFrozen,
+    
+    #[msg("Sale token claim window is still open")]
+    ClaimWindowOpen,
+    
+    #[msg("No unclaimed sale tokens to sweep")]
+    NothingToSweep,
+    
+    #[msg("Sale token claim window has closed")]
+    ClaimWindowClosed,
+    
+    #[msg("Protocol-wide daily reward cap reached")]
+    DailyRewardCapReached,
+    
+    #[msg("Position is not an idle dust position")]
+    PositionNotCollectable,
+    
+    #[msg("Invalid wallet age attestation or requirement")]
+    InvalidWalletAge,
+    
+    #[msg("Wallet is younger than this launch's minimum wallet age")]
+    WalletTooNew,
+    
+    #[msg("Verified holder minimum hold is too long")]
+    InvalidVerifiedHolderFilter,
+    
+    #[msg("Holder rewards can only be raised")]
+    HolderRewardsNotRaised,
+    
+    #[msg("Position has no voting power")]
+    NoVotingPower,
+    
+    #[msg("Voting on this proposal has ended")]
+    ProposalVotingClosed,
+    
+    #[msg("Invalid vote delegate")]
+    InvalidDelegate,
+    
+    #[msg("Voting on this proposal is still open")]
+    ProposalVotingOpen,
+    
+    #[msg("Proposal was not passed")]
+    ProposalNotPassed,
+    
+    #[msg("Proposal has already been executed")]
+    ProposalAlreadyExecuted,
+    
+    #[msg("Proposal has no action to execute")]
+    ProposalHasNoAction,
+    
+    #[msg("Proposal did not reach quorum")]
+    ProposalQuorumNotMet,
+    
+    #[msg("Position changed after the proposal opened")]
+    PositionChangedSinceSnapshot,
+    
+    #[msg("Governance quorum must be at most 100%")]
+    InvalidGovernanceQuorum,
+    
+    #[msg("Graduation reward share exceeds the maximum")]
+    InvalidGraduationReward,
+    
+    #[msg("Recorded balance would exceed the holder's token account")]
+    RecordExceedsBalance,
+    
+    #[msg("Supply buckets do not add up to the total supply")]
+    SupplyInvariantViolated,
+    
+    #[msg("Amount exceeds what is left in its supply bucket")]
+    SupplyBucketOverdrawn,
+    
+    #[msg("Reward claims are paused on this launch")]
+    ClaimsPaused,
+    
+    #[msg("Reward claims are not paused on this launch")]
+    ClaimsNotPaused,
+    
+    #[msg("Position already uses the current layout")]
+    PositionLayoutCurrent,
+    
+    #[msg("Position belongs to a different launch")]
+    PositionLaunchMismatch,
+    
+    #[msg("Rank thresholds must start at zero and strictly increase")]
+    InvalidRankThresholds,
+    
+    #[msg("Channel fee share exceeds the maximum")]
+    InvalidChannelFeeShare,
+    
+    #[msg("The launch's partner channel account is required")]
+    ChannelRequired,
+    
+    #[msg("Invalid gas sponsorship: refund too large or above the epoch cap")]
+    InvalidGasSponsorship,
+    
+    #[msg("Invalid seed positions: count, balance, entry time or position address")]
+    InvalidSeedPositions,
+    
+    #[msg("Position account already exists")]
+    PositionAlreadyExists,
+    
+    #[msg("Position import already committed")]
+    ImportAlreadyCommitted,
+    
+    #[msg("This launch has no position import")]
+    NoPositionImport,
+    
+    #[msg("Invalid position import proof")]
+    InvalidImportProof,
+    
+    #[msg("Invalid graduation grace: window or share too large, or share without a window")]
+    InvalidGraduationGrace,
+    
+    #[msg("Referral stats don't match the position's referral code or the leaderboard")]
+    ReferralStatsMismatch,
+    
+    #[msg("Referral code is not on the launch's leaderboard")]
+    NotReferralLeader,
+    
+    #[msg("Launch id shard out of range")]
+    InvalidLaunchIdShard,
+    
+    #[msg("Loyalty source must be another graduated launch by the same creator")]
+    InvalidLoyaltySource,
+    
+    #[msg("Loyalty snapshot already committed")]
+    LoyaltySnapshotAlreadyCommitted,
+    
+    #[msg("This launch has no loyalty snapshot")]
+    NoLoyaltySnapshot,
+    
+    #[msg("Invalid loyalty allocation proof")]
+    InvalidLoyaltyProof,
+    
+    #[msg("Audit sample too large or not sorted by address")]
+    InvalidAuditSample,
+    
+    #[msg("No detector is set for the signed detection feed")]
+    DetectorNotSet,
+    
+    #[msg("Insiders cannot be credited seeded, imported or loyalty allocations")]
+    InsiderNotEligible,
+    
+    #[msg("Loyalty bonuses need a position in the source launch held since before it graduated")]
+    NotLoyalHolder,
+    
+    #[msg("A flagged bundler's claim needs the bundler stats account")]
+    BundlerStatsRequired,
+    
+    #[msg("Vesting schedules must be the owner's, sorted by address")]
+    InvalidVestingSchedules,
+    
+    #[msg("A schedule sold OTC needs its sale and buyer accounts")]
+    OtcSaleRequired,
+    
+    #[msg("Launch has been ruled a rug")]
+    LaunchRugFlagged,
+    
+    #[msg("Position was opened or changed after the rug ruling")]
+    PositionChangedSinceRuling,
+    
+    #[msg("Referral stats need the launch's referral board alongside")]
+    ReferralBoardRequired,
+    
+    #[msg("Referral bonuses are already being paid from this board")]
+    ReferralBoardFinal,
+    
+    #[msg("Launch mint transfers are still frozen")]
+    TransfersStillFrozen,
+    
+    #[msg("Protocol-wide actions can only be proposed on the protocol token's launch")]
+    NotProtocolGovernance,
+    
+    #[msg("Launch already uses the current layout")]
+    LaunchLayoutCurrent,
+    
+    #[msg("Token-denominated claims need the treasury to pay the buy fee")]
+    TreasuryRequired,
+    
+    #[msg("The profile owner's wallet can't be unlinked")]
+    ProfileOwnerWallet,
+    
+    #[msg("Setting the interest rate needs the launch mint, its authority and Token-2022")]
+    InterestRateAccountsRequired,
+    
+    #[msg("Only tokens bought from the curve can be sold back into it")]
+    BonusNotSellable,
+    
+    #[msg("Transfer fees can only be harvested into the reward vault")]
+    InvalidFeeDestination,
+    
+    #[msg("Pending flag has expired")]
+    FlagExpired,
+    
+    #[msg("Launch has not graduated")]
+    LaunchNotGraduated,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_option::COption;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token_2022::spl_token_2022;
+    use std::collections::BTreeSet;
+
+    /// An account the test keeps for its whole run
+    fn account(
+        key: Pubkey,
+        owner: Pubkey,
+        data: Vec<u8>,
+        executable: bool,
+    ) -> AccountInfo<'static> {
+        AccountInfo::new(
+            Box::leak(Box::new(key)),
+            false,
+            true,
+            Box::leak(Box::new(1_000_000_000)),
+            Box::leak(data.into_boxed_slice()),
+            Box::leak(Box::new(owner)),
+            executable,
+            0,
+        )
+    }
+
+    /// Program state with every field zeroed, as `init` leaves it
+    fn zeroed<T: AccountDeserialize + Discriminator>(size: usize) -> T {
+        let mut data = vec![0; size];
+        data[..8].copy_from_slice(&T::DISCRIMINATOR);
+        T::try_deserialize(&mut &data[..]).unwrap()
+    }
+
+    fn state_account<T: AccountSerialize>(
+        key: Pubkey,
+        size: usize,
+        state: &T,
+    ) -> AccountInfo<'static> {
+        let mut data = vec![0; size];
+        state.try_serialize(&mut &mut data[..]).unwrap();
+        account(key, crate::ID, data, false)
+    }
+
+    /// A launch account with every field zeroed apart from what `edit` sets
+    fn launch_account(key: Pubkey, edit: impl FnOnce(&mut Launch)) -> AccountInfo<'static> {
+        let mut launch = zeroed::<Launch>(Launch::SIZE);
+        edit(&mut launch);
+        state_account(key, Launch::SIZE, &launch)
+    }
+
+    fn token_2022_account(mint: Pubkey, owner: Pubkey) -> AccountInfo<'static> {
+        let mut data = vec![0; spl_token_2022::state::Account::LEN];
+        spl_token_2022::state::Account {
+            mint,
+            owner,
+            amount: 0,
+            delegate: COption::None,
+            state: AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        }
+        .pack_into_slice(&mut data);
+        account(Pubkey::new_unique(), Token2022::id(), data, false)
+    }
+
+    /// Validate `HarvestTransferFees` with the fees going to a token account
+    /// owned by `destination_owner`
+    fn harvest_into(destination_owner: impl FnOnce(&Pubkey, &Pubkey) -> Pubkey) -> Result<()> {
+        let (launch_key, mint, creator) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let reward_vault = pda::reward_vault_address(&launch_key);
+        let launch_authority = pda::launch_authority_address(&launch_key);
+        let accounts: &'static [AccountInfo<'static>] = Box::leak(Box::new([
+            launch_account(launch_key, |launch| {
+                launch.creator = creator;
+                launch.mint = mint;
+            }),
+            account(mint, Token2022::id(), vec![], false),
+            account(launch_authority, system_program::ID, vec![], false),
+            account(reward_vault, system_program::ID, vec![], false),
+            token_2022_account(mint, destination_owner(&reward_vault, &creator)),
+            account(Token2022::id(), Pubkey::default(), vec![], true),
+        ]));
+        HarvestTransferFees::try_accounts(
+            &crate::ID,
+            &mut &accounts[..],
+            &[],
+            &mut HarvestTransferFeesBumps::default(),
+            &mut BTreeSet::new(),
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    fn transfer_fees_harvest_into_the_reward_vault() {
+        assert!(harvest_into(|reward_vault, _| *reward_vault).is_ok());
+    }
+
+    #[test]
+    fn transfer_fees_cannot_be_harvested_to_the_creator() {
+        assert_eq!(
+            harvest_into(|_, creator| *creator).unwrap_err(),
+            DiamondPadError::InvalidFeeDestination.into()
+        );
+    }
+
+    #[test]
+    fn recovered_position_reconciles_against_the_moved_contribution() {
+        let (launch_key, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (old_wallet, new_wallet) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        // The old wallet bought 500 tokens it hasn't claimed yet
+        let mut old = zeroed::<Contribution>(Contribution::SIZE);
+        open_contribution(&mut old, launch_key, old_wallet, 255);
+        old.tokens_allocated = 500;
+
+        let (contribution_address, contribution_bump) = Pubkey::find_program_address(
+            &[b"contribution", launch_key.as_ref(), new_wallet.as_ref()],
+            &crate::ID,
+        );
+        let mut contribution = zeroed::<Contribution>(Contribution::SIZE);
+        take_over_contribution(&mut contribution, &old, new_wallet, contribution_bump);
+
+        let (position_address, position_bump) = Pubkey::find_program_address(
+            &[b"position", launch_key.as_ref(), new_wallet.as_ref()],
+            &crate::ID,
+        );
+        let mut position = zeroed::<Position>(Position::SIZE);
+        position.holder = new_wallet;
+        position.launch = launch_key;
+        position.balance = 500;
+        position.bump = position_bump;
+
+        let holder_token_account =
+            get_associated_token_address_with_program_id(&new_wallet, &mint, &Token2022::id());
+        let accounts: &'static [AccountInfo<'static>] = Box::leak(Box::new([
+            launch_account(launch_key, |launch| launch.mint = mint),
+            state_account(position_address, Position::SIZE, &position),
+            account(holder_token_account, system_program::ID, vec![], false),
+            state_account(contribution_address, Contribution::SIZE, &contribution),
+        ]));
+        let reconcile = ReconcilePosition::try_accounts(
+            &crate::ID,
+            &mut &accounts[..],
+            &[],
+            &mut ReconcilePositionBumps::default(),
+            &mut BTreeSet::new(),
+        )
+        .unwrap();
+
+        assert_eq!(held_balance(&reconcile).unwrap(), position.balance);
+    }
+
+    #[test]
+    fn removed_flaggers_approvals_do_not_count() {
+        let mut protocol = zeroed::<Protocol>(Protocol::SIZE);
+        protocol.authority = Pubkey::new_unique();
+        let (kept, removed) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let approvals = [protocol.authority, kept, removed];
+
+        let registration = |key: Pubkey| Flagger { key, added_at: 0, bump: 255 };
+        let kept_registration =
+            state_account(Pubkey::new_unique(), Flagger::SIZE, &registration(kept));
+        assert_eq!(
+            current_flag_approvals(&protocol, &approvals, &[kept_registration]).unwrap(),
+            2
+        );
+
+        // A closed registration belongs to the system program again
+        let closed_registration =
+            account(Pubkey::new_unique(), system_program::ID, vec![], false);
+        assert!(current_flag_approvals(&protocol, &approvals, &[closed_registration]).is_err());
+    }
+
+    #[test]
+    fn relock_bonus_follows_the_combined_lock() {
+        let locked = |extensions: &[i64]| {
+            let mut launch = zeroed::<Launch>(Launch::SIZE);
+            launch.config.lp_lock_days = 30;
+            launch.config.holder_rewards_bps = 500;
+            launch.lp_unlock_timestamp = 30 * 86400;
+            for days in extensions {
+                let unlock = launch.lp_unlock_timestamp + days * 86400;
+                extend_launch_lp_lock(&mut launch, unlock).unwrap();
+            }
+            launch.config.holder_rewards_bps
+        };
+
+        assert_eq!(locked(&[180]), 600);
+        assert_eq!(locked(&[90, 90]), locked(&[180]));
+        assert_eq!(locked(&[60, 60, 60]), 600);
+        assert_eq!(locked(&[90; 10]), 500 + LP_BURN_BONUS_BPS);
+    }
 }
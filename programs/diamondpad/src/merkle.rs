@@ -0,0 +1,31 @@
+//! Merkle proofs for off-chain computed distributions.
+//!
+//! Leaves are `keccak(wallet || amount_le)`, or for position imports
+//! `keccak(wallet || balance_le || hold_start_le)`. Pairs are hashed in
+//! sorted order, so a proof is just the sibling hashes from leaf to root
+//! with no left/right flags.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak::hashv;
+
+/// Leaf for `wallet` receiving `amount`
+pub fn leaf(wallet: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[wallet.as_ref(), &amount.to_le_bytes()]).to_bytes()
+}
+
+/// Leaf for `wallet` importing `balance` held since `hold_start`
+pub fn import_leaf(wallet: &Pubkey, balance: u64, hold_start: i64) -> [u8; 32] {
+    hashv(&[wallet.as_ref(), &balance.to_le_bytes(), &hold_start.to_le_bytes()]).to_bytes()
+}
+
+/// Whether `proof` connects `leaf` to `root`
+pub fn verify(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == *root
+}
@@ -0,0 +1,76 @@
+//! Program-derived addresses, for clients and for programs building CPIs.
+//!
+//! Each helper mirrors the seeds on the matching account constraint in
+//! `lib.rs`; the bump is dropped since callers only need the address.
+
+use anchor_lang::prelude::*;
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &crate::ID).0
+}
+
+pub fn protocol_address() -> Pubkey {
+    find(&[b"protocol"])
+}
+
+pub fn protocol_stats_address() -> Pubkey {
+    find(&[b"protocol_stats"])
+}
+
+pub fn bundler_stats_address() -> Pubkey {
+    find(&[b"bundler_stats"])
+}
+
+/// A protocol-numbered launch. Seeded and creator-nonce launches live at
+/// their own addresses.
+pub fn launch_address(launch_id: u64) -> Pubkey {
+    find(&[b"launch", launch_id.to_le_bytes().as_ref()])
+}
+
+pub fn sol_vault_address(launch: &Pubkey) -> Pubkey {
+    find(&[b"sol_vault", launch.as_ref()])
+}
+
+pub fn reward_vault_address(launch: &Pubkey) -> Pubkey {
+    find(&[b"reward_vault", launch.as_ref()])
+}
+
+pub fn launch_mint_address(launch: &Pubkey) -> Pubkey {
+    find(&[b"launch_mint", launch.as_ref()])
+}
+
+pub fn launch_authority_address(launch: &Pubkey) -> Pubkey {
+    find(&[b"launch_authority", launch.as_ref()])
+}
+
+pub fn position_address(launch: &Pubkey, holder: &Pubkey) -> Pubkey {
+    find(&[b"position", launch.as_ref(), holder.as_ref()])
+}
+
+pub fn contribution_address(launch: &Pubkey, contributor: &Pubkey) -> Pubkey {
+    find(&[b"contribution", launch.as_ref(), contributor.as_ref()])
+}
+
+pub fn bundler_address(wallet: &Pubkey) -> Pubkey {
+    find(&[b"bundler", wallet.as_ref()])
+}
+
+pub fn creator_account_address(creator: &Pubkey) -> Pubkey {
+    find(&[b"creator_account", creator.as_ref()])
+}
+
+pub fn creator_launch_address(creator: &Pubkey, nonce: u64) -> Pubkey {
+    find(&[b"creator_launch", creator.as_ref(), nonce.to_le_bytes().as_ref()])
+}
+
+pub fn launch_id_shard_address(shard: u8) -> Pubkey {
+    find(&[b"launch_id_shard", shard.to_le_bytes().as_ref()])
+}
+
+pub fn creator_stake_address(creator: &Pubkey) -> Pubkey {
+    find(&[b"creator_stake", creator.as_ref()])
+}
+
+pub fn creator_checklist_address(launch: &Pubkey) -> Pubkey {
+    find(&[b"creator_checklist", launch.as_ref()])
+}
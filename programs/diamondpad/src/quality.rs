@@ -0,0 +1,90 @@
+//! Launch quality scoring.
+//!
+//! A 0-100 safety score built only from things the program can check for
+//! itself: the LP lock, the launch mint's authority, the dev allocation and
+//! its vesting, and the creator's stake record. Aggregators can sort on the
+//! stored total and show the components from the refresh event.
+
+use anchor_lang::prelude::*;
+
+use crate::LaunchTier;
+
+pub const MAX_LP_POINTS: u8 = 25;
+pub const MAX_MINT_POINTS: u8 = 15;
+pub const MAX_DEV_ALLOCATION_POINTS: u8 = 20;
+pub const MAX_VESTING_POINTS: u8 = 20;
+pub const MAX_CREATOR_POINTS: u8 = 20;
+
+/// Everything the score is computed from, read at refresh time
+pub struct QualityInputs {
+    pub lp_burned: bool,
+    /// Days the LP stays locked from now (or will be locked for, pre-graduation)
+    pub lp_lock_days_remaining: u64,
+    pub mint_authority_revoked: bool,
+    pub dev_allocation_bps: u16,
+    pub dev_vesting_days: u16,
+    /// `None` when the creator has no stake account
+    pub creator_tier: Option<LaunchTier>,
+    pub creator_times_slashed: u32,
+}
+
+/// Per-component breakdown of a launch's quality score
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QualityScore {
+    pub lp: u8,
+    pub mint: u8,
+    pub dev_allocation: u8,
+    pub vesting: u8,
+    pub creator: u8,
+}
+
+impl QualityScore {
+    pub fn compute(inputs: &QualityInputs) -> Self {
+        let lp = if inputs.lp_burned {
+            MAX_LP_POINTS
+        } else {
+            match inputs.lp_lock_days_remaining {
+                365.. => 20,
+                180..=364 => 15,
+                90..=179 => 10,
+                1..=89 => 5,
+                0 => 0,
+            }
+        };
+
+        let mint = if inputs.mint_authority_revoked { MAX_MINT_POINTS } else { 0 };
+
+        let dev_allocation = match inputs.dev_allocation_bps {
+            0 => MAX_DEV_ALLOCATION_POINTS,
+            1..=500 => 15,
+            501..=1000 => 10,
+            1001..=2000 => 5,
+            _ => 0,
+        };
+
+        // Nothing to vest is as good as the longest vesting
+        let vesting = if inputs.dev_allocation_bps == 0 {
+            MAX_VESTING_POINTS
+        } else {
+            match inputs.dev_vesting_days {
+                365.. => MAX_VESTING_POINTS,
+                180..=364 => 12,
+                90..=179 => 6,
+                _ => 0,
+            }
+        };
+
+        let creator = match (inputs.creator_tier, inputs.creator_times_slashed) {
+            (Some(_), 1..) | (None, _) => 0,
+            (Some(LaunchTier::Standard), _) => 10,
+            (Some(LaunchTier::Silver), _) => 15,
+            (Some(LaunchTier::Gold), _) => MAX_CREATOR_POINTS,
+        };
+
+        Self { lp, mint, dev_allocation, vesting, creator }
+    }
+
+    pub fn total(&self) -> u8 {
+        self.lp + self.mint + self.dev_allocation + self.vesting + self.creator
+    }
+}
@@ -0,0 +1,256 @@
+//! Deterministic event replay (`client` feature).
+//!
+//! Indexers feed `Replayer` the program's events in transaction order and
+//! get back the protocol, launch and position figures the program itself
+//! would hold, so a database built from the same stream can be checked
+//! against program logic. Replay is a pure fold over events: the same
+//! stream always produces the same state, and maps are ordered so
+//! iterating them is deterministic too.
+//!
+//! Only events carrying enough to move tracked state are decoded; the rest
+//! come back as `None` from `ReplayEvent::decode` and can be skipped.
+//! Layouts are the current ones, so events emitted before a field was
+//! appended won't decode.
+
+use std::collections::BTreeMap;
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::{
+    DiamondRank, LaunchCreated, LaunchFailed, LaunchGraduated, LaunchStatus, PositionCollected,
+    PositionUpdated, RewardsClaimed, TokensBought, TokensSold, SHARDED_LAUNCH_ID_BIT,
+};
+
+/// An event replay knows how to apply
+pub enum ReplayEvent {
+    LaunchCreated(LaunchCreated),
+    TokensBought(TokensBought),
+    TokensSold(TokensSold),
+    PositionUpdated(PositionUpdated),
+    RewardsClaimed(RewardsClaimed),
+    PositionCollected(PositionCollected),
+    LaunchGraduated(LaunchGraduated),
+    LaunchFailed(LaunchFailed),
+}
+
+impl ReplayEvent {
+    /// Decode one event from the base64-decoded payload of a
+    /// `Program data:` log line. `None` for events replay doesn't track.
+    pub fn decode(data: &[u8]) -> Result<Option<Self>> {
+        if data.len() < 8 {
+            return Ok(None);
+        }
+        let (discriminator, mut body) = data.split_at(8);
+        let body = &mut body;
+        let event = match discriminator {
+            d if d == LaunchCreated::DISCRIMINATOR => {
+                Self::LaunchCreated(AnchorDeserialize::deserialize(body)?)
+            }
+            d if d == TokensBought::DISCRIMINATOR => {
+                Self::TokensBought(AnchorDeserialize::deserialize(body)?)
+            }
+            d if d == TokensSold::DISCRIMINATOR => {
+                Self::TokensSold(AnchorDeserialize::deserialize(body)?)
+            }
+            d if d == PositionUpdated::DISCRIMINATOR => {
+                Self::PositionUpdated(AnchorDeserialize::deserialize(body)?)
+            }
+            d if d == RewardsClaimed::DISCRIMINATOR => {
+                Self::RewardsClaimed(AnchorDeserialize::deserialize(body)?)
+            }
+            d if d == PositionCollected::DISCRIMINATOR => {
+                Self::PositionCollected(AnchorDeserialize::deserialize(body)?)
+            }
+            d if d == LaunchGraduated::DISCRIMINATOR => {
+                Self::LaunchGraduated(AnchorDeserialize::deserialize(body)?)
+            }
+            d if d == LaunchFailed::DISCRIMINATOR => {
+                Self::LaunchFailed(AnchorDeserialize::deserialize(body)?)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(event))
+    }
+}
+
+/// Protocol-wide totals
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct ProtocolReplay {
+    pub total_launches: u64,
+    pub total_buy_volume: u64,
+    pub total_sell_volume: u64,
+    pub total_fees: u64,
+    pub total_rewards_claimed: u64,
+}
+
+/// One launch, keyed by its address
+#[derive(Clone, PartialEq, Eq)]
+pub struct LaunchReplay {
+    pub launch_id: u64,
+    pub creator: Pubkey,
+    pub total_supply: u64,
+    pub status: LaunchStatus,
+    pub total_raised: u64,
+    pub tokens_sold: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub total_buy_volume: u64,
+    pub total_sell_volume: u64,
+    pub reward_vault_funded: u64,
+    pub rewards_distributed: u64,
+    pub holder_count: u64,
+    pub peak_holder_count: u64,
+}
+
+/// One holder's position, keyed by `(launch, holder)`
+#[derive(Clone, PartialEq, Eq)]
+pub struct PositionReplay {
+    pub balance: u64,
+    pub diamond_rank: DiamondRank,
+    pub multiplier_bps: u16,
+    pub total_rewards_claimed: u64,
+}
+
+/// State rebuilt from an event stream
+#[derive(Clone, Default)]
+pub struct Replayer {
+    pub protocol: ProtocolReplay,
+    pub launches: BTreeMap<Pubkey, LaunchReplay>,
+    /// Launch addresses by launch id, from `LaunchCreated`
+    pub launch_keys: BTreeMap<u64, Pubkey>,
+    pub positions: BTreeMap<(Pubkey, Pubkey), PositionReplay>,
+}
+
+impl Replayer {
+    /// Fold a whole stream, in order, into fresh state
+    pub fn replay<'a>(events: impl IntoIterator<Item = &'a ReplayEvent>) -> Self {
+        let mut replayer = Self::default();
+        for event in events {
+            replayer.apply(event);
+        }
+        replayer
+    }
+
+    /// Apply one event. Events for a launch replay never saw created are
+    /// ignored, so a stream has to start from the launch's creation.
+    pub fn apply(&mut self, event: &ReplayEvent) {
+        match event {
+            ReplayEvent::LaunchCreated(e) => {
+                // Shard-numbered launches don't take a protocol id
+                if e.launch_id & SHARDED_LAUNCH_ID_BIT == 0 {
+                    self.protocol.total_launches += 1;
+                }
+                self.launch_keys.insert(e.launch_id, e.launch);
+                self.launches.insert(
+                    e.launch,
+                    LaunchReplay {
+                        launch_id: e.launch_id,
+                        creator: e.creator,
+                        total_supply: e.total_supply,
+                        status: LaunchStatus::Pending,
+                        total_raised: 0,
+                        tokens_sold: 0,
+                        virtual_sol_reserves: 0,
+                        virtual_token_reserves: 0,
+                        total_buy_volume: 0,
+                        total_sell_volume: 0,
+                        reward_vault_funded: 0,
+                        rewards_distributed: 0,
+                        holder_count: 0,
+                        peak_holder_count: 0,
+                    },
+                );
+            }
+            ReplayEvent::TokensBought(e) => {
+                self.protocol.total_buy_volume += e.quote_amount;
+                self.protocol.total_fees += e.fee;
+                let Some(launch) = self.launch_mut(e.launch_id) else { return };
+                launch.total_raised += e.quote_amount - e.fee;
+                launch.tokens_sold += e.tokens_out;
+                launch.total_buy_volume += e.quote_amount;
+                launch.reward_vault_funded += e.reward_fee;
+                launch.virtual_sol_reserves = e.virtual_sol_reserves;
+                launch.virtual_token_reserves = e.virtual_token_reserves;
+                if launch.status == LaunchStatus::Pending {
+                    launch.status = LaunchStatus::Active;
+                }
+            }
+            ReplayEvent::TokensSold(e) => {
+                let gross_quote = e.quote_out + e.fee;
+                self.protocol.total_sell_volume += gross_quote;
+                self.protocol.total_fees += e.fee;
+                let Some(launch) = self.launch_mut(e.launch_id) else { return };
+                launch.total_raised = launch.total_raised.saturating_sub(gross_quote);
+                launch.tokens_sold = launch.tokens_sold.saturating_sub(e.token_amount);
+                launch.total_sell_volume += gross_quote;
+                launch.reward_vault_funded += e.reward_fee;
+                launch.virtual_sol_reserves = e.virtual_sol_reserves;
+                launch.virtual_token_reserves = e.virtual_token_reserves;
+            }
+            ReplayEvent::PositionUpdated(e) => {
+                let previous = self
+                    .positions
+                    .get(&(e.launch, e.holder))
+                    .map_or(0, |position| position.balance);
+                let entry = self.positions.entry((e.launch, e.holder)).or_insert(PositionReplay {
+                    balance: 0,
+                    diamond_rank: DiamondRank::Paper,
+                    multiplier_bps: 0,
+                    total_rewards_claimed: 0,
+                });
+                entry.balance = e.balance;
+                entry.diamond_rank = e.diamond_rank;
+                entry.multiplier_bps = e.multiplier_bps;
+                if let Some(launch) = self.launches.get_mut(&e.launch) {
+                    if previous == 0 && e.balance > 0 {
+                        launch.holder_count += 1;
+                        launch.peak_holder_count = launch.peak_holder_count.max(launch.holder_count);
+                    } else if previous > 0 && e.balance == 0 {
+                        launch.holder_count = launch.holder_count.saturating_sub(1);
+                    }
+                }
+            }
+            ReplayEvent::RewardsClaimed(e) => {
+                self.protocol.total_rewards_claimed += e.amount;
+                let Some(&launch_key) = self.launch_keys.get(&e.launch_id) else { return };
+                if let Some(position) = self.positions.get_mut(&(launch_key, e.holder)) {
+                    position.total_rewards_claimed += e.amount;
+                }
+                let Some(launch) = self.launches.get_mut(&launch_key) else { return };
+                launch.rewards_distributed += e.amount;
+                // Token-denominated claims buy on the curve with the rewards
+                if e.tokens_out > 0 {
+                    launch.total_raised += e.amount;
+                    launch.tokens_sold += e.tokens_out;
+                    launch.virtual_sol_reserves += e.amount;
+                    launch.virtual_token_reserves =
+                        launch.virtual_token_reserves.saturating_sub(e.tokens_out);
+                }
+            }
+            ReplayEvent::PositionCollected(e) => {
+                let Some(&launch_key) = self.launch_keys.get(&e.launch_id) else { return };
+                self.positions.remove(&(launch_key, e.holder));
+                if e.balance > 0 {
+                    if let Some(launch) = self.launches.get_mut(&launch_key) {
+                        launch.holder_count = launch.holder_count.saturating_sub(1);
+                    }
+                }
+            }
+            ReplayEvent::LaunchGraduated(e) => {
+                let Some(launch) = self.launch_mut(e.launch_id) else { return };
+                launch.status = LaunchStatus::Graduated;
+                launch.reward_vault_funded += e.reward_topup;
+            }
+            ReplayEvent::LaunchFailed(e) => {
+                let Some(launch) = self.launch_mut(e.launch_id) else { return };
+                launch.status = LaunchStatus::Failed;
+            }
+        }
+    }
+
+    fn launch_mut(&mut self, launch_id: u64) -> Option<&mut LaunchReplay> {
+        let launch_key = self.launch_keys.get(&launch_id)?;
+        self.launches.get_mut(launch_key)
+    }
+}
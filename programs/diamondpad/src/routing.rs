@@ -0,0 +1,32 @@
+//! Router CPI introspection.
+//!
+//! Aggregators route a user's swap through their own program, which then
+//! CPIs into ours to record the position. The signer is still the user, so
+//! the only thing telling us a router is involved is the top-level
+//! instruction: if it isn't addressed to this program, we're running inside
+//! a CPI from whichever program it is addressed to. That only names our
+//! immediate caller when we're one CPI deep, so deeper nesting is refused.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+use crate::DiamondPadError;
+
+/// The program that CPI'd into us, or `None` when called directly. Errors
+/// if another program sits between the top-level one and us, since the
+/// instructions sysvar can't say which it is.
+pub fn cpi_caller(instructions_sysvar: &AccountInfo) -> Result<Option<Pubkey>> {
+    let current = load_current_index_checked(instructions_sysvar)?;
+    let ix = load_instruction_at_checked(current as usize, instructions_sysvar)?;
+    if ix.program_id == crate::ID {
+        return Ok(None);
+    }
+    require!(
+        get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT + 1,
+        DiamondPadError::RouterNotAllowed
+    );
+    Ok(Some(ix.program_id))
+}
@@ -0,0 +1,94 @@
+//! Supply bucket accounting.
+//!
+//! A launch's total supply is split at creation into the buckets it can
+//! ever be issued from: the sale, the dev allocation, the LP seed and the
+//! graduation airdrop. The reserves always add up to the total supply, and
+//! every instruction that lets launch tokens out draws on exactly one
+//! bucket, which can never go past its reserve.
+//!
+//! Holder rewards are paid in lamports from fees, so they have no bucket;
+//! `holder_rewards_bps` is a fee share, not a token allocation.
+//!
+//! Launches created before the ledger existed read it as all zeros. It's
+//! reserved from their config the first time they issue anything (see
+//! `Launch::supply_ledger`).
+
+use anchor_lang::prelude::*;
+
+use crate::{DiamondPadError, LaunchConfig};
+
+/// Number of `SupplyBucket` variants
+pub const SUPPLY_BUCKETS: usize = 4;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum SupplyBucket {
+    Sale,
+    Dev,
+    Liquidity,
+    Airdrop,
+}
+
+/// Per-bucket reserves and how much of each has been issued, indexed by
+/// `SupplyBucket`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub struct SupplyLedger {
+    pub reserved: [u64; SUPPLY_BUCKETS],
+    pub issued: [u64; SUPPLY_BUCKETS],
+}
+
+impl SupplyLedger {
+    /// Carve `total_supply` up by the config's shares. The sale takes the
+    /// rounding remainder, so it never reserves less than the curve's
+    /// `sale_supply`.
+    pub fn reserve(total_supply: u64, config: &LaunchConfig, liquidity_pool_bps: u16) -> Self {
+        let share = |bps: u16| ((total_supply as u128) * bps as u128 / 10000) as u64;
+        let mut reserved = [0; SUPPLY_BUCKETS];
+        reserved[SupplyBucket::Dev as usize] = share(config.dev_allocation_bps);
+        reserved[SupplyBucket::Liquidity as usize] = share(liquidity_pool_bps);
+        reserved[SupplyBucket::Airdrop as usize] = share(config.graduation_airdrop_bps);
+        let carved: u64 = reserved.iter().sum();
+        reserved[SupplyBucket::Sale as usize] = total_supply.saturating_sub(carved);
+        Self { reserved, issued: [0; SUPPLY_BUCKETS] }
+    }
+
+    /// Nothing reserved yet: a launch from before the ledger existed
+    pub fn is_unset(&self) -> bool {
+        self.reserved == [0; SUPPLY_BUCKETS]
+    }
+
+    pub fn remaining(&self, bucket: SupplyBucket) -> u64 {
+        let i = bucket as usize;
+        self.reserved[i].saturating_sub(self.issued[i])
+    }
+
+    /// Reserves add up to the total supply and no bucket is overdrawn
+    pub fn check(&self, total_supply: u64) -> Result<()> {
+        let reserved = self
+            .reserved
+            .iter()
+            .try_fold(0u64, |sum, r| sum.checked_add(*r));
+        require!(
+            reserved == Some(total_supply),
+            DiamondPadError::SupplyInvariantViolated
+        );
+        require!(
+            self.issued.iter().zip(&self.reserved).all(|(issued, reserved)| issued <= reserved),
+            DiamondPadError::SupplyBucketOverdrawn
+        );
+        Ok(())
+    }
+
+    /// Return `amount` issued from `bucket` that was never minted
+    pub fn release(&mut self, bucket: SupplyBucket, amount: u64) {
+        let i = bucket as usize;
+        self.issued[i] = self.issued[i].saturating_sub(amount);
+    }
+
+    /// Issue `amount` from `bucket`, failing rather than overdrawing it
+    pub fn issue(&mut self, bucket: SupplyBucket, amount: u64, total_supply: u64) -> Result<()> {
+        self.check(total_supply)?;
+        require!(amount <= self.remaining(bucket), DiamondPadError::SupplyBucketOverdrawn);
+        self.issued[bucket as usize] += amount;
+        Ok(())
+    }
+}
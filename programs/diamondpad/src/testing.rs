@@ -0,0 +1,386 @@
+//! `ProgramTest` fixtures and scenario builders (`testing` feature).
+//!
+//! `TestEnv` boots the program in a `ProgramTest` bank with the protocol
+//! already initialized, and wraps the instructions tests reach for most.
+//! `Scenario` builds on it: one launch, some buys along its curve, and
+//! positions aged into whichever diamond ranks a test needs.
+//!
+//! Ranks come from hold time, so `age_position` rewrites a real position's
+//! entry time (and the rank and multiplier that follow from it) rather than
+//! waiting. Reward weight catches up on the position's next settle.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::{system_instruction, system_program};
+use anchor_lang::{AccountDeserialize, AccountSerialize, InstructionData, ToAccountMetas};
+use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::Account as SolanaAccount;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+use crate::curve::{ConstantProductCurve, CurveType};
+pub use crate::pda::{
+    contribution_address, launch_address, position_address, protocol_address,
+    protocol_stats_address, reward_vault_address, sol_vault_address,
+};
+use crate::{
+    calculate_diamond_rank, get_diamond_multiplier_bps, DiamondRank, Launch, LaunchConfig,
+    Position, Protocol, RiskPolicy, SellPolicy, MIN_VIRTUAL_SOL_RESERVES,
+};
+
+/// Supply every fixture launch is created with
+pub const TEST_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
+/// Lamports a fresh test wallet starts with
+pub const TEST_WALLET_LAMPORTS: u64 = 100_000_000_000;
+
+const RANKS: [DiamondRank; 6] = [
+    DiamondRank::Paper,
+    DiamondRank::Bronze,
+    DiamondRank::Silver,
+    DiamondRank::Gold,
+    DiamondRank::Platinum,
+    DiamondRank::Diamond,
+];
+
+/// Anchor's entrypoint wants accounts that outlive the call; tests can
+/// afford to leak them
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    crate::entry(program_id, accounts, data)
+}
+
+/// A bank with the program loaded under its declared id
+pub fn program_test() -> ProgramTest {
+    ProgramTest::new(
+        "diamondpad",
+        crate::ID,
+        solana_program_test::processor!(process_instruction),
+    )
+}
+
+/// The smallest config that passes launch validation: constant-product
+/// curve, minimum vesting and LP lock, no sale deadline, no extensions
+pub fn default_launch_config() -> LaunchConfig {
+    LaunchConfig {
+        dev_allocation_bps: 0,
+        dev_vesting_days: 180,
+        lp_lock_days: 365,
+        holder_rewards_bps: 500,
+        min_contribution: 0,
+        max_position_bps: 10000,
+        sell_grace_bps: 0,
+        sell_policy: SellPolicy::ProportionalAging,
+        curve: CurveType::ConstantProduct(ConstantProductCurve),
+        virtual_sol_reserves: MIN_VIRTUAL_SOL_RESERVES * 30,
+        virtual_token_reserves_bps: 10000,
+        reward_fee_share_bps: 5000,
+        gatekeeper_network: None,
+        circuit_breaker_bps: 0,
+        circuit_breaker_window_minutes: 0,
+        circuit_breaker_cooldown_minutes: 0,
+        wash_score_reward_cutoff: 0,
+        risk_policy: RiskPolicy::Ignore,
+        max_risk_score: 0,
+        lp_boost_coverage_bps: 0,
+        lp_boost_bps: 0,
+        soft_cap: 0,
+        sale_duration_days: 0,
+        reward_epoch_budget: 0,
+        transfer_fee_bps: 0,
+        max_transfer_fee: 0,
+        interest_rate_bps: 0,
+        graduation_airdrop_bps: 0,
+        graduation_airdrop_recipients: 0,
+        min_hold_before_claim_days: 0,
+        priority_access_hours: 0,
+        freeze_until_graduation: false,
+        min_wallet_age_slots: 0,
+        verified_min_balance: 0,
+        verified_min_hold_days: 0,
+        governance_quorum_bps: 0,
+        graduation_reward_bps: 0,
+        graduation_grace_hours: 0,
+        graduation_grace_bps: 0,
+    }
+}
+
+/// Fewest days held that reach `rank` under the default rank schedule
+pub fn days_for_rank(rank: DiamondRank) -> i64 {
+    DiamondRank::DEFAULT_THRESHOLD_DAYS[rank as usize] as i64
+}
+
+/// A running bank with an initialized protocol; the bank's payer is the
+/// protocol authority and treasury
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+}
+
+impl TestEnv {
+    pub async fn new() -> Self {
+        Self::from_program_test(program_test()).await
+    }
+
+    /// Start from a caller-prepared `ProgramTest` (extra programs or accounts)
+    pub async fn from_program_test(program_test: ProgramTest) -> Self {
+        let mut env = Self { context: program_test.start_with_context().await };
+        let authority = env.authority();
+        let ix = Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::Initialize {
+                authority,
+                protocol: protocol_address(),
+                protocol_stats: protocol_stats_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Initialize { launch_token_mint: Pubkey::new_unique() }
+                .data(),
+        };
+        env.process(&[ix], &[]).await.expect("initialize protocol");
+        env
+    }
+
+    pub fn authority(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    /// Sign with the payer plus `signers` and process `instructions`
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<(), BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await?;
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context.banks_client.process_transaction(tx).await
+    }
+
+    /// A new wallet funded with TEST_WALLET_LAMPORTS
+    pub async fn funded_wallet(&mut self) -> Keypair {
+        let wallet = Keypair::new();
+        let ix = system_instruction::transfer(
+            &self.authority(),
+            &wallet.pubkey(),
+            TEST_WALLET_LAMPORTS,
+        );
+        self.process(&[ix], &[]).await.expect("fund wallet");
+        wallet
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, address: Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .expect("fetch account")
+            .expect("account exists");
+        T::try_deserialize(&mut account.data.as_slice()).expect("deserialize account")
+    }
+
+    /// Overwrite a program account's data in place, keeping its lamports
+    pub async fn write_account<T: AccountSerialize>(&mut self, address: Pubkey, value: &T) {
+        let mut account: SolanaAccount = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .expect("fetch account")
+            .expect("account exists");
+        let mut data = Vec::with_capacity(account.data.len());
+        value.try_serialize(&mut data).expect("serialize account");
+        account.data[..data.len()].copy_from_slice(&data);
+        self.context.set_account(&address, &account.into());
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .expect("read clock")
+            .unix_timestamp
+    }
+
+    /// Move the bank's clock forward without producing slots
+    pub async fn warp_forward(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.expect("read clock");
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// Create a launch with no mint, no creator stake and no review
+    pub async fn create_launch(&mut self, creator: &Keypair, config: LaunchConfig) -> Pubkey {
+        // `create_launch` takes the next protocol-numbered id; seeded and
+        // creator-nonce launches aren't at `launch_address`
+        let protocol: Protocol = self.account(protocol_address()).await;
+        let launch = launch_address(protocol.total_launches);
+        let ix = Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::CreateLaunch {
+                creator: creator.pubkey(),
+                protocol: protocol_address(),
+                launch,
+                sol_vault: sol_vault_address(&launch),
+                reward_vault: reward_vault_address(&launch),
+                creator_stake: None,
+                review_queue: None,
+                channel: None,
+                launch_mint: None,
+                launch_authority: None,
+                token_program: None,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::CreateLaunch {
+                name: "Test Launch".to_string(),
+                symbol: "TEST".to_string(),
+                uri: String::new(),
+                total_supply: TEST_TOTAL_SUPPLY,
+                config,
+            }
+            .data(),
+        };
+        self.process(&[ix], &[creator]).await.expect("create launch");
+        launch
+    }
+
+    /// Buy `quote_amount` lamports of `launch` on its curve, with no slippage limit
+    pub async fn buy(
+        &mut self,
+        launch: Pubkey,
+        buyer: &Keypair,
+        quote_amount: u64,
+    ) -> std::result::Result<(), BanksClientError> {
+        let protocol: Protocol = self.account(protocol_address()).await;
+        let ix = Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::Buy {
+                buyer: buyer.pubkey(),
+                protocol: protocol_address(),
+                protocol_stats: protocol_stats_address(),
+                launch,
+                position: position_address(&launch, &buyer.pubkey()),
+                contribution: contribution_address(&launch, &buyer.pubkey()),
+                sol_vault: sol_vault_address(&launch),
+                reward_vault: reward_vault_address(&launch),
+                treasury: protocol.treasury,
+                gateway_token: None,
+                wallet_risk: None,
+                referral_code: None,
+                referral_stats: None,
+                referral_board: None,
+                season: None,
+                season_score: None,
+                priority_launch: None,
+                priority_position: None,
+                holder_profile: None,
+                channel: None,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Buy { quote_amount, min_tokens_out: 0 }.data(),
+        };
+        self.process(&[ix], &[buyer]).await
+    }
+
+    /// Backdate a position's entry so it sits at exactly `rank` now
+    pub async fn age_position(&mut self, launch: Pubkey, holder: Pubkey, rank: DiamondRank) {
+        let now = self.now().await;
+        let address = position_address(&launch, &holder);
+        let threshold_days = self.account::<Launch>(launch).await.rank_thresholds();
+        let mut position: Position = self.account(address).await;
+        let entry = now - threshold_days[rank as usize] as i64 * 86400;
+        position.first_buy_timestamp = entry as u32;
+        position.clear_linked_entry();
+        position.diamond_rank = calculate_diamond_rank(entry, now, &threshold_days);
+        position.multiplier_bps = get_diamond_multiplier_bps(position.diamond_rank);
+        self.write_account(address, &position).await;
+    }
+}
+
+/// A holder the scenario bought in and aged to a rank
+pub struct ScenarioHolder {
+    pub wallet: Keypair,
+    pub position: Pubkey,
+    pub rank: DiamondRank,
+}
+
+/// What `Scenario::build` leaves behind
+pub struct ScenarioState {
+    pub env: TestEnv,
+    pub creator: Keypair,
+    pub launch: Pubkey,
+    pub holders: Vec<ScenarioHolder>,
+}
+
+/// Declarative setup: a launch, buys on its curve, and holders at ranks
+///
+/// ```ignore
+/// let state = Scenario::new().holders_at_each_rank(1_000_000_000).build().await;
+/// ```
+pub struct Scenario {
+    config: LaunchConfig,
+    holders: Vec<(DiamondRank, u64)>,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        Self { config: default_launch_config(), holders: Vec::new() }
+    }
+
+    pub fn launch_config(mut self, config: LaunchConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// A holder who buys `quote_amount` lamports and is aged to `rank`
+    pub fn holder(mut self, rank: DiamondRank, quote_amount: u64) -> Self {
+        self.holders.push((rank, quote_amount));
+        self
+    }
+
+    /// One holder at every rank, Paper to Diamond, each buying `quote_amount`
+    pub fn holders_at_each_rank(mut self, quote_amount: u64) -> Self {
+        self.holders.extend(RANKS.iter().map(|&rank| (rank, quote_amount)));
+        self
+    }
+
+    pub async fn build(self) -> ScenarioState {
+        let mut env = TestEnv::new().await;
+        let creator = env.funded_wallet().await;
+        let launch = env.create_launch(&creator, self.config).await;
+
+        let mut holders = Vec::with_capacity(self.holders.len());
+        for (rank, quote_amount) in self.holders {
+            let wallet = env.funded_wallet().await;
+            env.buy(launch, &wallet, quote_amount).await.expect("scenario buy");
+            env.age_position(launch, wallet.pubkey(), rank).await;
+            holders.push(ScenarioHolder {
+                position: position_address(&launch, &wallet.pubkey()),
+                wallet,
+                rank,
+            });
+        }
+
+        ScenarioState { env, creator, launch, holders }
+    }
+}
@@ -0,0 +1,101 @@
+//! Time-weighted average price tracking.
+//!
+//! Each launch keeps a small ring buffer of post-trade spot prices. An
+//! observation's price is treated as holding from its timestamp until the
+//! next one, so a single-block spike only counts for the seconds it lasted.
+
+use anchor_lang::prelude::*;
+
+/// Observations kept per launch
+pub const TWAP_OBSERVATIONS: usize = 16;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub struct PriceObservation {
+    pub timestamp: i64,
+    /// Spot price in lamports per `PRICE_PRECISION` tokens
+    pub price: u64,
+}
+
+/// Ring buffer of recent price observations
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PriceHistory {
+    pub observations: [PriceObservation; TWAP_OBSERVATIONS],
+    /// Slot the most recent observation was written to
+    pub head: u8,
+    pub len: u8,
+}
+
+impl PriceHistory {
+    /// Record the price after a trade; trades in the same second overwrite
+    /// each other so the last price of the second wins
+    pub fn record(&mut self, timestamp: i64, price: u64) {
+        if self.len > 0 && self.observations[self.head as usize].timestamp == timestamp {
+            self.observations[self.head as usize].price = price;
+            return;
+        }
+
+        if self.len > 0 {
+            self.head = ((self.head as usize + 1) % TWAP_OBSERVATIONS) as u8;
+        }
+        self.observations[self.head as usize] = PriceObservation { timestamp, price };
+        self.len = (self.len + 1).min(TWAP_OBSERVATIONS as u8);
+    }
+
+    pub fn latest(&self) -> Option<PriceObservation> {
+        (self.len > 0).then(|| self.observations[self.head as usize])
+    }
+
+    /// Largest peak-to-trough fall, in bps of the peak, across observations
+    /// from `since` onwards (0 if the buffer has nothing that recent)
+    pub fn max_drawdown_bps_since(&self, since: i64) -> u16 {
+        let mut peak: u64 = 0;
+        let mut worst: u128 = 0;
+        for age in (0..self.len as usize).rev() {
+            let slot = (self.head as usize + TWAP_OBSERVATIONS - age) % TWAP_OBSERVATIONS;
+            let observation = self.observations[slot];
+            if observation.timestamp < since {
+                continue;
+            }
+            peak = peak.max(observation.price);
+            if peak > 0 {
+                let drawdown = (peak - observation.price) as u128 * 10000 / peak as u128;
+                worst = worst.max(drawdown);
+            }
+        }
+        worst as u16
+    }
+
+    /// Time-weighted average price over the `window` seconds ending at `now`.
+    /// If the buffer doesn't reach back that far, averages over what it has.
+    pub fn twap(&self, now: i64, window: i64) -> Option<u64> {
+        if self.len == 0 || window <= 0 {
+            return None;
+        }
+
+        let start = now - window;
+        let mut end = now;
+        let mut weighted: u128 = 0;
+        let mut covered: i64 = 0;
+
+        // Walk newest to oldest; each price held from its timestamp until `end`
+        for age in 0..self.len as usize {
+            let slot = (self.head as usize + TWAP_OBSERVATIONS - age) % TWAP_OBSERVATIONS;
+            let observation = self.observations[slot];
+            let from = observation.timestamp.max(start);
+            if end > from {
+                let held = end - from;
+                weighted = weighted.checked_add((observation.price as u128).checked_mul(held as u128)?)?;
+                covered += held;
+            }
+            if observation.timestamp <= start {
+                break;
+            }
+            end = observation.timestamp.min(end);
+        }
+
+        if covered == 0 {
+            return self.latest().map(|observation| observation.price);
+        }
+        u64::try_from(weighted / covered as u128).ok()
+    }
+}
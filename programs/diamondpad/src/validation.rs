@@ -0,0 +1,238 @@
+//! Launch parameter validation.
+//!
+//! Every path that creates a launch or publishes a template runs the same
+//! checks: per-field protocol bounds first, then cross-checks on how the
+//! reserved supply shares combine.
+
+use anchor_lang::prelude::*;
+
+use crate::curve::{CurveType, MAX_EXPONENTIAL_GROWTH_BPS};
+use crate::{
+    DiamondPadError, LaunchConfig, LIQUIDITY_POOL_BPS, MAX_AIRDROP_RECIPIENTS,
+    MAX_CIRCUIT_BREAKER_COOLDOWN_MINUTES, MAX_GRADUATION_AIRDROP_BPS, MAX_GRADUATION_GRACE_BPS,
+    MAX_GRADUATION_GRACE_HOURS, MAX_GRADUATION_REWARD_BPS, MAX_HOLDER_REWARDS_BPS,
+    MAX_INTEREST_RATE_BPS, MAX_LP_BOOST_BPS, MAX_MIN_HOLD_BEFORE_CLAIM_DAYS,
+    MAX_MIN_WALLET_AGE_SLOTS, MAX_NAME_LEN, MAX_PRIORITY_ACCESS_HOURS, MAX_RISK_SCORE,
+    MAX_SALE_DURATION_DAYS, MAX_SELL_GRACE_BPS, MAX_SYMBOL_LEN, MAX_TRANSFER_FEE_BPS,
+    MAX_USERNAME_LEN, MAX_VERIFIED_MIN_HOLD_DAYS, MAX_VIRTUAL_SOL_RESERVES,
+    MAX_VIRTUAL_TOKEN_RESERVES_BPS, MIN_SALE_SUPPLY_BPS, MIN_VIRTUAL_SOL_RESERVES,
+    MIN_VIRTUAL_TOKEN_RESERVES_BPS,
+};
+
+/// Validate everything a creator supplies to create a launch
+pub fn validate_launch_params(
+    name: &str,
+    symbol: &str,
+    total_supply: u64,
+    config: &LaunchConfig,
+) -> Result<()> {
+    require!(name.len() <= MAX_NAME_LEN, DiamondPadError::NameTooLong);
+    require!(symbol.len() <= MAX_SYMBOL_LEN, DiamondPadError::SymbolTooLong);
+    require!(total_supply > 0, DiamondPadError::InvalidTotalSupply);
+    validate_launch_config(config)
+}
+
+/// Usernames are stored in canonical lowercase form so one PDA seed covers
+/// every casing of a name
+pub fn validate_username(name: &str) -> Result<()> {
+    require!(
+        !name.is_empty()
+            && name.len() <= MAX_USERNAME_LEN
+            && name
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_'),
+        DiamondPadError::InvalidUsername
+    );
+    Ok(())
+}
+
+/// A rank schedule starts everyone at Paper and gets strictly longer per
+/// rank, so ranks stay ordered by time held
+pub fn validate_rank_thresholds(threshold_days: &[u16; 6]) -> Result<()> {
+    require!(
+        threshold_days[0] == 0 && threshold_days.windows(2).all(|pair| pair[0] < pair[1]),
+        DiamondPadError::InvalidRankThresholds
+    );
+    Ok(())
+}
+
+/// Validate a launch configuration on its own (also used for templates)
+pub fn validate_launch_config(config: &LaunchConfig) -> Result<()> {
+    validate_field_bounds(config)?;
+    validate_curve(&config.curve)?;
+    validate_virtual_reserves(config)?;
+    validate_circuit_breaker(config)?;
+    validate_sale_window(config)?;
+    validate_mint_extensions(config)?;
+    validate_graduation_airdrop(config)?;
+    validate_graduation_grace(config)?;
+    validate_supply_shares(config)
+}
+
+fn validate_field_bounds(config: &LaunchConfig) -> Result<()> {
+    require!(config.dev_allocation_bps <= 1000, DiamondPadError::DevAllocationTooHigh);
+    require!(config.dev_vesting_days >= 180, DiamondPadError::VestingTooShort);
+    require!(config.lp_lock_days >= 365, DiamondPadError::LpLockTooShort);
+    require!(
+        config.holder_rewards_bps <= MAX_HOLDER_REWARDS_BPS,
+        DiamondPadError::HolderRewardsTooHigh
+    );
+    require!(config.max_position_bps <= 10000, DiamondPadError::InvalidMaxPosition);
+    require!(config.sell_grace_bps <= MAX_SELL_GRACE_BPS, DiamondPadError::SellGraceTooHigh);
+    require!(config.reward_fee_share_bps <= 10000, DiamondPadError::InvalidFeeShare);
+    require!(config.wash_score_reward_cutoff <= 10000, DiamondPadError::InvalidWashCutoff);
+    require!(config.max_risk_score <= MAX_RISK_SCORE, DiamondPadError::InvalidRiskScore);
+    require!(config.lp_boost_bps <= MAX_LP_BOOST_BPS, DiamondPadError::LpBoostTooHigh);
+    require!(
+        config.min_hold_before_claim_days <= MAX_MIN_HOLD_BEFORE_CLAIM_DAYS,
+        DiamondPadError::InvalidMinHoldBeforeClaim
+    );
+    require!(
+        config.priority_access_hours <= MAX_PRIORITY_ACCESS_HOURS,
+        DiamondPadError::InvalidPriorityAccess
+    );
+    require!(
+        config.min_wallet_age_slots <= MAX_MIN_WALLET_AGE_SLOTS,
+        DiamondPadError::InvalidWalletAge
+    );
+    require!(
+        config.verified_min_hold_days <= MAX_VERIFIED_MIN_HOLD_DAYS,
+        DiamondPadError::InvalidVerifiedHolderFilter
+    );
+    require!(
+        config.governance_quorum_bps <= 10000,
+        DiamondPadError::InvalidGovernanceQuorum
+    );
+    require!(
+        config.graduation_reward_bps <= MAX_GRADUATION_REWARD_BPS,
+        DiamondPadError::InvalidGraduationReward
+    );
+    Ok(())
+}
+
+/// Extra grace needs a window to apply in, and both stay short of a free exit
+fn validate_graduation_grace(config: &LaunchConfig) -> Result<()> {
+    require!(
+        config.graduation_grace_hours <= MAX_GRADUATION_GRACE_HOURS
+            && config.graduation_grace_bps <= MAX_GRADUATION_GRACE_BPS,
+        DiamondPadError::InvalidGraduationGrace
+    );
+    if config.graduation_grace_bps > 0 {
+        require!(config.graduation_grace_hours > 0, DiamondPadError::InvalidGraduationGrace);
+    }
+    Ok(())
+}
+
+fn validate_curve(curve: &CurveType) -> Result<()> {
+    match curve {
+        CurveType::Linear(linear) => {
+            require!(linear.base_price > 0, DiamondPadError::InvalidCurveParams);
+        }
+        CurveType::Exponential(exponential) => {
+            require!(exponential.base_price > 0, DiamondPadError::InvalidCurveParams);
+            require!(
+                exponential.growth_bps > 0 && exponential.growth_bps <= MAX_EXPONENTIAL_GROWTH_BPS,
+                DiamondPadError::InvalidCurveParams
+            );
+            require!(exponential.step_size > 0, DiamondPadError::InvalidCurveParams);
+        }
+        CurveType::ConstantProduct(_) => {}
+    }
+    Ok(())
+}
+
+/// An enabled breaker needs a lookback window and a bounded pause
+fn validate_circuit_breaker(config: &LaunchConfig) -> Result<()> {
+    if config.circuit_breaker_bps == 0 {
+        return Ok(());
+    }
+    require!(
+        config.circuit_breaker_window_minutes > 0,
+        DiamondPadError::InvalidCircuitBreaker
+    );
+    require!(
+        config.circuit_breaker_cooldown_minutes > 0
+            && config.circuit_breaker_cooldown_minutes <= MAX_CIRCUIT_BREAKER_COOLDOWN_MINUTES,
+        DiamondPadError::InvalidCircuitBreaker
+    );
+    Ok(())
+}
+
+/// A soft cap only means something with a deadline to reach it by
+fn validate_sale_window(config: &LaunchConfig) -> Result<()> {
+    require!(
+        config.sale_duration_days <= MAX_SALE_DURATION_DAYS,
+        DiamondPadError::InvalidSaleWindow
+    );
+    if config.soft_cap > 0 {
+        require!(config.sale_duration_days > 0, DiamondPadError::InvalidSaleWindow);
+    }
+    Ok(())
+}
+
+/// A percentage fee with no ceiling would tax large transfers without
+/// limit; interest is display-only accrual but still capped
+fn validate_mint_extensions(config: &LaunchConfig) -> Result<()> {
+    require!(
+        config.transfer_fee_bps <= MAX_TRANSFER_FEE_BPS,
+        DiamondPadError::InvalidTransferFee
+    );
+    if config.transfer_fee_bps > 0 {
+        require!(config.max_transfer_fee > 0, DiamondPadError::InvalidTransferFee);
+    }
+    require!(
+        (0..=MAX_INTEREST_RATE_BPS).contains(&config.interest_rate_bps),
+        DiamondPadError::InvalidInterestRate
+    );
+    Ok(())
+}
+
+/// An airdrop reserve needs someone to go to
+fn validate_graduation_airdrop(config: &LaunchConfig) -> Result<()> {
+    require!(
+        config.graduation_airdrop_bps <= MAX_GRADUATION_AIRDROP_BPS,
+        DiamondPadError::InvalidGraduationAirdrop
+    );
+    if config.graduation_airdrop_bps > 0 {
+        require!(
+            config.graduation_airdrop_recipients > 0
+                && config.graduation_airdrop_recipients <= MAX_AIRDROP_RECIPIENTS,
+            DiamondPadError::InvalidGraduationAirdrop
+        );
+    }
+    Ok(())
+}
+
+/// Too little virtual SOL makes the opening price trivially pumpable; too
+/// much makes it unreachable. Virtual tokens below the sale supply would
+/// let the curve run dry, and far above it flattens the curve so the sale
+/// sells out at a near-zero price.
+fn validate_virtual_reserves(config: &LaunchConfig) -> Result<()> {
+    require!(
+        config.virtual_sol_reserves >= MIN_VIRTUAL_SOL_RESERVES
+            && config.virtual_sol_reserves <= MAX_VIRTUAL_SOL_RESERVES,
+        DiamondPadError::InvalidVirtualReserves
+    );
+    require!(
+        config.virtual_token_reserves_bps >= MIN_VIRTUAL_TOKEN_RESERVES_BPS
+            && config.virtual_token_reserves_bps <= MAX_VIRTUAL_TOKEN_RESERVES_BPS,
+        DiamondPadError::InvalidVirtualReserves
+    );
+    Ok(())
+}
+
+/// Dev allocation, LP share, holder rewards and the graduation airdrop are
+/// carved out of the same supply as the sale, so together they must leave
+/// a meaningful sale.
+fn validate_supply_shares(config: &LaunchConfig) -> Result<()> {
+    let reserved_bps = config.dev_allocation_bps as u32
+        + LIQUIDITY_POOL_BPS as u32
+        + config.holder_rewards_bps as u32
+        + config.graduation_airdrop_bps as u32;
+    require!(reserved_bps <= 10000, DiamondPadError::AllocationExceedsSupply);
+    require!(
+        10000 - reserved_bps >= MIN_SALE_SUPPLY_BPS as u32,
+        DiamondPadError::SaleSupplyTooSmall
+    );
+    Ok(())
+}